@@ -572,6 +572,23 @@ impl CatalogState {
         self.entry_by_id.get_mut(id).expect("catalog out of sync")
     }
 
+    /// Returns the compaction window of an index on `on` with the given
+    /// options: the index's own logical compaction window, if it sets one, or
+    /// else the window it inherits from `on`.
+    pub fn index_compaction_window(
+        &self,
+        on: &GlobalId,
+        options: &[plan::IndexOption],
+    ) -> Option<Duration> {
+        let own_window = options.iter().find_map(|option| match option {
+            plan::IndexOption::LogicalCompactionWindow(window) => Some(*window),
+        });
+        match own_window {
+            Some(window) => window,
+            None => self.get_entry(on).item().index_logical_compaction_window(),
+        }
+    }
+
     pub fn try_get_entry_in_schema(
         &self,
         name: &QualifiedItemName,
@@ -732,6 +749,7 @@ impl CatalogState {
                     desc,
                     conn_id: None,
                     resolved_ids,
+                    custom_logical_compaction_window: view.retain_history,
                 })
             }
             _ => bail!("Expected valid CREATE VIEW statement"),
@@ -2286,6 +2304,7 @@ pub struct View {
     pub desc: RelationDesc,
     pub conn_id: Option<ConnectionId>,
     pub resolved_ids: ResolvedIds,
+    pub custom_logical_compaction_window: Option<Duration>,
 }
 
 #[derive(Debug, Clone, Serialize)]
@@ -2598,9 +2617,30 @@ impl CatalogItem {
             CatalogItem::Table(table) => table.custom_logical_compaction_window,
             CatalogItem::Source(source) => source.custom_logical_compaction_window,
             CatalogItem::Index(index) => index.custom_logical_compaction_window,
+            CatalogItem::View(view) => view.custom_logical_compaction_window,
             CatalogItem::MaterializedView(_)
             | CatalogItem::Log(_)
-            | CatalogItem::View(_)
+            | CatalogItem::Sink(_)
+            | CatalogItem::Type(_)
+            | CatalogItem::Func(_)
+            | CatalogItem::Secret(_)
+            | CatalogItem::Connection(_) => None,
+        }
+    }
+
+    /// The compaction window that indexes on this item inherit, if any.
+    ///
+    /// This is the window set with `RETAIN HISTORY` on a view. Views have no
+    /// collection of their own to compact, so the window instead applies to
+    /// the indexes built on them.
+    pub fn index_logical_compaction_window(&self) -> Option<Duration> {
+        match self {
+            CatalogItem::View(view) => view.custom_logical_compaction_window,
+            CatalogItem::Table(_)
+            | CatalogItem::Source(_)
+            | CatalogItem::Index(_)
+            | CatalogItem::MaterializedView(_)
+            | CatalogItem::Log(_)
             | CatalogItem::Sink(_)
             | CatalogItem::Type(_)
             | CatalogItem::Func(_)
@@ -3218,6 +3258,34 @@ impl Catalog {
             storage: Arc::new(tokio::sync::Mutex::new(config.storage)),
         };
 
+        // Refuse to open a catalog that was last written by a newer version, as
+        // this version may not understand its contents. Pre-release and build
+        // metadata are ignored so that development builds can open catalogs
+        // written by the release they are based on.
+        let catalog_version = catalog
+            .storage()
+            .await
+            .get_catalog_content_version()
+            .await?;
+        if let Some(catalog_version) = catalog_version {
+            let this_version = catalog.config().build_info.version;
+            match (
+                semver::Version::parse(&catalog_version),
+                semver::Version::parse(this_version),
+            ) {
+                (Ok(v), Ok(this_v))
+                    if (v.major, v.minor, v.patch) > (this_v.major, this_v.minor, this_v.patch) =>
+                {
+                    return Err(Error::new(ErrorKind::CatalogVersionTooNew {
+                        catalog_version,
+                        this_version,
+                    })
+                    .into());
+                }
+                _ => (),
+            }
+        }
+
         catalog.create_temporary_schema(&SYSTEM_CONN_ID, MZ_SYSTEM_ROLE_ID)?;
 
         let databases = catalog.storage().await.load_databases().await?;
@@ -4614,6 +4682,7 @@ impl Catalog {
                 builtin_cluster_replica_size: "1".into(),
                 default_availability_zone: DUMMY_AVAILABILITY_ZONE.into(),
                 bootstrap_role: None,
+                bootstrap_sql: false,
             },
             None,
         )
@@ -7390,6 +7459,7 @@ impl Catalog {
                     desc,
                     conn_id: None,
                     resolved_ids,
+                    custom_logical_compaction_window: view.retain_history,
                 })
             }
             Plan::CreateMaterializedView(CreateMaterializedViewPlan {
@@ -7407,16 +7477,19 @@ impl Catalog {
                     cluster_id: materialized_view.cluster_id,
                 })
             }
-            Plan::CreateIndex(CreateIndexPlan { index, .. }) => CatalogItem::Index(Index {
-                create_sql: index.create_sql,
-                on: index.on,
-                keys: index.keys,
-                conn_id: None,
-                resolved_ids,
-                cluster_id: index.cluster_id,
-                custom_logical_compaction_window,
-                is_retained_metrics_object,
-            }),
+            Plan::CreateIndex(CreateIndexPlan { index, options, .. }) => {
+                CatalogItem::Index(Index {
+                    create_sql: index.create_sql,
+                    on: index.on,
+                    keys: index.keys,
+                    conn_id: None,
+                    resolved_ids,
+                    cluster_id: index.cluster_id,
+                    custom_logical_compaction_window: custom_logical_compaction_window
+                        .or_else(|| self.state.index_compaction_window(&index.on, &options)),
+                    is_retained_metrics_object,
+                })
+            }
             Plan::CreateSink(CreateSinkPlan {
                 sink,
                 with_snapshot,
@@ -8754,8 +8827,10 @@ impl mz_sql::catalog::CatalogItem for CatalogEntry {
 mod tests {
     use std::collections::{BTreeMap, BTreeSet};
     use std::iter;
+    use std::time::Duration;
 
     use itertools::Itertools;
+    use mz_build_info::DUMMY_BUILD_INFO;
     use mz_controller::clusters::ClusterId;
     use mz_expr::{MirRelationExpr, OptimizedMirRelationExpr};
     use mz_ore::collections::CollectionExt;
@@ -8776,7 +8851,8 @@ mod tests {
 
     use crate::catalog::storage::MZ_SYSTEM_ROLE_ID;
     use crate::catalog::{
-        Catalog, CatalogItem, Index, MaterializedView, Op, Table, SYSTEM_CONN_ID,
+        Catalog, CatalogItem, Index, MaterializedView, Op, SerializedCatalogItem, Table,
+        SYSTEM_CONN_ID,
     };
     use crate::session::{Session, DEFAULT_DATABASE_NAME};
 
@@ -8895,6 +8971,39 @@ mod tests {
         }
     }
 
+    #[mz_ore::test(tokio::test)]
+    #[cfg_attr(miri, ignore)] // unsupported operation: can't call foreign function `TLS_client_method` on OS `linux`
+    async fn test_catalog_version_too_new() {
+        let debug_stash_factory = DebugStashFactory::new().await;
+        {
+            let stash = debug_stash_factory.open_debug().await;
+            let catalog = Catalog::open_debug_stash(stash, NOW_ZERO.clone())
+                .await
+                .expect("unable to open debug catalog");
+            // Simulate the catalog having been written by a future version.
+            catalog
+                .storage()
+                .await
+                .set_catalog_content_version("999.0.0")
+                .await
+                .expect("failed to set catalog version");
+        }
+        {
+            let stash = debug_stash_factory.open_debug().await;
+            let err = Catalog::open_debug_stash(stash, NOW_ZERO.clone())
+                .await
+                .expect_err("opening a catalog from a future version should fail");
+            assert_eq!(
+                err.to_string(),
+                format!(
+                    "catalog was last written by version 999.0.0, which is newer than this \
+                     version ({}); downgrading is not supported",
+                    DUMMY_BUILD_INFO.version,
+                ),
+            );
+        }
+    }
+
     #[mz_ore::test(tokio::test)]
     #[cfg_attr(miri, ignore)] // unsupported operation: can't call foreign function `TLS_client_method` on OS `linux`
     async fn test_effective_search_path() {
@@ -9785,6 +9894,71 @@ mod tests {
         }
     }
 
+    // Test that an index on a view with `RETAIN HISTORY` keeps its own logical
+    // compaction window, if it sets one, when it is loaded from the catalog.
+    #[mz_ore::test(tokio::test)]
+    #[cfg_attr(miri, ignore)] //  unsupported operation: can't call foreign function `TLS_client_method` on OS `linux`
+    async fn test_index_compaction_window() {
+        Catalog::with_debug(NOW_ZERO.clone(), |mut catalog| async move {
+            let item = catalog
+                .state()
+                .parse_view_item(
+                    r#"CREATE VIEW "materialize"."public"."v" WITH (RETAIN HISTORY = '1s') AS SELECT 1 AS "a""#
+                        .into(),
+                )
+                .expect("unable to parse view");
+            let view_id = catalog
+                .allocate_user_id()
+                .await
+                .expect("cannot fail to allocate user ids");
+            let oid = catalog
+                .allocate_oid()
+                .expect("cannot fail to allocate oids");
+            catalog
+                .transact(
+                    mz_repr::Timestamp::MIN,
+                    None,
+                    vec![Op::CreateItem {
+                        id: view_id,
+                        oid,
+                        name: QualifiedItemName {
+                            qualifiers: ItemQualifiers {
+                                database_spec: ResolvedDatabaseSpecifier::Id(DatabaseId::User(1)),
+                                schema_spec: SchemaSpecifier::Id(SchemaId::User(3)),
+                            },
+                            item: "v".to_string(),
+                        },
+                        item,
+                        owner_id: MZ_SYSTEM_ROLE_ID,
+                    }],
+                    |_catalog| Ok(()),
+                )
+                .await
+                .expect("failed to transact");
+
+            let index_compaction_window = |with_options: &str| {
+                let create_sql = format!(
+                    r#"CREATE INDEX "i" IN CLUSTER "default" ON "materialize"."public"."v" ("a"){with_options}"#
+                );
+                let item = catalog
+                    .deserialize_item(GlobalId::User(100), SerializedCatalogItem::V1 { create_sql })
+                    .expect("unable to parse index");
+                match item {
+                    CatalogItem::Index(index) => index.custom_logical_compaction_window,
+                    item => panic!("expected index, got {}", item.typ()),
+                }
+            };
+            // The index inherits the view's window...
+            assert_eq!(index_compaction_window(""), Some(Duration::from_secs(1)));
+            // ...unless it sets its own.
+            assert_eq!(
+                index_compaction_window(" WITH (LOGICAL COMPACTION WINDOW = '5s')"),
+                Some(Duration::from_secs(5)),
+            );
+        })
+        .await;
+    }
+
     #[mz_ore::test]
     fn test_update_privilege_owners() {
         let old_owner = RoleId::User(1);
@@ -79,6 +79,11 @@ pub enum ErrorKind {
         this_version: &'static str,
         cause: String,
     },
+    #[error("catalog was last written by version {catalog_version}, which is newer than this version ({this_version}); downgrading is not supported")]
+    CatalogVersionTooNew {
+        catalog_version: String,
+        this_version: &'static str,
+    },
     #[error("failed to migrate schema of builtin objects: {0}")]
     FailedBuiltinSchemaMigration(String),
     #[error("failpoint {0} reached)")]
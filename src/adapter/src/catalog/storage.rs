@@ -177,6 +177,9 @@ pub struct BootstrapArgs {
     pub builtin_cluster_replica_size: String,
     pub default_availability_zone: String,
     pub bootstrap_role: Option<String>,
+    /// Whether a bootstrap SQL file will be applied to a freshly initialized
+    /// catalog. If so, the catalog records that the file is pending.
+    pub bootstrap_sql: bool,
 }
 
 /// A [`Connection`] represent an open connection to the stash. It exposes optimized methods for
@@ -19,6 +19,7 @@ use mz_sql::catalog::{RoleAttributes, SystemObjectType};
 use mz_sql::names::{
     DatabaseId, ObjectId, ResolvedDatabaseSpecifier, SchemaId, SchemaSpecifier, PUBLIC_ROLE_NAME,
 };
+use mz_sql::session::vars::{Var, BOOTSTRAP_SQL_NEXT_STATEMENT};
 use mz_stash::objects::{proto, RustType};
 use mz_stash::{StashError, Transaction, TypedCollection, STASH_VERSION, USER_VERSION_KEY};
 use mz_storage_client::types::sources::Timeline;
@@ -804,8 +805,22 @@ pub async fn initialize(
         .initialize(tx, vec![])
         .await?;
     ITEM_COLLECTION.initialize(tx, vec![]).await?;
+    // Record that the bootstrap SQL file, if any, has yet to be applied, so
+    // that it is retried until it succeeds.
+    let system_configurations = if options.bootstrap_sql {
+        vec![(
+            proto::ServerConfigurationKey {
+                name: BOOTSTRAP_SQL_NEXT_STATEMENT.name().to_string(),
+            },
+            proto::ServerConfigurationValue {
+                value: "0".to_string(),
+            },
+        )]
+    } else {
+        vec![]
+    };
     SYSTEM_CONFIGURATION_COLLECTION
-        .initialize(tx, vec![])
+        .initialize(tx, system_configurations)
         .await?;
     STORAGE_USAGE_COLLECTION.initialize(tx, vec![]).await?;
 
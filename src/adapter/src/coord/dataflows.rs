@@ -865,6 +865,9 @@ fn eval_unmaterializable_func(
             let t: Datum = state.config().start_time.try_into()?;
             pack(t)
         }
+        // `random` yields a new value for every row, so it is evaluated
+        // alongside the rest of the expression.
+        UnmaterializableFunc::Random => Ok(MirScalarExpr::CallUnmaterializable(f.clone())),
         UnmaterializableFunc::Version => {
             let build_info = state.config().build_info;
             let version = format!(
@@ -894,6 +894,7 @@ impl Coordinator {
                 None
             },
             resolved_ids,
+            custom_logical_compaction_window: view.retain_history,
         };
         ops.push(catalog::Op::CreateItem {
             id: view_id,
@@ -1078,6 +1079,23 @@ impl Coordinator {
             return Err(AdapterError::BadItemInStorageCluster { cluster_name });
         }
 
+        // Indexes on a view with `RETAIN HISTORY` inherit its compaction
+        // window, unless the index sets its own.
+        let inherited_compaction_window = self
+            .catalog()
+            .get_entry(&index.on)
+            .item()
+            .index_logical_compaction_window();
+        let custom_logical_compaction_window = self
+            .catalog()
+            .state()
+            .index_compaction_window(&index.on, &options);
+        let options: Vec<_> = inherited_compaction_window
+            .map(|window| IndexOption::LogicalCompactionWindow(Some(window)))
+            .into_iter()
+            .chain(options)
+            .collect();
+
         let id = self.catalog_mut().allocate_user_id().await?;
         let index = catalog::Index {
             create_sql: index.create_sql,
@@ -1087,7 +1105,7 @@ impl Coordinator {
             resolved_ids,
             cluster_id,
             is_retained_metrics_object: false,
-            custom_logical_compaction_window: None,
+            custom_logical_compaction_window,
         };
         let oid = self.catalog_mut().allocate_oid()?;
         let on = self.catalog().get_entry(&index.on);
@@ -32,6 +32,7 @@ use flate2::Compression;
 
 use crate::error::{DecodeError, Error as AvroError};
 use crate::types::{ToAvro, Value};
+use crate::util::max_allocation_bytes;
 
 /// The compression codec used to compress blocks.
 #[derive(Clone, Copy, Debug, PartialEq)]
@@ -112,14 +113,40 @@ impl Codec {
     }
 
     /// Decompress a stream of bytes in-place.
+    ///
+    /// The decompressed size is limited to the value configured by
+    /// [`max_allocation_bytes`].
     pub fn decompress(self, stream: &mut Vec<u8>) -> Result<(), AvroError> {
+        self.decompress_with_limit(stream, max_allocation_bytes(512 * 1024 * 1024))
+    }
+
+    /// Decompress a stream of bytes in-place, failing with
+    /// [`DecodeError::DecompressedBlockTooLarge`] if the decompressed data
+    /// would exceed `limit` bytes.
+    ///
+    /// Compressed blocks can expand to many times their size, so the limit
+    /// protects against small malicious blocks that decompress to enormous
+    /// amounts of data.
+    pub fn decompress_with_limit(
+        self,
+        stream: &mut Vec<u8>,
+        limit: usize,
+    ) -> Result<(), AvroError> {
         match self {
             Codec::Null => (),
             Codec::Deflate => {
                 let mut decoded = Vec::new();
                 {
-                    let mut decoder = DeflateDecoder::new(&**stream);
-                    decoder.read_to_end(&mut decoded)?;
+                    // Read at most one byte past the limit, which is enough
+                    // to detect that the limit was exceeded without
+                    // decompressing the remainder of the block.
+                    let decoder = DeflateDecoder::new(&**stream);
+                    decoder
+                        .take((limit as u64).saturating_add(1))
+                        .read_to_end(&mut decoded)?;
+                }
+                if decoded.len() > limit {
+                    return Err(DecodeError::DecompressedBlockTooLarge(limit).into());
                 }
                 *stream = decoded;
             }
@@ -129,6 +156,9 @@ impl Codec {
 
                 let decompressed_size = snap::raw::decompress_len(&stream[..stream.len() - 4])
                     .map_err(std::io::Error::from)?;
+                if decompressed_size > limit {
+                    return Err(DecodeError::DecompressedBlockTooLarge(limit).into());
+                }
                 let mut decoded = vec![0; decompressed_size];
                 snap::raw::Decoder::new()
                     .decompress(&stream[..stream.len() - 4], &mut decoded[..])
@@ -184,6 +214,35 @@ mod tests {
         assert_eq!(INPUT, stream.as_slice());
     }
 
+    #[mz_ore::test]
+    #[cfg_attr(miri, ignore)] // unsupported operation: can't call foreign function `deflateInit2_` on OS `linux`
+    fn deflate_decompress_limit() {
+        let codec = Codec::Deflate;
+        let input = vec![0; 1024 * 1024];
+        let mut compressed = input.clone();
+        codec.compress(&mut compressed).unwrap();
+        assert!(compressed.len() < 4096);
+
+        // A limit exactly at the decompressed size is permitted.
+        let mut stream = compressed.clone();
+        codec
+            .decompress_with_limit(&mut stream, input.len())
+            .unwrap();
+        assert_eq!(input, stream);
+
+        // A lower limit is not.
+        let mut stream = compressed.clone();
+        let err = codec.decompress_with_limit(&mut stream, 4096).unwrap_err();
+        assert!(
+            matches!(
+                err,
+                AvroError::Decode(DecodeError::DecompressedBlockTooLarge(4096))
+            ),
+            "unexpected error: {err}",
+        );
+        assert_eq!(stream, compressed);
+    }
+
     #[cfg(feature = "snappy")]
     #[mz_ore::test]
     fn snappy_compress_and_decompress() {
@@ -26,6 +26,7 @@ use std::fmt;
 use chrono::NaiveDateTime;
 use fmt::{Debug, Display};
 
+use crate::reader::AVRO_MAGIC;
 use crate::types::ScalarKind;
 use crate::util::TsUnit;
 use crate::{ParseSchemaError, SchemaResolutionError};
@@ -41,6 +42,9 @@ pub enum DecodeError {
         expected: u32,
         actual: u32,
     },
+    /// A compressed block decompressed to more than the permitted number of
+    /// bytes. Contains the limit.
+    DecompressedBlockTooLarge(usize),
     ExpectedNonnegInteger(i64),
     BadTimestamp {
         unit: TsUnit,
@@ -84,7 +88,9 @@ pub enum DecodeError {
     UnexpectedUuid,
     UnexpectedFixed,
     UnexpectedScalarKind(ScalarKind),
-    WrongHeaderMagic([u8; 4]),
+    /// The input did not begin with the magic bytes of an Avro object
+    /// container file. Contains the bytes that were found instead.
+    BadMagic([u8; 4]),
     MissingAvroDotSchema,
     I32OutOfRange(i64),
     IntConversionError,
@@ -104,6 +110,9 @@ pub enum DecodeError {
     },
     DateOutOfRange(i32),
     TimestampOutOfRange(NaiveDateTime),
+    /// The input contained bytes after the end of the decoded datum.
+    /// Contains the number of unconsumed bytes.
+    TrailingBytes(usize),
     Custom(String),
 }
 
@@ -116,6 +125,11 @@ impl DecodeError {
                 "Bad Snappy CRC32; expected {:x} but got {:x}",
                 expected, actual
             ),
+            DecodeError::DecompressedBlockTooLarge(limit) => write!(
+                f,
+                "Decompressed block exceeds maximum size of {} bytes",
+                limit
+            ),
             DecodeError::ExpectedNonnegInteger(i) => {
                 write!(f, "Expected non-negative integer, got {}", i)
             }
@@ -157,7 +171,11 @@ impl DecodeError {
             DecodeError::UnexpectedScalarKind(kind) => {
                 write!(f, "Scalar of unexpected kind: {:?}", kind)
             }
-            DecodeError::WrongHeaderMagic(magic) => write!(f, "Wrong header magic: {:x?}", magic),
+            DecodeError::BadMagic(magic) => write!(
+                f,
+                "not an Avro object container file: magic bytes mismatch (expected {:x?}, found {:x?})",
+                AVRO_MAGIC, magic
+            ),
             DecodeError::MissingAvroDotSchema => write!(
                 f,
                 "Symbol's value as variable is void: avro.schema missing from header"
@@ -189,6 +207,9 @@ impl DecodeError {
             DecodeError::TimestampOutOfRange(inner) => {
                 write!(f, "Timestamp out of range: {}", inner)
             }
+            DecodeError::TrailingBytes(n) => {
+                write!(f, "{} unexpected trailing bytes after datum", n)
+            }
         }
     }
 }
@@ -416,11 +416,14 @@ pub use crate::decode::{
     ValueOrReader,
 };
 pub use crate::encode::encode as encode_unchecked;
-pub use crate::reader::{from_avro_datum, Block, BlockIter, Reader};
+pub use crate::reader::{
+    from_avro_datum, from_avro_datum_strict, AsyncReader, Block, BlockIter, RawBlock, RawBlockIter,
+    Reader,
+};
 pub use crate::schema::{ParseSchemaError, Schema};
 pub use crate::types::SchemaResolutionError;
 pub use crate::util::max_allocation_bytes;
-pub use crate::writer::{to_avro_datum, write_avro_datum, ValidationError, Writer};
+pub use crate::writer::{merge, to_avro_datum, write_avro_datum, ValidationError, Writer};
 
 #[cfg(test)]
 mod tests {
@@ -587,6 +590,73 @@ mod tests {
         assert!(reader.next().is_none());
     }
 
+    #[mz_ore::test]
+    #[cfg_attr(miri, ignore)] // unsupported operation: inline assembly is not supported
+    fn test_enum_resolution_default() {
+        let writer_raw_schema = r#"
+            {
+                "type": "record",
+                "name": "test",
+                "fields": [
+                    {
+                        "name": "c",
+                        "type": {
+                            "type": "enum",
+                            "name": "suit",
+                            "symbols": ["diamonds", "spades", "clubs", "hearts"]
+                        }
+                    }
+                ]
+            }
+        "#;
+        // Unlike in `test_enum_resolution`, the default is a property of the
+        // enum itself rather than of the field, so it applies to symbols
+        // written by the writer that are unknown to the reader.
+        let reader_raw_schema = r#"
+            {
+                "type": "record",
+                "name": "test",
+                "fields": [
+                    {
+                        "name": "c",
+                        "type": {
+                            "type": "enum",
+                            "name": "suit",
+                            "symbols": ["diamonds", "spades", "ninja", "hearts"],
+                            "default": "spades"
+                        }
+                    }
+                ]
+            }
+        "#;
+        let writer_schema = Schema::from_str(writer_raw_schema).unwrap();
+        let reader_schema = Schema::from_str(reader_raw_schema).unwrap();
+        let mut writer = Writer::with_codec(writer_schema.clone(), Vec::new(), Codec::Null);
+        for symbol in ["clubs", "hearts"] {
+            let mut record = Record::new(writer_schema.top_node()).unwrap();
+            record.put("c", symbol);
+            writer.append(record).unwrap();
+        }
+        writer.flush().unwrap();
+        let input = writer.into_inner();
+        let mut reader = Reader::with_schema(&reader_schema, &input[..]).unwrap();
+        assert_eq!(
+            reader.next().unwrap().unwrap(),
+            Value::Record(vec![(
+                "c".to_string(),
+                Value::Enum(1, "spades".to_string())
+            )])
+        );
+        assert_eq!(
+            reader.next().unwrap().unwrap(),
+            Value::Record(vec![(
+                "c".to_string(),
+                Value::Enum(3, "hearts".to_string())
+            )])
+        );
+        assert!(reader.next().is_none());
+    }
+
     //TODO: move where it fits better
     #[mz_ore::test]
     fn test_enum_no_reader_schema() {
@@ -26,8 +26,10 @@
 use std::collections::BTreeMap;
 use std::str::{from_utf8, FromStr};
 
+use futures::stream::{self, Stream};
 use serde_json::from_slice;
 use sha2::Sha256;
+use tokio::io::{AsyncRead, AsyncReadExt};
 
 use crate::decode::{decode, AvroRead};
 use crate::error::{DecodeError, Error as AvroError};
@@ -39,11 +41,15 @@ use crate::schema::{
 use crate::types::Value;
 use crate::{util, Codec, SchemaResolutionError};
 
+/// The magic bytes at the start of every Avro object container file.
+pub(crate) const AVRO_MAGIC: [u8; 4] = [b'O', b'b', b'j', 1u8];
+
 #[derive(Debug, Clone)]
 pub(crate) struct Header {
     writer_schema: Schema,
     marker: [u8; 16],
     codec: Codec,
+    user_metadata: BTreeMap<String, Vec<u8>>,
 }
 
 impl Header {
@@ -57,8 +63,8 @@ impl Header {
         let mut buf = [0u8; 4];
         reader.read_exact(&mut buf)?;
 
-        if buf != [b'O', b'b', b'j', 1u8] {
-            return Err(AvroError::Decode(DecodeError::WrongHeaderMagic(buf)));
+        if buf != AVRO_MAGIC {
+            return Err(AvroError::Decode(DecodeError::BadMagic(buf)));
         }
 
         if let Value::Map(meta) = decode(meta_schema.top_node(), reader)? {
@@ -96,6 +102,15 @@ impl Header {
                 })
                 .unwrap_or(Ok(Codec::Null))?;
 
+            let user_metadata = meta
+                .into_iter()
+                .filter(|(key, _)| !key.starts_with("avro."))
+                .map(|(key, value)| match value {
+                    Value::Bytes(bytes) => (key, bytes),
+                    _ => unreachable!(),
+                })
+                .collect();
+
             let mut marker = [0u8; 16];
             reader.read_exact(&mut marker)?;
 
@@ -103,6 +118,7 @@ impl Header {
                 writer_schema,
                 marker,
                 codec,
+                user_metadata,
             })
         } else {
             unreachable!()
@@ -118,6 +134,7 @@ pub struct Reader<R> {
     header: Header,
     inner: R,
     errored: bool,
+    reader_schema: Option<Schema>,
     resolved_schema: Option<Schema>,
     messages_remaining: usize,
     // Internal buffering to reduce allocation.
@@ -168,6 +185,48 @@ impl<R: AvroRead> Iterator for BlockIter<R> {
     }
 }
 
+/// A block of an Avro OCF file, exactly as it appears in the file.
+///
+/// Unlike a [`Block`], the bytes of a `RawBlock` are not decompressed, so
+/// blocks can be copied from one file to another without decoding and
+/// re-encoding their objects.
+#[derive(Debug, Clone)]
+pub struct RawBlock {
+    /// The bytes of the block, compressed with the file's codec
+    pub bytes: Vec<u8>,
+    /// The number of Avro objects in the block
+    pub len: usize,
+    /// The sync marker that follows the block
+    pub marker: [u8; 16],
+}
+
+impl RawBlock {
+    /// Appends the encoding of this block, as it appears in an OCF file, to
+    /// `buf`.
+    ///
+    /// The result is only valid in a file whose header has the same schema,
+    /// codec, and sync marker as the file the block was read from.
+    pub fn encode(&self, buf: &mut Vec<u8>) {
+        util::zig_i64(self.len as i64, buf);
+        util::zig_i64(self.bytes.len() as i64, buf);
+        buf.extend_from_slice(&self.bytes);
+        buf.extend_from_slice(&self.marker);
+    }
+}
+
+/// An iterator over the `RawBlock`s of a `Reader`
+pub struct RawBlockIter<R> {
+    inner: Reader<R>,
+}
+
+impl<R: AvroRead> Iterator for RawBlockIter<R> {
+    type Item = Result<RawBlock, AvroError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.read_raw_block_next().transpose()
+    }
+}
+
 impl<R: AvroRead> Reader<R> {
     /// Creates a `Reader` given something implementing the `tokio::io::AsyncRead` trait to read from.
     /// No reader `Schema` will be set.
@@ -179,6 +238,7 @@ impl<R: AvroRead> Reader<R> {
             header,
             inner,
             errored: false,
+            reader_schema: None,
             resolved_schema: None,
             messages_remaining: 0,
             buf: vec![],
@@ -206,6 +266,7 @@ impl<R: AvroRead> Reader<R> {
         Ok(Reader {
             header,
             errored: false,
+            reader_schema: Some(reader_schema.clone()),
             resolved_schema,
             inner,
             messages_remaining: 0,
@@ -219,6 +280,26 @@ impl<R: AvroRead> Reader<R> {
         &self.header.writer_schema
     }
 
+    /// Get a reference to the reader `Schema`
+    /// (or the writer schema, if no reader schema was provided)
+    pub fn reader_schema(&self) -> &Schema {
+        match &self.reader_schema {
+            Some(schema) => schema,
+            None => self.writer_schema(),
+        }
+    }
+
+    /// Get the user metadata from the file header, i.e., the entries whose
+    /// keys are not in the reserved `avro.` namespace.
+    pub fn user_metadata(&self) -> &BTreeMap<String, Vec<u8>> {
+        &self.header.user_metadata
+    }
+
+    /// Get the codec with which the blocks of the file are compressed.
+    pub fn codec(&self) -> Codec {
+        self.header.codec
+    }
+
     /// Get a reference to the resolved schema
     /// (or just the writer schema, if no reader schema was provided
     ///  or the two schemas are identical)
@@ -248,6 +329,18 @@ impl<R: AvroRead> Reader<R> {
         Ok(Some(item))
     }
 
+    /// Converts this reader into an iterator over the remaining blocks of the
+    /// file, without decompressing or decoding them.
+    ///
+    /// # Panics
+    ///
+    /// Panics if some, but not all, of the values in the current block have
+    /// been read.
+    pub fn into_raw_blocks(self) -> RawBlockIter<R> {
+        assert!(self.is_empty(), "Expected self to be empty!");
+        RawBlockIter { inner: self }
+    }
+
     fn is_empty(&self) -> bool {
         self.messages_remaining == 0
     }
@@ -301,6 +394,35 @@ impl<R: AvroRead> Reader<R> {
             }
         }
     }
+
+    /// Reads the next block from the file without decompressing it, or
+    /// returns `None` if the file has no more blocks.
+    fn read_raw_block_next(&mut self) -> Result<Option<RawBlock>, AvroError> {
+        let len = match util::read_long(&mut self.inner) {
+            Ok(len) => len,
+            // We finished reading cleanly from the stream.
+            Err(AvroError::IO(std::io::ErrorKind::UnexpectedEof)) => return Ok(None),
+            Err(e) => return Err(e),
+        };
+        let len = usize::try_from(len).map_err(|_| DecodeError::ExpectedNonnegInteger(len))?;
+        let block_bytes = util::read_long(&mut self.inner)?;
+        let block_bytes = usize::try_from(block_bytes)
+            .map_err(|_| DecodeError::ExpectedNonnegInteger(block_bytes))?;
+        let mut bytes = vec![0; util::safe_len(block_bytes)?];
+        self.inner.read_exact(&mut bytes)?;
+        let mut marker = [0u8; 16];
+        self.inner.read_exact(&mut marker)?;
+
+        if marker != self.header.marker {
+            return Err(DecodeError::MismatchedBlockHeader {
+                expected: self.header.marker,
+                actual: marker,
+            }
+            .into());
+        }
+
+        Ok(Some(RawBlock { bytes, len, marker }))
+    }
 }
 
 impl<R: AvroRead> Iterator for Reader<R> {
@@ -321,6 +443,213 @@ impl<R: AvroRead> Iterator for Reader<R> {
     }
 }
 
+/// A reader for Avro object container files that incrementally decodes values
+/// from an [`AsyncRead`] source.
+///
+/// Unlike [`Reader`], which requires a synchronous source, an `AsyncReader`
+/// only buffers a single block of the file at a time.
+///
+/// Construct an `AsyncReader` with [`Reader::from_async_read`].
+pub struct AsyncReader<R> {
+    header: Header,
+    inner: R,
+    errored: bool,
+    messages_remaining: usize,
+    // Bytes that were read from `inner` while decoding the header, but that
+    // belong to the first block.
+    pending: Vec<u8>,
+    pending_idx: usize,
+    // The decompressed contents of the current block.
+    buf: Vec<u8>,
+    buf_idx: usize,
+}
+
+/// The number of bytes to request from the underlying source at a time while
+/// reading the header.
+const ASYNC_HEADER_CHUNK_SIZE: usize = 4096;
+
+/// The maximum size of a header that [`AsyncReader`] will buffer.
+const ASYNC_MAX_HEADER_SIZE: usize = 16 * 1024 * 1024;
+
+impl<R: AsyncRead + Unpin> Reader<R> {
+    /// Creates an [`AsyncReader`] that decodes values from the given
+    /// `tokio::io::AsyncRead`.
+    ///
+    /// **NOTE** The avro header is read before this function returns.
+    /// Subsequent values are decoded as the bytes for their block arrive.
+    pub async fn from_async_read(mut inner: R) -> Result<AsyncReader<R>, AvroError> {
+        // The header has no length prefix, so read until we've accumulated
+        // enough bytes to decode it. The buffer doubles in size between
+        // attempts, so that decoding a large header doesn't take quadratic
+        // time.
+        let mut pending = vec![];
+        let mut eof = false;
+        loop {
+            let mut slice = &pending[..];
+            match Header::from_reader(&mut slice) {
+                Ok(header) => {
+                    let pending_idx = pending.len() - slice.len();
+                    return Ok(AsyncReader {
+                        header,
+                        inner,
+                        errored: false,
+                        messages_remaining: 0,
+                        pending,
+                        pending_idx,
+                        buf: vec![],
+                        buf_idx: 0,
+                    });
+                }
+                Err(AvroError::IO(std::io::ErrorKind::UnexpectedEof)) if !eof => {
+                    let len = pending.len();
+                    if len >= ASYNC_MAX_HEADER_SIZE {
+                        return Err(AvroError::Allocation {
+                            attempted: len * 2,
+                            allowed: ASYNC_MAX_HEADER_SIZE,
+                        });
+                    }
+                    let target = (len * 2)
+                        .max(ASYNC_HEADER_CHUNK_SIZE)
+                        .min(ASYNC_MAX_HEADER_SIZE);
+                    pending.resize(target, 0);
+                    let mut filled = len;
+                    while filled < target {
+                        let n = inner.read(&mut pending[filled..]).await?;
+                        if n == 0 {
+                            eof = true;
+                            break;
+                        }
+                        filled += n;
+                    }
+                    pending.truncate(filled);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncReader<R> {
+    /// Get a reference to the writer `Schema`.
+    pub fn writer_schema(&self) -> &Schema {
+        &self.header.writer_schema
+    }
+
+    /// Get the user metadata from the file header, i.e., the entries whose
+    /// keys are not in the reserved `avro.` namespace.
+    pub fn user_metadata(&self) -> &BTreeMap<String, Vec<u8>> {
+        &self.header.user_metadata
+    }
+
+    /// Read the next Avro value from the file, if one exists.
+    pub async fn read_next(&mut self) -> Result<Option<Value>, AvroError> {
+        if self.messages_remaining == 0 {
+            self.read_block_next().await?;
+            if self.messages_remaining == 0 {
+                return Ok(None);
+            }
+        }
+
+        let mut block_bytes = &self.buf[self.buf_idx..];
+        let b_original = block_bytes.len();
+        let item = from_avro_datum(&self.header.writer_schema, &mut block_bytes)?;
+        self.buf_idx += b_original - block_bytes.len();
+        self.messages_remaining -= 1;
+        Ok(Some(item))
+    }
+
+    /// Converts this reader into a stream of the Avro values in the file.
+    ///
+    /// The stream ends after the first error.
+    pub fn into_stream(self) -> impl Stream<Item = Result<Value, AvroError>> {
+        stream::unfold(self, |mut reader| async move {
+            if reader.errored {
+                return None;
+            }
+            match reader.read_next().await {
+                Ok(opt) => opt.map(|value| (Ok(value), reader)),
+                Err(e) => {
+                    reader.errored = true;
+                    Some((Err(e), reader))
+                }
+            }
+        })
+    }
+
+    async fn read_exact(&mut self, buf: &mut [u8]) -> Result<(), AvroError> {
+        let pending = &self.pending[self.pending_idx..];
+        let n = pending.len().min(buf.len());
+        buf[..n].copy_from_slice(&pending[..n]);
+        self.pending_idx += n;
+        if self.pending_idx == self.pending.len() && !self.pending.is_empty() {
+            self.pending = vec![];
+            self.pending_idx = 0;
+        }
+        self.inner.read_exact(&mut buf[n..]).await?;
+        Ok(())
+    }
+
+    async fn read_long(&mut self) -> Result<i64, AvroError> {
+        self.read_long_or_eof()
+            .await?
+            .ok_or(AvroError::IO(std::io::ErrorKind::UnexpectedEof))
+    }
+
+    /// Like `read_long`, but returns `None` if the source ends before the
+    /// first byte of the long. Ending partway through the long is an error.
+    async fn read_long_or_eof(&mut self) -> Result<Option<i64>, AvroError> {
+        // Longs are variable-length encoded, with the high bit of each byte
+        // indicating whether another byte follows.
+        let mut encoded = vec![];
+        loop {
+            let mut byte = [0u8; 1];
+            match self.read_exact(&mut byte).await {
+                Ok(()) => (),
+                Err(AvroError::IO(std::io::ErrorKind::UnexpectedEof)) if encoded.is_empty() => {
+                    return Ok(None)
+                }
+                Err(e) => return Err(e),
+            }
+            encoded.push(byte[0]);
+            if byte[0] >> 7 == 0 || encoded.len() > 10 {
+                break;
+            }
+        }
+        util::read_long(&mut &encoded[..]).map(Some)
+    }
+
+    async fn read_block_next(&mut self) -> Result<(), AvroError> {
+        assert_eq!(self.messages_remaining, 0, "Expected self to be empty!");
+        let block_len = match self.read_long_or_eof().await? {
+            Some(block_len) => block_len,
+            // The file ended cleanly between blocks.
+            None => return Ok(()),
+        };
+        let block_bytes = self.read_long().await?;
+        let block_bytes = usize::try_from(block_bytes)
+            .map_err(|_| DecodeError::ExpectedNonnegInteger(block_bytes))?;
+        let mut buf = std::mem::take(&mut self.buf);
+        buf.resize(util::safe_len(block_bytes)?, 0);
+        self.read_exact(&mut buf).await?;
+        let mut marker = [0u8; 16];
+        self.read_exact(&mut marker).await?;
+
+        if marker != self.header.marker {
+            return Err(DecodeError::MismatchedBlockHeader {
+                expected: self.header.marker,
+                actual: marker,
+            }
+            .into());
+        }
+
+        self.header.codec.decompress(&mut buf)?;
+        self.buf = buf;
+        self.buf_idx = 0;
+        self.messages_remaining = block_len as usize;
+        Ok(())
+    }
+}
+
 pub struct SchemaResolver<'a> {
     pub named: Vec<Option<NamedSchemaPiece>>,
     pub indices: BTreeMap<FullName, usize>,
@@ -928,12 +1257,30 @@ pub fn from_avro_datum<R: AvroRead>(schema: &Schema, reader: &mut R) -> Result<V
     Ok(value)
 }
 
+/// Like [`from_avro_datum`], but requires that `bytes` contain exactly one
+/// datum.
+///
+/// Returns [`DecodeError::TrailingBytes`] if any bytes remain after the
+/// datum is decoded, which usually indicates a corrupt or mis-framed buffer.
+pub fn from_avro_datum_strict(schema: &Schema, mut bytes: &[u8]) -> Result<Value, AvroError> {
+    let value = decode(schema.top_node(), &mut bytes)?;
+    if !bytes.is_empty() {
+        return Err(DecodeError::TrailingBytes(bytes.len()).into());
+    }
+    Ok(value)
+}
+
 #[cfg(test)]
 mod tests {
     use std::io::Cursor;
+    use std::pin::Pin;
+    use std::task::{Context, Poll};
+
+    use futures::{StreamExt, TryStreamExt};
+    use tokio::io::ReadBuf;
 
     use crate::types::{Record, ToAvro};
-    use crate::Reader;
+    use crate::{Reader, Writer};
 
     use super::*;
 
@@ -980,6 +1327,33 @@ mod tests {
         assert_eq!(from_avro_datum(&schema, &mut encoded).unwrap(), expected);
     }
 
+    #[mz_ore::test]
+    fn test_from_avro_datum_trailing_bytes() {
+        let schema: Schema = SCHEMA.parse().unwrap();
+        let encoded: &'static [u8] = &[54, 6, 102, 111, 111, 1, 2];
+
+        let mut record = Record::new(schema.top_node()).unwrap();
+        record.put("a", 27i64);
+        record.put("b", "foo");
+        let expected = record.avro();
+
+        // The lenient variant ignores the trailing bytes.
+        assert_eq!(
+            from_avro_datum(&schema, &mut &encoded[..]).unwrap(),
+            expected
+        );
+
+        // The strict variant rejects them.
+        assert_eq!(
+            from_avro_datum_strict(&schema, encoded),
+            Err(AvroError::Decode(DecodeError::TrailingBytes(2)))
+        );
+        assert_eq!(
+            from_avro_datum_strict(&schema, &encoded[..5]).unwrap(),
+            expected
+        );
+    }
+
     #[mz_ore::test]
     fn test_null_union() {
         let schema: Schema = UNION_SCHEMA.parse().unwrap();
@@ -1049,6 +1423,266 @@ mod tests {
         assert!(Reader::new(empty).is_err());
     }
 
+    #[mz_ore::test]
+    fn test_reader_bad_magic() {
+        let invalid = b"PAR1\x15\x04\x15\x10";
+        match Reader::new(&invalid[..]) {
+            Err(AvroError::Decode(DecodeError::BadMagic(magic))) => assert_eq!(&magic, b"PAR1"),
+            Err(e) => panic!("unexpected error: {e}"),
+            Ok(_) => panic!("unexpectedly read non-Avro input"),
+        }
+    }
+
+    #[mz_ore::test]
+    #[cfg_attr(miri, ignore)] // unsupported operation: inline assembly is not supported
+    fn test_reader_raw_blocks() {
+        let schema = Schema::from_str(SCHEMA).unwrap();
+        let mut writer = Writer::with_codec(schema.clone(), Vec::new(), Codec::Deflate);
+        for i in 0..100 {
+            let mut record = Record::new(schema.top_node()).unwrap();
+            record.put("a", i64::from(i));
+            record.put("b", format!("value {i}"));
+            writer.append(record).unwrap();
+            if i % 10 == 9 {
+                writer.flush().unwrap();
+            }
+        }
+        let encoded = writer.into_inner();
+        let expected = Reader::new(&encoded[..])
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        let mut body = &encoded[..];
+        Header::from_reader(&mut body).unwrap();
+        let header = &encoded[..encoded.len() - body.len()];
+
+        let blocks = Reader::new(&encoded[..])
+            .unwrap()
+            .into_raw_blocks()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(blocks.len(), 10);
+        assert!(blocks.iter().all(|block| block.len == 10));
+
+        // Reassembling every block reproduces the original file.
+        let mut reassembled = header.to_vec();
+        for block in &blocks {
+            block.encode(&mut reassembled);
+        }
+        assert_eq!(reassembled, encoded);
+
+        // Reassembling a subset of the blocks produces a file containing just
+        // the values in those blocks.
+        let mut subset = header.to_vec();
+        blocks[2].encode(&mut subset);
+        blocks[7].encode(&mut subset);
+        let values = Reader::new(&subset[..])
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(values, [&expected[20..30], &expected[70..80]].concat());
+    }
+
+    /// An `AsyncRead` that yields at most `chunk_size` bytes per read, and that
+    /// is only ready on every other poll.
+    struct ChunkedRead<'a> {
+        data: &'a [u8],
+        chunk_size: usize,
+        ready: bool,
+    }
+
+    impl AsyncRead for ChunkedRead<'_> {
+        fn poll_read(
+            mut self: Pin<&mut Self>,
+            cx: &mut Context<'_>,
+            buf: &mut ReadBuf<'_>,
+        ) -> Poll<std::io::Result<()>> {
+            self.ready = !self.ready;
+            if !self.ready {
+                cx.waker().wake_by_ref();
+                return Poll::Pending;
+            }
+            let n = self.chunk_size.min(self.data.len()).min(buf.remaining());
+            buf.put_slice(&self.data[..n]);
+            self.data = &self.data[n..];
+            Poll::Ready(Ok(()))
+        }
+    }
+
+    #[mz_ore::test(tokio::test)]
+    #[cfg_attr(miri, ignore)] // unsupported operation: returning ready events from epoll_wait is not yet implemented
+    async fn test_reader_from_async_read() {
+        let schema = Schema::from_str(SCHEMA).unwrap();
+        let mut writer = Writer::with_codec(schema.clone(), Vec::new(), Codec::Deflate);
+        for i in 0..100 {
+            let mut record = Record::new(schema.top_node()).unwrap();
+            record.put("a", i64::from(i));
+            record.put("b", format!("value {i}"));
+            writer.append(record).unwrap();
+            if i % 7 == 0 {
+                writer.flush().unwrap();
+            }
+        }
+        writer.flush().unwrap();
+        let encoded = writer.into_inner();
+
+        let expected = Reader::new(&encoded[..])
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(expected.len(), 100);
+
+        for chunk_size in [1, 3, 64, encoded.len()] {
+            let read = ChunkedRead {
+                data: &encoded,
+                chunk_size,
+                ready: false,
+            };
+            let reader = Reader::from_async_read(read).await.unwrap();
+            let values = reader.into_stream().try_collect::<Vec<_>>().await.unwrap();
+            assert_eq!(values, expected);
+        }
+
+        // Truncating the input in the middle of a block produces an error.
+        let read = ChunkedRead {
+            data: &encoded[..encoded.len() - 1],
+            chunk_size: 5,
+            ready: false,
+        };
+        let reader = Reader::from_async_read(read).await.unwrap();
+        let results = reader.into_stream().collect::<Vec<_>>().await;
+        assert!(results.last().unwrap().is_err());
+
+        // Truncating the input in the middle of a block header produces an
+        // error, rather than looking like the end of the file.
+        let mut slice = &encoded[..];
+        Header::from_reader(&mut slice).unwrap();
+        let header_len = encoded.len() - slice.len();
+        let mut truncated = encoded[..header_len].to_vec();
+        // A block count of 1, followed by the first byte of a multi-byte
+        // block size.
+        truncated.extend([0x02, 0x80]);
+        let read = ChunkedRead {
+            data: &truncated,
+            chunk_size: 5,
+            ready: false,
+        };
+        let reader = Reader::from_async_read(read).await.unwrap();
+        let results = reader.into_stream().collect::<Vec<_>>().await;
+        assert_eq!(results.len(), 1);
+        assert!(results[0].is_err());
+
+        // An implausibly large block size is rejected before it is allocated.
+        let mut huge = encoded[..header_len].to_vec();
+        util::zig_i64(1, &mut huge);
+        util::zig_i64(i64::MAX, &mut huge);
+        let read = ChunkedRead {
+            data: &huge,
+            chunk_size: 64,
+            ready: false,
+        };
+        let reader = Reader::from_async_read(read).await.unwrap();
+        let results = reader.into_stream().collect::<Vec<_>>().await;
+        assert!(matches!(results[..], [Err(AvroError::Allocation { .. })]));
+    }
+
+    #[mz_ore::test(tokio::test)]
+    #[cfg_attr(miri, ignore)] // unsupported operation: returning ready events from epoll_wait is not yet implemented
+    async fn test_reader_from_async_read_header_too_large() {
+        // A header that never ends is rejected once it exceeds the limit,
+        // rather than being buffered indefinitely.
+        let mut data = AVRO_MAGIC.to_vec();
+        // A metadata map with a single entry whose value is larger than the
+        // limit.
+        util::zig_i64(1, &mut data);
+        util::zig_i64(0, &mut data);
+        util::zig_i64(i64::try_from(ASYNC_MAX_HEADER_SIZE * 2).unwrap(), &mut data);
+        data.resize(ASYNC_MAX_HEADER_SIZE * 3, 0);
+        let read = ChunkedRead {
+            data: &data,
+            chunk_size: 1 << 20,
+            ready: false,
+        };
+        let result = Reader::from_async_read(read).await;
+        assert!(matches!(result, Err(AvroError::Allocation { .. })));
+    }
+
+    #[mz_ore::test]
+    #[cfg_attr(miri, ignore)] // unsupported operation: inline assembly is not supported
+    fn test_user_metadata() {
+        let schema: Schema = SCHEMA.parse().unwrap();
+        let mut writer = Writer::new(schema.clone(), Vec::new());
+        writer.add_user_metadata("origin", "test").unwrap();
+        assert!(writer.add_user_metadata("avro.codec", "deflate").is_err());
+
+        let mut record = Record::new(schema.top_node()).unwrap();
+        record.put("a", 27i64);
+        record.put("b", "foo");
+        writer.append(record).unwrap();
+        assert!(writer.add_user_metadata("late", "value").is_err());
+        writer.flush().unwrap();
+        let input = writer.into_inner();
+
+        let reader = Reader::new(&input[..]).unwrap();
+        assert_eq!(
+            reader.user_metadata(),
+            &BTreeMap::from([("origin".to_string(), b"test".to_vec())])
+        );
+        assert_eq!(reader.count(), 1);
+    }
+
+    #[mz_ore::test]
+    fn test_reader_schemas() {
+        let writer_schema: Schema = SCHEMA.parse().unwrap();
+        let reader_schema: Schema = r#"
+            {
+                "type": "record",
+                "name": "test",
+                "fields": [
+                    {"name": "b", "type": "string"},
+                    {"name": "c", "type": "long", "default": 0}
+                ]
+            }
+        "#
+        .parse()
+        .unwrap();
+
+        let reader = Reader::new(ENCODED).unwrap();
+        assert_eq!(
+            reader.writer_schema().canonical_form(),
+            writer_schema.canonical_form()
+        );
+        assert_eq!(
+            reader.reader_schema().canonical_form(),
+            writer_schema.canonical_form()
+        );
+
+        let reader = Reader::with_schema(&reader_schema, ENCODED).unwrap();
+        assert_eq!(
+            reader.writer_schema().canonical_form(),
+            writer_schema.canonical_form()
+        );
+        assert_eq!(
+            reader.reader_schema().canonical_form(),
+            reader_schema.canonical_form()
+        );
+        let values = reader.collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(
+            values,
+            vec![
+                Value::Record(vec![
+                    ("b".to_string(), Value::String("foo".to_string())),
+                    ("c".to_string(), Value::Long(0)),
+                ]),
+                Value::Record(vec![
+                    ("b".to_string(), Value::String("bar".to_string())),
+                    ("c".to_string(), Value::Long(0)),
+                ]),
+            ]
+        );
+    }
+
     #[mz_ore::test]
     fn test_reader_only_header() {
         let invalid = ENCODED.iter().copied().take(165).collect::<Vec<u8>>();
@@ -87,6 +87,12 @@ pub fn resolve_schemas(
     Ok(sch)
 }
 
+/// The default maximum depth to which a schema may be nested.
+///
+/// Parsing recurses once per level of nesting, so without a limit an
+/// adversarial schema could overflow the stack.
+pub const DEFAULT_MAX_SCHEMA_DEPTH: usize = 64;
+
 /// Describes errors happened while parsing Avro schemas.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ParseSchemaError(String);
@@ -835,16 +841,59 @@ impl PartialEq for UnionSchema {
     }
 }
 
-#[derive(Default)]
+/// A step in the path from the root of a JSON schema to a nested schema.
+#[derive(Clone, Debug)]
+enum SchemaPathSegment {
+    /// The value of the named key of an object.
+    Key(&'static str),
+    /// The element at the given index of an array.
+    Index(usize),
+}
+
+/// Renders a path in the form `fields[2].type.items`.
+struct SchemaPath<'a>(&'a [SchemaPathSegment]);
+
+impl fmt::Display for SchemaPath<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, segment) in self.0.iter().enumerate() {
+            match segment {
+                SchemaPathSegment::Key(key) if i == 0 => write!(f, "{}", key)?,
+                SchemaPathSegment::Key(key) => write!(f, ".{}", key)?,
+                SchemaPathSegment::Index(index) => write!(f, "[{}]", index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
 struct SchemaParser {
     named: Vec<Option<NamedSchemaPiece>>,
     indices: BTreeMap<FullName, usize>,
+    /// The current nesting depth.
+    depth: usize,
+    /// The maximum permitted nesting depth.
+    max_depth: usize,
+    /// The path from the root of the schema to the value being parsed.
+    path: Vec<SchemaPathSegment>,
+    /// The path to the value at which parsing failed, if it has.
+    error_path: Option<Vec<SchemaPathSegment>>,
 }
 
 impl SchemaParser {
     fn parse(mut self, value: &Value) -> Result<Schema, AvroError> {
-        let top = self.parse_inner("", value)?;
-        let SchemaParser { named, indices } = self;
+        let top = match self.parse_inner("", value) {
+            Ok(top) => top,
+            Err(AvroError::ParseSchema(e)) => match self.error_path {
+                Some(path) if !path.is_empty() => {
+                    return Err(
+                        ParseSchemaError::new(format!("{} (at {})", e, SchemaPath(&path))).into(),
+                    )
+                }
+                _ => return Err(e.into()),
+            },
+            Err(e) => return Err(e),
+        };
+        let SchemaParser { named, indices, .. } = self;
         Ok(Schema {
             named: named.into_iter().map(|o| o.unwrap()).collect(),
             indices,
@@ -856,6 +905,41 @@ impl SchemaParser {
         &mut self,
         default_namespace: &str,
         value: &Value,
+    ) -> Result<SchemaPieceOrNamed, AvroError> {
+        if self.depth == self.max_depth {
+            return Err(ParseSchemaError::new(format!(
+                "Schema is nested more than {} levels deep",
+                self.max_depth
+            ))
+            .into());
+        }
+        self.depth += 1;
+        let result = self.parse_value(default_namespace, value);
+        self.depth -= 1;
+        result
+    }
+
+    /// Runs `f` with `segment` appended to the current path.
+    ///
+    /// If `f` fails, and no path has yet been recorded for the failure, the
+    /// path is recorded so that it can be reported in the error.
+    fn with_segment<T, F>(&mut self, segment: SchemaPathSegment, f: F) -> Result<T, AvroError>
+    where
+        F: FnOnce(&mut Self) -> Result<T, AvroError>,
+    {
+        self.path.push(segment);
+        let result = f(self);
+        if result.is_err() && self.error_path.is_none() {
+            self.error_path = Some(self.path.clone());
+        }
+        self.path.pop();
+        result
+    }
+
+    fn parse_value(
+        &mut self,
+        default_namespace: &str,
+        value: &Value,
     ) -> Result<SchemaPieceOrNamed, AvroError> {
         match *value {
             Value::String(ref t) => {
@@ -970,7 +1054,11 @@ impl SchemaParser {
                 }
             }),
             Some(&Value::Object(ref data)) => match data.get("type") {
-                Some(value) => self.parse_inner(default_namespace, value),
+                Some(value) => self.with_segment(SchemaPathSegment::Key("type"), |p| {
+                    p.with_segment(SchemaPathSegment::Key("type"), |p| {
+                        p.parse_inner(default_namespace, value)
+                    })
+                }),
                 None => Err(
                     ParseSchemaError::new(format!("Unknown complex type: {:?}", complex)).into(),
                 ),
@@ -998,7 +1086,11 @@ impl SchemaParser {
                     .filter_map(|field| field.as_object())
                     .enumerate()
                     .map(|(position, field)| {
-                        self.parse_record_field(default_namespace, field, position)
+                        self.with_segment(SchemaPathSegment::Key("fields"), |p| {
+                            p.with_segment(SchemaPathSegment::Index(position), |p| {
+                                p.parse_record_field(default_namespace, field, position)
+                            })
+                        })
                     })
                     .collect::<Result<_, _>>()
             })?;
@@ -1030,7 +1122,11 @@ impl SchemaParser {
         let schema = field
             .get("type")
             .ok_or_else(|| ParseSchemaError::new("No `type` in record field").into())
-            .and_then(|type_| self.parse_inner(default_namespace, type_))?;
+            .and_then(|type_| {
+                self.with_segment(SchemaPathSegment::Key("type"), |p| {
+                    p.parse_inner(default_namespace, type_)
+                })
+            })?;
 
         let default = field.get("default").cloned();
 
@@ -1121,7 +1217,11 @@ impl SchemaParser {
         complex
             .get("items")
             .ok_or_else(|| ParseSchemaError::new("No `items` in array").into())
-            .and_then(|items| self.parse_inner(default_namespace, items))
+            .and_then(|items| {
+                self.with_segment(SchemaPathSegment::Key("items"), |p| {
+                    p.parse_inner(default_namespace, items)
+                })
+            })
             .map(|schema| SchemaPiece::Array(Box::new(schema)))
     }
 
@@ -1135,7 +1235,11 @@ impl SchemaParser {
         complex
             .get("values")
             .ok_or_else(|| ParseSchemaError::new("No `values` in map").into())
-            .and_then(|items| self.parse_inner(default_namespace, items))
+            .and_then(|values| {
+                self.with_segment(SchemaPathSegment::Key("values"), |p| {
+                    p.parse_inner(default_namespace, values)
+                })
+            })
             .map(|schema| SchemaPiece::Map(Box::new(schema)))
     }
 
@@ -1148,7 +1252,12 @@ impl SchemaParser {
     ) -> Result<SchemaPiece, AvroError> {
         items
             .iter()
-            .map(|value| self.parse_inner(default_namespace, value))
+            .enumerate()
+            .map(|(i, value)| {
+                self.with_segment(SchemaPathSegment::Index(i), |p| {
+                    p.parse_inner(default_namespace, value)
+                })
+            })
             .collect::<Result<Vec<_>, _>>()
             .and_then(|schemas| Ok(SchemaPiece::Union(UnionSchema::new(schemas)?)))
     }
@@ -1346,10 +1455,23 @@ impl SchemaParser {
 impl Schema {
     /// Create a `Schema` from a `serde_json::Value` representing a JSON Avro
     /// schema.
+    ///
+    /// Schemas nested more than [`DEFAULT_MAX_SCHEMA_DEPTH`] levels deep are
+    /// rejected.
     pub fn parse(value: &Value) -> Result<Self, AvroError> {
+        Self::parse_with_max_depth(value, DEFAULT_MAX_SCHEMA_DEPTH)
+    }
+
+    /// Like [`Schema::parse`], but rejects schemas nested more than
+    /// `max_depth` levels deep.
+    pub fn parse_with_max_depth(value: &Value, max_depth: usize) -> Result<Self, AvroError> {
         let p = SchemaParser {
             named: vec![],
             indices: Default::default(),
+            depth: 0,
+            max_depth,
+            path: vec![],
+            error_path: None,
         };
         p.parse(value)
     }
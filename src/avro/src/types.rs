@@ -317,6 +317,30 @@ impl<'a> Record<'a> {
         }
     }
 
+    /// Fill in every field that was not `put` and whose schema is a union
+    /// with a `null` variant (e.g., `["null", "string"]`) and a `null`
+    /// default by setting it to the union's null variant.
+    ///
+    /// Unset fields are otherwise left as `Value::Null`, which does not
+    /// validate against a union schema.
+    pub fn put_default_for_missing(&mut self) {
+        for (schema_field, (_, value)) in self.schema_fields.iter().zip(self.fields.iter_mut()) {
+            if !matches!(value, Value::Null) || schema_field.default != Some(JsonValue::Null) {
+                continue;
+            }
+            if let SchemaPieceOrNamed::Piece(SchemaPiece::Union(union)) = &schema_field.schema {
+                if let Some((null_variant, _)) = union.match_piece(&SchemaPiece::Null) {
+                    *value = Value::Union {
+                        index: null_variant,
+                        inner: Box::new(Value::Null),
+                        n_variants: union.variants().len(),
+                        null_variant: Some(null_variant),
+                    };
+                }
+            }
+        }
+    }
+
     /// Get the field description corresponding to the given name.
     pub fn field_by_name(&self, name: &str) -> Option<&'a RecordField> {
         self.schema_lookup
@@ -434,6 +458,62 @@ impl Value {
         }
     }
 
+    /// Converts the value to JSON, following the conventions the Avro
+    /// specification uses for default values.
+    ///
+    /// `bytes` and `fixed` values become strings whose code points are the
+    /// byte values, and non-finite floats become `"NaN"`, `"Infinity"`, or
+    /// `"-Infinity"`. A union value becomes the JSON for its variant, without
+    /// wrapping it in an object keyed by the variant's type name, as the name
+    /// is not recorded in the `Value`. Logical types become their underlying
+    /// representation, except that timestamps, whose precision is likewise
+    /// not recorded, become microseconds since the Unix epoch.
+    pub fn to_json(&self) -> JsonValue {
+        fn bytes_to_json(bytes: &[u8]) -> JsonValue {
+            JsonValue::String(bytes.iter().map(|b| char::from(*b)).collect())
+        }
+
+        fn float_to_json(f: f64) -> JsonValue {
+            match serde_json::Number::from_f64(f) {
+                Some(n) => JsonValue::Number(n),
+                None if f.is_nan() => JsonValue::String("NaN".into()),
+                None if f.is_sign_positive() => JsonValue::String("Infinity".into()),
+                None => JsonValue::String("-Infinity".into()),
+            }
+        }
+
+        match self {
+            Value::Null => JsonValue::Null,
+            Value::Boolean(b) => JsonValue::Bool(*b),
+            Value::Int(i) | Value::Date(i) => JsonValue::from(*i),
+            Value::Long(l) => JsonValue::from(*l),
+            Value::Float(f) => float_to_json(f64::from(*f)),
+            Value::Double(f) => float_to_json(*f),
+            Value::Timestamp(ts) => JsonValue::from(
+                ts.timestamp() * 1_000_000 + i64::from(ts.timestamp_subsec_micros()),
+            ),
+            Value::Decimal(DecimalValue { unscaled, .. }) => bytes_to_json(unscaled),
+            Value::Bytes(bytes) | Value::Fixed(_, bytes) => bytes_to_json(bytes),
+            Value::String(s) | Value::Enum(_, s) => JsonValue::String(s.clone()),
+            Value::Union { inner, .. } => inner.to_json(),
+            Value::Array(items) => JsonValue::Array(items.iter().map(Value::to_json).collect()),
+            Value::Map(items) => JsonValue::Object(
+                items
+                    .iter()
+                    .map(|(key, value)| (key.clone(), value.to_json()))
+                    .collect(),
+            ),
+            Value::Record(fields) => JsonValue::Object(
+                fields
+                    .iter()
+                    .map(|(name, value)| (name.clone(), value.to_json()))
+                    .collect(),
+            ),
+            Value::Json(json) => JsonValue::String(json.to_string()),
+            Value::Uuid(uuid) => JsonValue::String(uuid.to_string()),
+        }
+    }
+
     // TODO - `into_` functions for all possible Value variants (perhaps generate this using a macro?)
     pub fn into_string(self) -> Option<String> {
         match self {
@@ -653,4 +733,51 @@ mod tests {
             .top_node()
         ));
     }
+
+    #[mz_ore::test]
+    fn to_json() {
+        let union = |index, inner| Value::Union {
+            index,
+            inner: Box::new(inner),
+            n_variants: 2,
+            null_variant: Some(0),
+        };
+        let value = Value::Record(vec![
+            ("id".into(), Value::Long(1)),
+            ("name".into(), union(1, Value::String("alice".into()))),
+            ("nickname".into(), union(0, Value::Null)),
+            (
+                "scores".into(),
+                Value::Array(vec![Value::Double(1.5), Value::Float(f32::NAN)]),
+            ),
+            (
+                "tags".into(),
+                Value::Map(BTreeMap::from([(
+                    "color".into(),
+                    Value::Enum(1, "red".into()),
+                )])),
+            ),
+            ("payload".into(), Value::Bytes(vec![0x00, 0x41, 0xff])),
+            ("checksum".into(), Value::Fixed(2, vec![0xde, 0xad])),
+            ("born".into(), Value::Date(10)),
+            (
+                "updated".into(),
+                Value::Timestamp(NaiveDateTime::from_timestamp_opt(-1, 500_000_000).unwrap()),
+            ),
+        ]);
+        assert_eq!(
+            value.to_json(),
+            serde_json::json!({
+                "id": 1,
+                "name": "alice",
+                "nickname": null,
+                "scores": [1.5, "NaN"],
+                "tags": {"color": "red"},
+                "payload": "\u{0}A\u{ff}",
+                "checksum": "\u{de}\u{ad}",
+                "born": 10,
+                "updated": -500_000,
+            })
+        );
+    }
 }
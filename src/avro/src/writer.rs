@@ -29,10 +29,11 @@ use std::io::{Seek, SeekFrom, Write};
 
 use anyhow::Error;
 use rand::random;
+use sha2::Sha256;
 
 use crate::decode::AvroRead;
 use crate::encode::{encode, encode_ref, encode_to_vec};
-use crate::reader::Header;
+use crate::reader::{Header, RawBlock, Reader};
 use crate::schema::{Schema, SchemaPiece};
 use crate::types::{ToAvro, Value};
 use crate::Codec;
@@ -72,6 +73,7 @@ pub struct Writer<W> {
     codec: Option<Codec>,
     marker: [u8; 16],
     has_header: bool,
+    user_metadata: BTreeMap<String, Vec<u8>>,
 }
 
 impl<W: Write> Writer<W> {
@@ -107,6 +109,7 @@ impl<W: Write> Writer<W> {
             codec,
             marker,
             has_header: false,
+            user_metadata: BTreeMap::new(),
         }
     }
 
@@ -126,6 +129,7 @@ impl<W: Write> Writer<W> {
             codec: Some(codec),
             marker,
             has_header: true,
+            user_metadata: BTreeMap::new(),
         })
     }
 
@@ -134,6 +138,31 @@ impl<W: Write> Writer<W> {
         &self.schema
     }
 
+    /// Adds a user metadata entry to the file header.
+    ///
+    /// Keys in the `avro.` namespace are reserved and are rejected, as is
+    /// adding metadata after the header has been written.
+    pub fn add_user_metadata<K, V>(&mut self, key: K, value: V) -> Result<(), Error>
+    where
+        K: Into<String>,
+        V: AsRef<[u8]>,
+    {
+        let key = key.into();
+        if key.starts_with("avro.") {
+            return Err(
+                ValidationError::new(format!("metadata key {} is reserved for Avro", key)).into(),
+            );
+        }
+        if self.has_header {
+            return Err(ValidationError::new(
+                "metadata cannot be added after the header has been written",
+            )
+            .into());
+        }
+        self.user_metadata.insert(key, value.as_ref().to_vec());
+        Ok(())
+    }
+
     /// Append a compatible value (implementing the `ToAvro` trait) to a `Writer`, also performing
     /// schema validation.
     ///
@@ -143,14 +172,7 @@ impl<W: Write> Writer<W> {
     /// internal buffering for performance reasons. If you want to be sure the value has been
     /// written, then call [`flush`](struct.Writer.html#method.flush).
     pub fn append<T: ToAvro>(&mut self, value: T) -> Result<usize, Error> {
-        let n = if !self.has_header {
-            let header = self.header()?;
-            let n = self.append_bytes(header.as_ref())?;
-            self.has_header = true;
-            n
-        } else {
-            0
-        };
+        let n = self.append_header()?;
         let avro = value.avro();
         write_value_ref(&self.schema, &avro, &mut self.buffer)?;
 
@@ -171,14 +193,7 @@ impl<W: Write> Writer<W> {
     /// internal buffering for performance reasons. If you want to be sure the value has been
     /// written, then call [`flush`](struct.Writer.html#method.flush).
     pub fn append_value_ref(&mut self, value: &Value) -> Result<usize, Error> {
-        let n = if !self.has_header {
-            let header = self.header()?;
-            let n = self.append_bytes(header.as_ref())?;
-            self.has_header = true;
-            n
-        } else {
-            0
-        };
+        let n = self.append_header()?;
 
         write_value_ref(&self.schema, value, &mut self.buffer)?;
 
@@ -282,6 +297,38 @@ impl<W: Write> Writer<W> {
         self.writer
     }
 
+    /// Append the header to the payload, if it has not been appended yet.
+    fn append_header(&mut self) -> Result<usize, Error> {
+        if self.has_header {
+            return Ok(0);
+        }
+        let header = self.header()?;
+        let n = self.append_bytes(header.as_ref())?;
+        self.has_header = true;
+        Ok(n)
+    }
+
+    /// Append the remaining blocks of `reader` to the payload without
+    /// decompressing or decoding them.
+    ///
+    /// The blocks must have been written with the same schema and codec as
+    /// this `Writer`. Any values buffered by `append` must have been flushed.
+    fn append_raw_blocks<R: AvroRead>(&mut self, reader: Reader<R>) -> Result<usize, Error> {
+        let mut num_bytes = self.append_header()?;
+        let mut buf = Vec::new();
+        for block in reader.into_raw_blocks() {
+            let block = RawBlock {
+                marker: self.marker,
+                ..block?
+            };
+            buf.clear();
+            block.encode(&mut buf);
+            self.writer.write_all(&buf)?;
+            num_bytes += buf.len();
+        }
+        Ok(num_bytes)
+    }
+
     /// Generate and append synchronization marker to the payload.
     fn append_marker(&mut self) -> Result<usize, Error> {
         // using .writer.write directly to avoid mutable borrow of self
@@ -308,6 +355,9 @@ impl<W: Write> Writer<W> {
         if let Some(codec) = self.codec {
             metadata.insert("avro.codec", codec.avro());
         };
+        for (key, value) in &self.user_metadata {
+            metadata.insert(key, Value::Bytes(value.clone()));
+        }
 
         let mut header = Vec::new();
         header.extend_from_slice(AVRO_OBJECT_HEADER);
@@ -326,6 +376,51 @@ impl<W: Write> Writer<W> {
     }
 }
 
+/// Merges the Avro Object Container Files in `inputs` into a single file
+/// written to `writer`, returning `writer`.
+///
+/// The merged file has the schema and codec of the first input, and every
+/// other input must have the same schema and codec. The blocks of the inputs
+/// are copied without being decompressed or decoded, but are rewritten with
+/// the sync marker of the merged file. User metadata is not preserved.
+pub fn merge<W, R, I>(writer: W, inputs: I) -> Result<W, Error>
+where
+    W: Write,
+    R: AvroRead,
+    I: IntoIterator<Item = R>,
+{
+    let mut inputs = inputs.into_iter();
+    let first = match inputs.next() {
+        Some(input) => Reader::new(input)?,
+        None => return Err(ValidationError::new("no input files to merge").into()),
+    };
+    let codec = first.codec();
+    let fingerprint = first.writer_schema().fingerprint::<Sha256>().bytes;
+    let mut writer = Writer::with_codec(first.writer_schema().clone(), writer, codec);
+    writer.append_raw_blocks(first)?;
+    for (i, input) in inputs.enumerate() {
+        let reader = Reader::new(input)?;
+        if reader.writer_schema().fingerprint::<Sha256>().bytes != fingerprint {
+            return Err(ValidationError::new(format!(
+                "input {} has a different schema than input 0",
+                i + 1
+            ))
+            .into());
+        }
+        if reader.codec() != codec {
+            return Err(ValidationError::new(format!(
+                "input {} uses codec {:?}, but input 0 uses codec {:?}",
+                i + 1,
+                reader.codec(),
+                codec
+            ))
+            .into());
+        }
+        writer.append_raw_blocks(reader)?;
+    }
+    Ok(writer.into_inner())
+}
+
 /// Encode a compatible value (implementing the `ToAvro` trait) into Avro format, also performing
 /// schema validation.
 ///
@@ -406,6 +501,22 @@ mod tests {
         assert_eq!(to_avro_datum(&schema, record).unwrap(), expected);
     }
 
+    #[mz_ore::test]
+    fn test_to_avro_datum_mismatch() {
+        let schema = Schema::from_str(SCHEMA).unwrap();
+        let mut record = Record::new(schema.top_node()).unwrap();
+        record.put("a", "not a long");
+        record.put("b", "foo");
+        let value = record.avro();
+
+        let err = to_avro_datum(&schema, value.clone()).unwrap_err();
+        assert!(err.downcast_ref::<ValidationError>().is_some());
+
+        let mut buffer = vec![];
+        assert!(write_avro_datum(&schema, value, &mut buffer).is_err());
+        assert!(buffer.is_empty());
+    }
+
     #[mz_ore::test]
     fn test_union() {
         let schema = Schema::from_str(UNION_SCHEMA).unwrap();
@@ -532,6 +643,40 @@ mod tests {
         b: String,
     }
 
+    #[mz_ore::test]
+    #[cfg_attr(miri, ignore)] // slow
+    fn test_writer_extend_many() {
+        let schema = Schema::from_str(SCHEMA).unwrap();
+        let make_record = |a: i64| {
+            let mut record = Record::new(schema.top_node()).unwrap();
+            record.put("a", a);
+            record.put("b", format!("{a:0>32}"));
+            record.avro()
+        };
+
+        let mut writer = Writer::new(schema.clone(), Vec::new());
+        writer.extend((0..1000).map(make_record)).unwrap();
+        let result = writer.into_inner();
+
+        // The values were flushed in several blocks as they were appended...
+        let blocks = Reader::new(&result[..]).unwrap().into_raw_blocks();
+        assert!(blocks.count() > 1);
+
+        // ...and read back in order.
+        let actual: Result<Vec<_>, _> = Reader::new(&result[..]).unwrap().collect();
+        let expected: Vec<_> = (0..1000).map(make_record).collect();
+        assert_eq!(actual.unwrap(), expected);
+
+        // The first invalid value stops the extension.
+        let mut writer = Writer::new(schema.clone(), Vec::new());
+        let values = [make_record(0), Value::Long(1), make_record(2)];
+        let err = writer.extend(values).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Validation error: value does not match schema"
+        );
+    }
+
     #[mz_ore::test]
     #[cfg_attr(miri, ignore)] // unsupported operation: can't call foreign function `deflateInit2_` on OS `linux`
     fn test_writer_with_codec() {
@@ -635,4 +780,154 @@ mod tests {
             actual
         );
     }
+
+    #[mz_ore::test]
+    #[cfg_attr(miri, ignore)] // slow
+    fn test_merge() {
+        let schema = Schema::from_str(SCHEMA).unwrap();
+        let make_record = |a: i64, b| {
+            let mut record = Record::new(schema.top_node()).unwrap();
+            record.put("a", a);
+            record.put("b", b);
+            record.avro()
+        };
+        let write_file = |codec, records: Vec<Value>| {
+            let mut writer = Writer::with_codec(schema.clone(), Vec::new(), codec);
+            for record in records {
+                writer.append(record).unwrap();
+                writer.flush().unwrap();
+            }
+            writer.into_inner()
+        };
+
+        let file1 = write_file(
+            Codec::Deflate,
+            vec![make_record(27, "foo"), make_record(54, "bar")],
+        );
+        let file2 = write_file(Codec::Deflate, vec![make_record(42, "baz")]);
+        let merged = merge(Vec::new(), [&file1[..], &file2[..]]).unwrap();
+
+        // The merged file reads back as the concatenation of the inputs,
+        // despite each input having its own sync marker.
+        let reader = Reader::new(&merged[..]).unwrap();
+        assert_eq!(reader.codec(), Codec::Deflate);
+        let actual: Result<Vec<_>, _> = reader.collect();
+        assert_eq!(
+            actual.unwrap(),
+            vec![
+                make_record(27, "foo"),
+                make_record(54, "bar"),
+                make_record(42, "baz")
+            ]
+        );
+
+        // Inputs must agree on the codec...
+        let file3 = write_file(Codec::Null, vec![make_record(84, "zar")]);
+        let err = merge(Vec::new(), [&file1[..], &file3[..]]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Validation error: input 1 uses codec Null, but input 0 uses codec Deflate"
+        );
+
+        // ...and on the schema.
+        let other_schema = Schema::from_str(r#""long""#).unwrap();
+        let mut writer = Writer::with_codec(other_schema, Vec::new(), Codec::Deflate);
+        writer.append(1i64).unwrap();
+        writer.flush().unwrap();
+        let file4 = writer.into_inner();
+        let err = merge(Vec::new(), [&file1[..], &file2[..], &file4[..]]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Validation error: input 2 has a different schema than input 0"
+        );
+
+        let err = merge(Vec::new(), Vec::<&[u8]>::new()).unwrap_err();
+        assert_eq!(err.to_string(), "Validation error: no input files to merge");
+    }
+
+    #[mz_ore::test]
+    fn test_writer_default_for_missing() {
+        let schema = Schema::from_str(
+            r#"
+            {
+                "type": "record",
+                "name": "test",
+                "fields": [
+                    {"name": "a", "type": "long"},
+                    {"name": "b", "type": ["null", "string"], "default": null}
+                ]
+            }
+        "#,
+        )
+        .unwrap();
+
+        let mut record = Record::new(schema.top_node()).unwrap();
+        record.put("a", 27i64);
+
+        // Without defaults, the missing field does not match its union schema.
+        let mut writer = Writer::new(schema.clone(), Vec::new());
+        assert!(writer.append(record.clone()).is_err());
+
+        record.put_default_for_missing();
+        let mut writer = Writer::new(schema.clone(), Vec::new());
+        writer.append(record).unwrap();
+        writer.flush().unwrap();
+        let buf = writer.into_inner();
+
+        let reader = Reader::new(&buf[..]).unwrap();
+        let actual: Result<Vec<_>, _> = reader.collect();
+        assert_eq!(
+            actual.unwrap(),
+            vec![Value::Record(vec![
+                ("a".into(), Value::Long(27)),
+                (
+                    "b".into(),
+                    Value::Union {
+                        index: 0,
+                        inner: Box::new(Value::Null),
+                        n_variants: 2,
+                        null_variant: Some(0),
+                    }
+                ),
+            ])]
+        );
+    }
+
+    #[mz_ore::test]
+    fn test_writer_default_for_missing_null_not_first() {
+        let schema = Schema::from_str(
+            r#"
+            {
+                "type": "record",
+                "name": "test",
+                "fields": [
+                    {"name": "a", "type": ["string", "null"], "default": null}
+                ]
+            }
+        "#,
+        )
+        .unwrap();
+
+        let mut record = Record::new(schema.top_node()).unwrap();
+        record.put_default_for_missing();
+        let mut writer = Writer::new(schema.clone(), Vec::new());
+        writer.append(record).unwrap();
+        writer.flush().unwrap();
+        let buf = writer.into_inner();
+
+        let reader = Reader::new(&buf[..]).unwrap();
+        let actual: Result<Vec<_>, _> = reader.collect();
+        assert_eq!(
+            actual.unwrap(),
+            vec![Value::Record(vec![(
+                "a".into(),
+                Value::Union {
+                    index: 1,
+                    inner: Box::new(Value::Null),
+                    n_variants: 2,
+                    null_variant: Some(1),
+                }
+            )])]
+        );
+    }
 }
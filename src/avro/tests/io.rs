@@ -138,6 +138,32 @@ static SCHEMAS_TO_VALIDATE: Lazy<Vec<(&'static str, Value)>> = Lazy::new(|| {
                 .into(),
             ),
         ),
+        (
+            r#"{"type": "map", "values": {"type": "record", "name": "Test", "fields": [{"name": "f", "type": "long"}]}}"#,
+            Value::Map(
+                [
+                    (
+                        "a".to_string(),
+                        Value::Record(vec![("f".to_string(), Value::Long(1))]),
+                    ),
+                    (
+                        "b".to_string(),
+                        Value::Record(vec![("f".to_string(), Value::Long(2))]),
+                    ),
+                ]
+                .into(),
+            ),
+        ),
+        (
+            r#"{"type": "map", "values": ["null", "long"]}"#,
+            Value::Map(
+                [
+                    ("a".to_string(), nullable_long(Some(1))),
+                    ("b".to_string(), nullable_long(None)),
+                ]
+                .into(),
+            ),
+        ),
         (
             r#"["string", "null", "long"]"#,
             Value::Union {
@@ -154,6 +180,20 @@ static SCHEMAS_TO_VALIDATE: Lazy<Vec<(&'static str, Value)>> = Lazy::new(|| {
     ]
 });
 
+/// Returns a value of the union schema `["null", "long"]`.
+fn nullable_long(value: Option<i64>) -> Value {
+    let (index, inner) = match value {
+        None => (0, Value::Null),
+        Some(value) => (1, Value::Long(value)),
+    };
+    Value::Union {
+        index,
+        inner: Box::new(inner),
+        n_variants: 2,
+        null_variant: Some(0),
+    }
+}
+
 static BINARY_ENCODINGS: Lazy<Vec<(i64, Vec<u8>)>> = Lazy::new(|| {
     vec![
         (0, vec![0x00]),
@@ -261,6 +301,33 @@ fn test_round_trip() {
     }
 }
 
+#[mz_ore::test]
+fn test_map_block_framing() {
+    let schema = Schema::from_str(r#"{"type": "map", "values": ["null", "long"]}"#).unwrap();
+    let encoded = vec![
+        // A block with a negative count of -2 and a byte size of 7...
+        0x03, 0x0e, //
+        // ...containing "a" => 5...
+        0x02, b'a', 0x02, 0x0a, //
+        // ...and "b" => null.
+        0x02, b'b', 0x00, //
+        // A block with a count of 1 containing "c" => 2.
+        0x02, 0x02, b'c', 0x02, 0x04, //
+        // The terminating empty block.
+        0x00,
+    ];
+    let decoded = from_avro_datum(&schema, &mut Cursor::new(encoded)).unwrap();
+    let expected = Value::Map(
+        [
+            ("a".to_string(), nullable_long(Some(5))),
+            ("b".to_string(), nullable_long(None)),
+            ("c".to_string(), nullable_long(Some(2))),
+        ]
+        .into(),
+    );
+    assert_eq!(decoded, expected);
+}
+
 #[mz_ore::test]
 fn test_binary_int_encoding() {
     for (number, hex_encoding) in BINARY_ENCODINGS.iter() {
@@ -94,6 +94,7 @@ use std::collections::BTreeMap;
 use std::str::FromStr;
 
 use chrono::NaiveDateTime;
+use mz_avro::schema::DEFAULT_MAX_SCHEMA_DEPTH;
 use mz_avro::types::{DecimalValue, Value};
 use mz_avro::Schema;
 use once_cell::sync::Lazy;
@@ -558,6 +559,78 @@ fn test_unparseable_logical_types() {
     }
 }
 
+#[mz_ore::test]
+fn test_schema_max_depth() {
+    // Returns a schema nested `depth` levels deep.
+    fn nested_schema(depth: usize) -> String {
+        let mut schema = r#""int""#.to_string();
+        for _ in 1..depth {
+            schema = format!(r#"{{"type": "array", "items": {}}}"#, schema);
+        }
+        schema
+    }
+
+    Schema::from_str(&nested_schema(DEFAULT_MAX_SCHEMA_DEPTH)).unwrap();
+    let err = Schema::from_str(&nested_schema(DEFAULT_MAX_SCHEMA_DEPTH + 1)).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "Schema parse error: Schema is nested more than 64 levels deep (at {})",
+            vec!["items"; DEFAULT_MAX_SCHEMA_DEPTH].join(".")
+        )
+    );
+
+    let value = serde_json::from_str(&nested_schema(4)).unwrap();
+    Schema::parse_with_max_depth(&value, 4).unwrap();
+    assert!(Schema::parse_with_max_depth(&value, 3).is_err());
+}
+
+#[mz_ore::test]
+fn test_schema_parse_error_path() {
+    let err = Schema::from_str(
+        r#"{
+            "type": "record",
+            "name": "r",
+            "fields": [
+                {"name": "a", "type": "int"},
+                {"name": "b", "type": "int"},
+                {
+                    "name": "c",
+                    "type": {
+                        "type": "array",
+                        "items": {"type": "map", "values": ["null", "bogus"]}
+                    }
+                }
+            ]
+        }"#,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Schema parse error: Unknown type: bogus (at fields[2].type.items.values[1])"
+    );
+
+    let err = Schema::from_str(
+        r#"{
+            "type": "record",
+            "name": "r",
+            "fields": [
+                {"name": "a", "type": "int"},
+                {"name": "b"}
+            ]
+        }"#,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Schema parse error: No `type` in record field (at fields[1])"
+    );
+
+    // Errors in the top-level schema have no path.
+    let err = Schema::from_str(r#""bogus""#).unwrap_err();
+    assert_eq!(err.to_string(), "Schema parse error: Unknown type: bogus");
+}
+
 #[mz_ore::test]
 fn test_valid_schemas() {
     for (raw_schema, value) in VALID_SCHEMAS.iter() {
@@ -44,6 +44,9 @@ pub struct ClusterConfig {
     pub persist_clients: Arc<PersistClientCache>,
     /// A process-global handle to tracing configuration.
     pub tracing_handle: Arc<TracingHandle>,
+    /// The CPU cores to which to pin the timely workers of this process, one
+    /// per worker. If unset, workers are not pinned.
+    pub worker_core_affinity: Option<Vec<usize>>,
 }
 
 /// A client managing access to the local portion of a Timely cluster
@@ -61,6 +64,8 @@ where
     tokio_handle: tokio::runtime::Handle,
     /// A process-global handle to tracing configuration.
     tracing_handle: Arc<TracingHandle>,
+    /// The CPU cores to which to pin the timely workers, if any.
+    worker_core_affinity: Option<Vec<usize>>,
     worker: Worker,
 }
 
@@ -112,6 +117,7 @@ where
                 Arc::clone(&config.persist_clients),
                 tokio_executor.clone(),
                 Arc::clone(&config.tracing_handle),
+                config.worker_core_affinity.clone(),
                 worker_config,
             );
             let client = Box::new(client);
@@ -134,6 +140,7 @@ where
         persist_clients: Arc<PersistClientCache>,
         tokio_handle: tokio::runtime::Handle,
         tracing_handle: Arc<TracingHandle>,
+        worker_core_affinity: Option<Vec<usize>>,
         worker_config: Worker,
     ) -> Self {
         Self {
@@ -142,6 +149,7 @@ where
             persist_clients,
             tokio_handle,
             tracing_handle,
+            worker_core_affinity,
             worker: worker_config,
         }
     }
@@ -152,9 +160,11 @@ where
         epoch: ClusterStartupEpoch,
         persist_clients: Arc<PersistClientCache>,
         tracing_handle: Arc<TracingHandle>,
+        worker_core_affinity: Option<Vec<usize>>,
         tokio_executor: Handle,
     ) -> Result<TimelyContainer<C, R, Worker::Activatable>, Error> {
         info!("Building timely container with config {config:?}");
+        validate_worker_core_affinity(worker_core_affinity.as_deref(), config.workers)?;
         let (client_txs, client_rxs): (Vec<_>, Vec<_>) = (0..config.workers)
             .map(|_| crossbeam_channel::unbounded())
             .unzip();
@@ -178,6 +188,15 @@ where
 
         let worker_guards = execute_from(builders, other, worker_config, move |timely_worker| {
             let timely_worker_index = timely_worker.index();
+            if let Some(cores) = &worker_core_affinity {
+                let core = cores[timely_worker_index % config.workers];
+                if let Err(e) = pin_current_thread(core) {
+                    warn!(
+                        "unable to pin timely worker {timely_worker_index} to core {core}: {}",
+                        e.display_with_causes()
+                    );
+                }
+            }
             let _tokio_guard = tokio_executor.enter();
             let client_rx = client_rxs.lock().unwrap()[timely_worker_index % config.workers]
                 .take()
@@ -218,6 +237,7 @@ where
         let persist_clients = Arc::clone(&self.persist_clients);
         let handle = self.tokio_handle.clone();
         let tracing_handle = Arc::clone(&self.tracing_handle);
+        let worker_core_affinity = self.worker_core_affinity.clone();
 
         let worker_config = self.worker.clone();
         let mut timely_lock = self.timely_container.lock().await;
@@ -240,6 +260,7 @@ where
                     epoch,
                     persist_clients,
                     tracing_handle,
+                    worker_core_affinity,
                     handle,
                 )
                 .await;
@@ -282,6 +303,40 @@ where
     }
 }
 
+/// Validates that `cores`, if specified, names one core for each of the
+/// `workers` timely workers.
+fn validate_worker_core_affinity(cores: Option<&[usize]>, workers: usize) -> Result<(), Error> {
+    match cores {
+        Some(cores) if cores.len() != workers => Err(anyhow!(
+            "worker core affinity specifies {} cores, but the cluster has {} workers",
+            cores.len(),
+            workers
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Pins the calling thread to the CPU core with index `core`.
+#[cfg(target_os = "linux")]
+fn pin_current_thread(core: usize) -> Result<(), Error> {
+    use nix::sched::{sched_setaffinity, CpuSet};
+    use nix::unistd::Pid;
+
+    let mut cpu_set = CpuSet::new();
+    cpu_set.set(core)?;
+    // A PID of zero refers to the calling thread.
+    sched_setaffinity(Pid::from_raw(0), &cpu_set)?;
+    Ok(())
+}
+
+/// Pins the calling thread to the CPU core with index `core`.
+#[cfg(not(target_os = "linux"))]
+fn pin_current_thread(_core: usize) -> Result<(), Error> {
+    Err(anyhow!(
+        "pinning threads to cores is only supported on Linux"
+    ))
+}
+
 impl<Client: Debug, Worker: crate::types::AsRunnableWorker<C, R>, C, R> Debug
     for ClusterClient<Client, Worker, C, R>
 {
@@ -320,3 +375,20 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::validate_worker_core_affinity;
+
+    #[mz_ore::test]
+    fn test_validate_worker_core_affinity() {
+        assert!(validate_worker_core_affinity(None, 4).is_ok());
+        assert!(validate_worker_core_affinity(Some(&[0, 2, 4, 6]), 4).is_ok());
+
+        let err = validate_worker_core_affinity(Some(&[0, 1]), 4).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "worker core affinity specifies 2 cores, but the cluster has 4 workers"
+        );
+    }
+}
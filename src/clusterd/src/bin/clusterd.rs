@@ -178,6 +178,17 @@ struct Args {
     /// A scratch directory that can be used for ephemeral storage.
     #[clap(long, env = "SCRATCH_DIRECTORY", value_name = "PATH")]
     scratch_directory: Option<PathBuf>,
+    /// The CPU cores to which to pin the timely workers, as a comma-separated
+    /// list with one core per worker. Only supported on Linux.
+    ///
+    /// The storage and compute servers each pin their workers to these cores.
+    #[clap(
+        long,
+        env = "WORKER_CORE_AFFINITY",
+        value_name = "CORE,...",
+        use_value_delimiter = true
+    )]
+    worker_core_affinity: Option<Vec<usize>>,
 }
 
 #[tokio::main]
@@ -315,6 +326,7 @@ async fn run(args: Args) -> Result<(), anyhow::Error> {
             metrics_registry: metrics_registry.clone(),
             persist_clients: Arc::clone(&persist_clients),
             tracing_handle: Arc::clone(&tracing_handle),
+            worker_core_affinity: args.worker_core_affinity.clone(),
         },
         SYSTEM_TIME.clone(),
         ConnectionContext::from_cli_args(
@@ -344,6 +356,7 @@ async fn run(args: Args) -> Result<(), anyhow::Error> {
             metrics_registry,
             persist_clients,
             tracing_handle,
+            worker_core_affinity: args.worker_core_affinity,
         })?;
     info!(
         "listening for compute controller connections on {}",
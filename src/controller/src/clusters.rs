@@ -26,7 +26,7 @@ use mz_compute_client::logging::LogVariant;
 use mz_compute_client::service::{ComputeClient, ComputeGrpcClient};
 use mz_orchestrator::{
     CpuLimit, DiskLimit, LabelSelectionLogic, LabelSelector, MemoryLimit, Service, ServiceConfig,
-    ServiceEvent, ServicePort,
+    ServiceEvent, ServicePort, ServiceType,
 };
 use mz_ore::halt;
 use mz_ore::task::{AbortOnDropHandle, JoinHandleExt};
@@ -36,7 +36,7 @@ use once_cell::sync::Lazy;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use timely::progress::Timestamp;
-use tracing::{error, warn};
+use tracing::warn;
 
 use crate::Controller;
 
@@ -455,7 +455,7 @@ where
                 match event {
                     Ok(event) => Some(event),
                     Err(error) => {
-                        error!("service watch error: {error}");
+                        warn!("service watch error: {error}");
                         None
                     }
                 }
@@ -501,6 +501,7 @@ where
                             format!("--persist-pubsub-url={}", persist_pubsub_url),
                         ]
                     },
+                    env: vec![],
                     ports: vec![
                         ServicePort {
                             name: "storagectl".into(),
@@ -538,6 +539,7 @@ where
                         ("workers".into(), location.allocation.workers.to_string()),
                         ("size".into(), location.size.to_string()),
                     ]),
+                    pod_labels: BTreeMap::new(),
                     availability_zone: Some(location.availability_zone),
                     // This constrains the orchestrator (for those orchestrators that support
                     // anti-affinity, today just k8s) to never schedule pods for different replicas
@@ -561,6 +563,13 @@ where
                             },
                         },
                     ]),
+                    disruption_budget: None,
+                    sidecars: vec![],
+                    metrics_port: None,
+                    metrics_path: None,
+                    scratch_volume: None,
+                    service_type: ServiceType::default(),
+                    headless: false,
                     disk_limit: location.allocation.disk_limit,
                     disk: location.disk,
                 },
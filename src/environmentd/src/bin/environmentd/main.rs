@@ -78,6 +78,7 @@
 //! It listens for SQL connections on port 6875 (MTRL) and for HTTP connections
 //! on port 6876.
 
+use std::collections::BTreeMap;
 use std::ffi::CStr;
 use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::path::PathBuf;
@@ -96,13 +97,14 @@ use mz_adapter::catalog::ClusterReplicaSizeMap;
 use mz_build_info::BuildInfo;
 use mz_cloud_resources::{AwsExternalIdPrefix, CloudResourceController};
 use mz_controller::ControllerConfig;
-use mz_environmentd::{Listeners, ListenersConfig, TlsConfig, BUILD_INFO};
+use mz_environmentd::{Listeners, ListenersConfig, TlsConfig, TlsMaterial, BUILD_INFO};
 use mz_frontegg_auth::{
     Authentication as FronteggAuthentication, AuthenticationConfig as FronteggConfig,
 };
 use mz_orchestrator::Orchestrator;
 use mz_orchestrator_kubernetes::{
-    KubernetesImagePullPolicy, KubernetesOrchestrator, KubernetesOrchestratorConfig,
+    KubernetesContainerSecurityContext, KubernetesImagePullPolicy, KubernetesOrchestrator,
+    KubernetesOrchestratorConfig, KubernetesPodSecurityContext, KubernetesUpdateStrategy,
 };
 use mz_orchestrator_process::{
     ProcessOrchestrator, ProcessOrchestratorConfig, ProcessOrchestratorTcpProxyConfig,
@@ -231,8 +233,13 @@ pub struct Args {
     /// "*" allows all.
     /// "*.domain.com" allows connections from any matching subdomain.
     ///
-    /// Wildcards in other positions (e.g., "https://*.foo.com" or "https://foo.*.com") have no effect.
-    #[structopt(long, env = "CORS_ALLOWED_ORIGIN")]
+    /// Wildcards in other positions (e.g., "https://*.foo.com" or "https://foo.*.com") are
+    /// rejected, as are origins that include a path.
+    #[structopt(
+        long,
+        env = "CORS_ALLOWED_ORIGIN",
+        parse(try_from_str = mz_http_util::parse_cors_allowed_origin)
+    )]
     cors_allowed_origin: Vec<HeaderValue>,
     /// How stringently to demand TLS authentication and encryption.
     ///
@@ -270,6 +277,11 @@ pub struct Args {
         value_name = "PATH"
     )]
     tls_key: Option<PathBuf>,
+    /// Whether the internal HTTP server, which serves metrics, should also
+    /// require TLS, using the certificate and key specified by `--tls-cert`
+    /// and `--tls-key`. Has no effect if TLS is disabled.
+    #[clap(long, env = "INTERNAL_HTTP_TLS")]
+    internal_http_tls: bool,
     /// Enables Frontegg authentication for the specified tenant ID.
     #[clap(
         long,
@@ -288,6 +300,22 @@ pub struct Args {
     /// The name of the admin role in Frontegg.
     #[clap(long, env = "FRONTEGG_ADMIN_ROLE", requires = "frontegg-tenant")]
     frontegg_admin_role: Option<String>,
+    /// How long a SQL connection may go without receiving a message from its
+    /// client before it is closed. By default, connections never time out.
+    #[clap(
+        long,
+        env = "IDLE_CONNECTION_TIMEOUT",
+        parse(try_from_str = humantime::parse_duration)
+    )]
+    idle_connection_timeout: Option<Duration>,
+    /// How long a SQL statement may take to execute before a warning is
+    /// logged about it. By default, slow statements are not logged.
+    #[clap(
+        long,
+        env = "SLOW_QUERY_THRESHOLD",
+        parse(try_from_str = humantime::parse_duration)
+    )]
+    slow_query_threshold: Option<Duration>,
 
     // === Orchestrator options. ===
     /// The service orchestrator implementation to use.
@@ -296,6 +324,10 @@ pub struct Args {
     /// Name of a non-default Kubernetes scheduler, if any.
     #[structopt(long, env = "ORCHESTRATOR_KUBERNETES_SCHEDULER_NAME")]
     orchestrator_kubernetes_scheduler_name: Option<String>,
+    /// Name of the Kubernetes PriorityClass to assign to the pods of services,
+    /// if any.
+    #[clap(long, env = "ORCHESTRATOR_KUBERNETES_PRIORITY_CLASS_NAME")]
+    orchestrator_kubernetes_priority_class_name: Option<String>,
     /// Labels to apply to all services created by the Kubernetes orchestrator
     /// in the form `KEY=VALUE`.
     #[structopt(long, env = "ORCHESTRATOR_KUBERNETES_SERVICE_LABEL")]
@@ -340,6 +372,65 @@ pub struct Args {
     /// The optional fs group for service's pods' `securityContext`.
     #[clap(long, env = "ORCHESTRATOR_KUBERNETES_SERVICE_FS_GROUP")]
     orchestrator_kubernetes_service_fs_group: Option<i64>,
+    /// Require the containers of services created by the Kubernetes
+    /// orchestrator to run as a non-root user.
+    #[clap(long, env = "ORCHESTRATOR_KUBERNETES_RUN_AS_NON_ROOT")]
+    orchestrator_kubernetes_run_as_non_root: bool,
+    /// The UID to run the containers of services created by the Kubernetes
+    /// orchestrator as, if not the default specified by their images.
+    #[clap(long, env = "ORCHESTRATOR_KUBERNETES_RUN_AS_USER")]
+    orchestrator_kubernetes_run_as_user: Option<i64>,
+    /// The GID to run the containers of services created by the Kubernetes
+    /// orchestrator as, if not the default of the container runtime.
+    #[clap(long, env = "ORCHESTRATOR_KUBERNETES_RUN_AS_GROUP")]
+    orchestrator_kubernetes_run_as_group: Option<i64>,
+    /// Mount the root filesystem of the containers of services created by
+    /// the Kubernetes orchestrator as read-only.
+    #[clap(long, env = "ORCHESTRATOR_KUBERNETES_READ_ONLY_ROOT_FILESYSTEM")]
+    orchestrator_kubernetes_read_only_root_filesystem: bool,
+    /// A Linux capability to drop from the containers of services created by
+    /// the Kubernetes orchestrator, e.g., `ALL`.
+    #[clap(long, env = "ORCHESTRATOR_KUBERNETES_DROP_CAPABILITY")]
+    orchestrator_kubernetes_drop_capability: Vec<String>,
+    /// The strategy to use when replacing the pods of services whose
+    /// configuration has changed, in the form `rolling-update`,
+    /// `rolling-update:PARTITION`, or `on-delete`.
+    #[clap(
+        long,
+        env = "ORCHESTRATOR_KUBERNETES_UPDATE_STRATEGY",
+        default_value = "rolling-update"
+    )]
+    orchestrator_kubernetes_update_strategy: KubernetesUpdateStrategy,
+    /// A template for the externally routable address of each process of a
+    /// service, for use when the cluster-internal DNS names of services are
+    /// not reachable by their peers.
+    ///
+    /// The placeholders `{name}`, `{index}`, and `{namespace}` are replaced
+    /// with the name of the service, the index of the process, and the
+    /// Kubernetes namespace, respectively.
+    #[clap(long, env = "ORCHESTRATOR_KUBERNETES_ANNOUNCE_ADDRESS")]
+    orchestrator_kubernetes_announce_address: Option<String>,
+    /// The DNS policy to set on the pods of services created by the
+    /// Kubernetes orchestrator, if not the Kubernetes default.
+    #[clap(long, env = "ORCHESTRATOR_KUBERNETES_DNS_POLICY")]
+    orchestrator_kubernetes_dns_policy: Option<String>,
+    /// Additional `/etc/hosts` entries for the pods of services created by
+    /// the Kubernetes orchestrator in the form `IP=HOSTNAME`.
+    #[clap(long, env = "ORCHESTRATOR_KUBERNETES_HOST_ALIAS")]
+    orchestrator_kubernetes_host_alias: Vec<KeyValueArg<String, String>>,
+    /// The topology key across which the Kubernetes orchestrator spreads the
+    /// pods of services with anti-affinity. Defaults to
+    /// `kubernetes.io/hostname`.
+    #[clap(long, env = "ORCHESTRATOR_KUBERNETES_ANTI_AFFINITY_TOPOLOGY_KEY")]
+    orchestrator_kubernetes_anti_affinity_topology_key: Option<String>,
+    /// The field manager to identify as when applying Kubernetes objects.
+    /// Defaults to `environmentd`.
+    #[clap(long, env = "ORCHESTRATOR_KUBERNETES_FIELD_MANAGER")]
+    orchestrator_kubernetes_field_manager: Option<String>,
+    /// Log the Kubernetes objects that would be applied for each service
+    /// instead of applying them.
+    #[clap(long, env = "ORCHESTRATOR_KUBERNETES_DRY_RUN")]
+    orchestrator_kubernetes_dry_run: bool,
     #[clap(long, env = "ORCHESTRATOR_PROCESS_WRAPPER")]
     orchestrator_process_wrapper: Option<String>,
     /// Where the process orchestrator should store secrets.
@@ -411,6 +502,14 @@ pub struct Args {
     /// granting attributes and privileges to some default role.
     #[clap(long, env = "BOOTSTRAP_ROLE")]
     bootstrap_role: Option<String>,
+    /// A file of SQL statements to execute, as the system user, when the
+    /// catalog is freshly initialized, e.g., to seed a new environment with
+    /// views and sources.
+    ///
+    /// The statements are not executed when restarting against an existing
+    /// catalog. If any statement fails, startup is aborted.
+    #[clap(long, env = "BOOTSTRAP_SQL", value_name = "PATH")]
+    bootstrap_sql: Option<PathBuf>,
 
     // === Storage options. ===
     /// Where the persist library should store its blob data.
@@ -502,6 +601,18 @@ pub struct Args {
     /// An API key for Segment. Enables export of audit events to Segment.
     #[clap(long, env = "SEGMENT_API_KEY")]
     segment_api_key: Option<String>,
+    /// The Segment-compatible API host to send events to, if not Segment's
+    /// own (e.g., `https://events.example.com`).
+    #[clap(long, env = "SEGMENT_API_HOST", requires = "segment-api-key")]
+    segment_api_host: Option<String>,
+    /// Suppress the periodic telemetry reports sent to Segment, even if a
+    /// Segment API key is configured. Audit events are still exported.
+    #[clap(long, env = "DISABLE_TELEMETRY_REPORTING")]
+    disable_telemetry_reporting: bool,
+    /// Run without contacting any external reporting or configuration
+    /// services. Overrides `--segment-api-key` and `--launchdarkly-sdk-key`.
+    #[clap(long, env = "AIR_GAPPED")]
+    air_gapped: bool,
     /// Public IP addresses which the cloud environment has configured for
     /// egress
     #[clap(
@@ -661,7 +772,10 @@ fn run(mut args: Args) -> Result<(), anyhow::Error> {
     } else {
         let cert = args.tls_cert.unwrap();
         let key = args.tls_key.unwrap();
-        Some(TlsConfig { cert, key })
+        Some(TlsConfig {
+            cert: TlsMaterial::File(cert),
+            key: TlsMaterial::File(key),
+        })
     };
     let frontegg = match (
         args.frontegg_tenant,
@@ -721,6 +835,7 @@ fn run(mut args: Args) -> Result<(), anyhow::Error> {
                     .block_on(KubernetesOrchestrator::new(KubernetesOrchestratorConfig {
                         context: args.orchestrator_kubernetes_context.clone(),
                         scheduler_name: args.orchestrator_kubernetes_scheduler_name,
+                        priority_class_name: args.orchestrator_kubernetes_priority_class_name,
                         service_labels: args
                             .orchestrator_kubernetes_service_label
                             .into_iter()
@@ -739,6 +854,30 @@ fn run(mut args: Args) -> Result<(), anyhow::Error> {
                             .orchestrator_kubernetes_ephemeral_volume_class
                             .clone(),
                         service_fs_group: args.orchestrator_kubernetes_service_fs_group.clone(),
+                        pod_security_context: KubernetesPodSecurityContext {
+                            run_as_non_root: args.orchestrator_kubernetes_run_as_non_root,
+                            run_as_user: args.orchestrator_kubernetes_run_as_user,
+                            run_as_group: args.orchestrator_kubernetes_run_as_group,
+                        },
+                        container_security_context: KubernetesContainerSecurityContext {
+                            read_only_root_filesystem: args
+                                .orchestrator_kubernetes_read_only_root_filesystem,
+                            drop_capabilities: args.orchestrator_kubernetes_drop_capability,
+                        },
+                        update_strategy: args.orchestrator_kubernetes_update_strategy,
+                        announce_address: args.orchestrator_kubernetes_announce_address,
+                        dns_policy: args.orchestrator_kubernetes_dns_policy,
+                        host_aliases: {
+                            let mut aliases: BTreeMap<String, Vec<String>> = BTreeMap::new();
+                            for alias in args.orchestrator_kubernetes_host_alias {
+                                aliases.entry(alias.key).or_default().push(alias.value);
+                            }
+                            aliases.into_iter().collect()
+                        },
+                        anti_affinity_topology_key: args
+                            .orchestrator_kubernetes_anti_affinity_topology_key,
+                        field_manager: args.orchestrator_kubernetes_field_manager,
+                        dry_run: args.orchestrator_kubernetes_dry_run,
                     }))
                     .context("creating kubernetes orchestrator")?,
             );
@@ -879,7 +1018,10 @@ fn run(mut args: Args) -> Result<(), anyhow::Error> {
         listeners
             .serve(mz_environmentd::Config {
                 tls,
+                internal_http_tls: args.internal_http_tls,
                 frontegg,
+                idle_connection_timeout: args.idle_connection_timeout,
+                slow_query_threshold: args.slow_query_threshold,
                 cors_allowed_origin,
                 adapter_stash_url: args.adapter_stash_url,
                 controller,
@@ -909,6 +1051,9 @@ fn run(mut args: Args) -> Result<(), anyhow::Error> {
                 storage_usage_collection_interval: args.storage_usage_collection_interval_sec,
                 storage_usage_retention_period: args.storage_usage_retention_period,
                 segment_api_key: args.segment_api_key,
+                segment_api_host: args.segment_api_host,
+                disable_telemetry_reporting: args.disable_telemetry_reporting,
+                air_gapped: args.air_gapped,
                 egress_ips: args.announce_egress_ip,
                 aws_account_id: args.aws_account_id,
                 aws_privatelink_availability_zones: args.aws_privatelink_availability_zones,
@@ -920,6 +1065,7 @@ fn run(mut args: Args) -> Result<(), anyhow::Error> {
                     .collect(),
                 config_sync_loop_interval: args.config_sync_loop_interval,
                 bootstrap_role: args.bootstrap_role,
+                bootstrap_sql: args.bootstrap_sql,
                 deploy_generation: args.deploy_generation,
             })
             .await
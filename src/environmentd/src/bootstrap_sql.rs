@@ -0,0 +1,131 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+//! Execution of a SQL file when a fresh catalog is bootstrapped.
+//!
+//! Operators can use the bootstrap SQL file to seed a new environment with
+//! objects like views and sources. The statements in the file are executed
+//! once, as the system user, immediately after the catalog is initialized.
+//!
+//! DDL cannot run inside a multi-statement transaction, so each statement is
+//! committed on its own. To avoid leaving an environment half-seeded, the
+//! catalog durably tracks the index of the next statement to execute in the
+//! `bootstrap_sql_next_statement` system variable. It is set to `0` atomically
+//! with the creation of a fresh catalog, advanced after each statement
+//! commits, and cleared once the whole file has been applied. If a statement
+//! fails, startup fails, and the next restart resumes from the failed
+//! statement. A crash between a statement committing and the index advancing
+//! will cause that one statement to be re-executed on restart.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{bail, Context};
+use mz_adapter::session::EndTransactionAction;
+use mz_adapter::{ExecuteResponse, PeekResponseUnary};
+use mz_sql::session::user::SYSTEM_USER;
+use mz_sql::session::vars::{Var, BOOTSTRAP_SQL_NEXT_STATEMENT};
+use tracing::info;
+
+/// Executes each pending statement in the SQL file at `path`, in order, in its
+/// own transaction.
+///
+/// Does nothing if the catalog records that the file has already been applied.
+/// Returns an error describing the failing statement if any statement fails.
+/// Statements that precede the failing statement remain committed and are not
+/// executed again when this function is next called.
+pub async fn execute(
+    adapter_client: &mz_adapter::Client,
+    path: &Path,
+) -> Result<(), anyhow::Error> {
+    let conn_id = adapter_client.new_conn_id()?;
+    let session = adapter_client.new_session(conn_id, SYSTEM_USER.clone());
+    let (mut session_client, _) = adapter_client.startup(session).await?;
+
+    let next_statement = session_client
+        .get_system_vars()
+        .await?
+        .into_iter()
+        .find(|(name, _)| name == BOOTSTRAP_SQL_NEXT_STATEMENT.name())
+        .map(|(_, value)| value)
+        .unwrap_or_default();
+    if next_statement.is_empty() {
+        session_client.terminate().await;
+        return Ok(());
+    }
+    let next_statement: usize = next_statement
+        .parse()
+        .with_context(|| format!("invalid bootstrap SQL progress: {next_statement}"))?;
+
+    let sql = tokio::fs::read_to_string(path)
+        .await
+        .with_context(|| format!("reading bootstrap SQL file {}", path.display()))?;
+    let stmts = mz_sql::parse::parse(&sql)
+        .with_context(|| format!("parsing bootstrap SQL file {}", path.display()))?;
+    if next_statement > stmts.len() {
+        bail!(
+            "bootstrap SQL file {} has {} statements, but {} have already been executed",
+            path.display(),
+            stmts.len(),
+            next_statement,
+        );
+    }
+    if next_statement > 0 {
+        info!("resuming bootstrap SQL at statement {next_statement}");
+    }
+
+    for (i, stmt) in stmts.into_iter().enumerate().skip(next_statement) {
+        let sql = stmt.to_string();
+
+        const EMPTY_PORTAL: &str = "";
+        let response = async {
+            session_client.start_transaction(Some(1))?;
+            session_client
+                .declare(EMPTY_PORTAL.into(), stmt, vec![])
+                .await?;
+            let (response, _) = session_client
+                .execute(EMPTY_PORTAL.into(), futures::future::pending())
+                .await?;
+            let result = match response {
+                ExecuteResponse::SendingRows { future, span: _ } => match future.await {
+                    PeekResponseUnary::Rows(rows) => format!("SELECT {}", rows.len()),
+                    PeekResponseUnary::Canceled => bail!("query canceled"),
+                    PeekResponseUnary::Error(e) => bail!(e),
+                },
+                response => response.tag().unwrap_or_else(|| format!("{response:?}")),
+            };
+            session_client
+                .end_transaction(EndTransactionAction::Commit)
+                .await?;
+            Ok::<_, anyhow::Error>(result)
+        }
+        .await
+        .with_context(|| format!("executing bootstrap SQL statement: {sql}"))?;
+        info!("bootstrap SQL: {sql}: {response}");
+        set_next_statement(&mut session_client, (i + 1).to_string()).await?;
+    }
+
+    set_next_statement(&mut session_client, String::new()).await?;
+    session_client.terminate().await;
+    Ok(())
+}
+
+/// Durably records the bootstrap SQL progress. An empty `value` marks the file
+/// as fully applied.
+async fn set_next_statement(
+    session_client: &mut mz_adapter::SessionClient,
+    value: String,
+) -> Result<(), anyhow::Error> {
+    let vars = BTreeMap::from([(BOOTSTRAP_SQL_NEXT_STATEMENT.name().to_string(), value)]);
+    session_client
+        .set_system_vars(vars)
+        .await
+        .context("recording bootstrap SQL progress")?;
+    Ok(())
+}
@@ -154,7 +154,12 @@ impl HttpServer {
                 adapter_client,
                 active_connection_count,
             });
-        let router = Router::new().merge(base_router).merge(ws_router);
+        // Routes that are deliberately served without authentication.
+        let public_router = Router::new().route("/api/version", routing::get(root::handle_version));
+        let router = Router::new()
+            .merge(base_router)
+            .merge(ws_router)
+            .merge(public_router);
         HttpServer { tls, router }
     }
 
@@ -192,6 +197,7 @@ impl Server for HttpServer {
 }
 
 pub struct InternalHttpConfig {
+    pub tls: Option<TlsConfig>,
     pub metrics_registry: MetricsRegistry,
     pub adapter_client_rx: oneshot::Receiver<mz_adapter::Client>,
     pub active_connection_count: Arc<Mutex<ConnectionCounter>>,
@@ -200,6 +206,7 @@ pub struct InternalHttpConfig {
 }
 
 pub struct InternalHttpServer {
+    tls: Option<TlsConfig>,
     router: Router,
 }
 
@@ -325,6 +332,7 @@ pub async fn handle_leader_promote(
 impl InternalHttpServer {
     pub fn new(
         InternalHttpConfig {
+            tls,
             metrics_registry,
             adapter_client_rx,
             active_connection_count,
@@ -340,7 +348,11 @@ impl InternalHttpServer {
         .route(
             "/metrics",
             routing::get(move || async move {
-                mz_http_util::handle_prometheus(&metrics_registry).await
+                mz_http_util::handle_prometheus_adaptive(
+                    &metrics_registry,
+                    mz_http_util::PROMETHEUS_STREAMING_THRESHOLD,
+                )
+                .await
             }),
         )
         .route(
@@ -393,6 +405,7 @@ impl InternalHttpServer {
             })));
 
         InternalHttpServer {
+            tls,
             router: router.merge(leader_router),
         }
     }
@@ -404,7 +417,19 @@ impl Server for InternalHttpServer {
 
     fn handle_connection(&self, conn: TcpStream) -> ConnectionHandler {
         let router = self.router.clone();
+        let tls_context = self.tls.as_ref().map(|tls| tls.context.clone());
         Box::pin(async {
+            let conn = match tls_context {
+                Some(tls_context) => {
+                    let mut ssl_stream = SslStream::new(Ssl::new(&tls_context)?, conn)?;
+                    if let Err(e) = Pin::new(&mut ssl_stream).accept().await {
+                        let _ = ssl_stream.get_mut().shutdown().await;
+                        return Err(e.into());
+                    }
+                    MaybeHttpsStream::Https(ssl_stream)
+                }
+                None => MaybeHttpsStream::Http(conn),
+            };
             let http = hyper::server::conn::Http::new();
             http.serve_connection(conn, router).err_into().await
         })
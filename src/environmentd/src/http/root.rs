@@ -11,6 +11,8 @@
 
 use askama::Template;
 use axum::response::IntoResponse;
+use axum::Json;
+use serde::Serialize;
 
 use crate::BUILD_INFO;
 
@@ -32,6 +34,25 @@ pub async fn handle_home(profiling: bool) -> impl IntoResponse {
     })
 }
 
+/// The response to a request to [`handle_version`].
+#[derive(Serialize)]
+struct VersionResponse {
+    version: &'static str,
+    sha: &'static str,
+    time: &'static str,
+    target_triple: &'static str,
+}
+
+/// Reports the build information of the running binary.
+pub async fn handle_version() -> impl IntoResponse {
+    Json(VersionResponse {
+        version: BUILD_INFO.version,
+        sha: BUILD_INFO.sha,
+        time: BUILD_INFO.time,
+        target_triple: mz_build_info::TARGET_TRIPLE,
+    })
+}
+
 mz_http_util::make_handle_static!(
     include_dir::include_dir!("$CARGO_MANIFEST_DIR/src/http/static"),
     "src/http/static",
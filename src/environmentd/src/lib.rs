@@ -107,7 +107,9 @@ use mz_secrets::SecretsController;
 use mz_sql::catalog::EnvironmentId;
 use mz_sql::session::vars::ConnectionCounter;
 use mz_storage_client::types::connections::ConnectionContext;
-use openssl::ssl::{SslAcceptor, SslFiletype, SslMethod};
+use openssl::pkey::PKey;
+use openssl::ssl::{SslAcceptor, SslAcceptorBuilder, SslFiletype, SslMethod};
+use openssl::x509::X509;
 use rand::seq::SliceRandom;
 use tokio::sync::oneshot;
 use tokio::sync::oneshot::error::RecvError;
@@ -116,6 +118,7 @@ use tower_http::cors::AllowOrigin;
 use crate::http::{HttpConfig, HttpServer, InternalHttpConfig, InternalHttpServer};
 use crate::server::{ConnectionStream, ListenerHandle};
 
+mod bootstrap_sql;
 pub mod http;
 mod server;
 mod telemetry;
@@ -141,8 +144,19 @@ pub struct Config {
     pub cors_allowed_origin: AllowOrigin,
     /// TLS encryption and authentication configuration.
     pub tls: Option<TlsConfig>,
+    /// Whether the internal HTTP server, which serves metrics, should also
+    /// require TLS, using the configuration in `tls`. Has no effect if `tls`
+    /// is `None`.
+    pub internal_http_tls: bool,
     /// Frontegg JWT authentication configuration.
     pub frontegg: Option<FronteggAuthentication>,
+    /// How long a SQL connection may go without receiving a message from its
+    /// client before it is closed. Applies only to the external SQL server.
+    /// If `None`, connections never time out.
+    pub idle_connection_timeout: Option<Duration>,
+    /// How long a SQL statement may take to execute before a warning is
+    /// logged about it. If `None`, slow statements are not logged.
+    pub slow_query_threshold: Option<Duration>,
 
     // === Connection options. ===
     /// Configuration for source and sink connections created by the storage
@@ -185,7 +199,21 @@ pub struct Config {
     /// How long to retain storage usage records for.
     pub storage_usage_retention_period: Option<Duration>,
     /// An API key for Segment. Enables export of audit events to Segment.
+    /// Ignored if `air_gapped` is set.
     pub segment_api_key: Option<String>,
+    /// The Segment-compatible API host to send events to, if not Segment's
+    /// own.
+    pub segment_api_host: Option<String>,
+    /// Whether to suppress the periodic telemetry reports sent to Segment.
+    pub disable_telemetry_reporting: bool,
+    /// Whether to run without contacting any external reporting or
+    /// configuration services.
+    ///
+    /// If set, `segment_api_key` and `launchdarkly_sdk_key` are ignored: no
+    /// audit events or telemetry reports are sent to Segment, regardless of
+    /// `disable_telemetry_reporting`, and system parameters are not
+    /// synchronized with LaunchDarkly.
+    pub air_gapped: bool,
     /// IP Addresses which will be used for egress.
     pub egress_ips: Vec<Ipv4Addr>,
     /// 12-digit AWS account id, which will be used to generate an AWS Principal.
@@ -193,7 +221,7 @@ pub struct Config {
     /// Supported AWS PrivateLink availability zone ids.
     pub aws_privatelink_availability_zones: Option<Vec<String>>,
     /// An SDK key for LaunchDarkly. Enables system parameter synchronization
-    /// with LaunchDarkly.
+    /// with LaunchDarkly. Ignored if `air_gapped` is set.
     pub launchdarkly_sdk_key: Option<String>,
     /// The interval in seconds at which to synchronize system parameter values.
     pub config_sync_loop_interval: Option<Duration>,
@@ -202,6 +230,9 @@ pub struct Config {
     pub launchdarkly_key_map: BTreeMap<String, String>,
     /// What role, if any, should be initially created with elevated privileges.
     pub bootstrap_role: Option<String>,
+    /// A file of SQL statements to execute, as the system user, when the
+    /// catalog is freshly initialized.
+    pub bootstrap_sql: Option<PathBuf>,
     /// Generation we want deployed. Generally only present when doing a production deploy.
     pub deploy_generation: Option<u64>,
 
@@ -219,10 +250,46 @@ pub struct Config {
 /// Configures TLS encryption for connections.
 #[derive(Debug, Clone)]
 pub struct TlsConfig {
-    /// The path to the TLS certificate.
-    pub cert: PathBuf,
-    /// The path to the TLS key.
-    pub key: PathBuf,
+    /// The TLS certificate chain.
+    pub cert: TlsMaterial,
+    /// The TLS private key.
+    pub key: TlsMaterial,
+}
+
+/// The source of a PEM-encoded TLS certificate chain or private key.
+#[derive(Debug, Clone)]
+pub enum TlsMaterial {
+    /// The path to a file containing the PEM-encoded material.
+    File(PathBuf),
+    /// The PEM-encoded material itself.
+    Memory(Vec<u8>),
+}
+
+impl TlsConfig {
+    /// Configures `builder` with the certificate chain and private key
+    /// described by this configuration.
+    fn configure_acceptor(&self, builder: &mut SslAcceptorBuilder) -> Result<(), anyhow::Error> {
+        match &self.cert {
+            TlsMaterial::File(path) => builder.set_certificate_chain_file(path)?,
+            TlsMaterial::Memory(pem) => {
+                let mut chain = X509::stack_from_pem(pem)?.into_iter();
+                let cert = chain
+                    .next()
+                    .ok_or_else(|| anyhow!("TLS certificate chain contains no certificates"))?;
+                builder.set_certificate(&cert)?;
+                for cert in chain {
+                    builder.add_extra_chain_cert(cert)?;
+                }
+            }
+        }
+        match &self.key {
+            TlsMaterial::File(path) => builder.set_private_key_file(path, SslFiletype::PEM)?,
+            TlsMaterial::Memory(pem) => {
+                builder.set_private_key(&PKey::private_key_from_pem(pem)?)?
+            }
+        }
+        Ok(())
+    }
 }
 
 /// Configuration for network listeners.
@@ -308,8 +375,8 @@ impl Listeners {
         )?)?;
 
         // Validate TLS configuration, if present.
-        let (pgwire_tls, http_tls) = match &config.tls {
-            None => (None, None),
+        let (pgwire_tls, http_tls, internal_http_tls) = match &config.tls {
+            None => (None, None, None),
             Some(tls_config) => {
                 let context = {
                     // Mozilla publishes three presets: old, intermediate, and modern. They
@@ -319,19 +386,22 @@ impl Listeners {
                     // ciphers. We once tried to use the modern preset, but it was
                     // incompatible with Fivetran, and presumably other JDBC-based tools.
                     let mut builder = SslAcceptor::mozilla_intermediate_v5(SslMethod::tls())?;
-                    builder.set_certificate_chain_file(&tls_config.cert)?;
-                    builder.set_private_key_file(&tls_config.key, SslFiletype::PEM)?;
+                    tls_config.configure_acceptor(&mut builder)?;
                     builder.build().into_context()
                 };
                 let pgwire_tls = mz_pgwire::TlsConfig {
                     context: context.clone(),
                     mode: mz_pgwire::TlsMode::Require,
                 };
+                let internal_http_tls = config.internal_http_tls.then(|| http::TlsConfig {
+                    context: context.clone(),
+                    mode: http::TlsMode::Require,
+                });
                 let http_tls = http::TlsConfig {
                     context,
                     mode: http::TlsMode::Require,
                 };
-                (Some(pgwire_tls), Some(http_tls))
+                (Some(pgwire_tls), Some(http_tls), internal_http_tls)
             }
         };
 
@@ -349,6 +419,7 @@ impl Listeners {
         let (internal_http_adapter_client_tx, internal_http_adapter_client_rx) = oneshot::channel();
         task::spawn(|| "internal_http_server", {
             let internal_http_server = InternalHttpServer::new(InternalHttpConfig {
+                tls: internal_http_tls,
                 metrics_registry: config.metrics_registry.clone(),
                 adapter_client_rx: internal_http_adapter_client_rx,
                 active_connection_count: Arc::clone(&active_connection_count),
@@ -398,6 +469,7 @@ impl Listeners {
                             .clone(),
                         default_availability_zone: mz_adapter::DUMMY_AVAILABILITY_ZONE.into(),
                         bootstrap_role: config.bootstrap_role.clone(),
+                        bootstrap_sql: config.bootstrap_sql.is_some(),
                     },
                     None,
                 )
@@ -427,7 +499,7 @@ impl Listeners {
             }
         }
 
-        let stash = config
+        let mut stash = config
             .controller
             .postgres_factory
             .open(config.adapter_stash_url.clone(), None, tls)
@@ -459,6 +531,7 @@ impl Listeners {
                     .cloned()
                     .unwrap_or_else(|| mz_adapter::DUMMY_AVAILABILITY_ZONE.into()),
                 bootstrap_role: config.bootstrap_role,
+                bootstrap_sql: config.bootstrap_sql.is_some(),
             },
             config.deploy_generation,
         )
@@ -477,8 +550,17 @@ impl Listeners {
         // Initialize controller.
         let controller = mz_controller::Controller::new(config.controller, envd_epoch).await;
 
+        let OutboundServiceKeys {
+            segment_api_key,
+            launchdarkly_sdk_key,
+        } = OutboundServiceKeys {
+            segment_api_key: config.segment_api_key,
+            launchdarkly_sdk_key: config.launchdarkly_sdk_key,
+        }
+        .air_gapped(config.air_gapped);
+
         // Initialize the system parameter frontend if `launchdarkly_sdk_key` is set.
-        let system_parameter_frontend = if let Some(ld_sdk_key) = config.launchdarkly_sdk_key {
+        let system_parameter_frontend = if let Some(ld_sdk_key) = launchdarkly_sdk_key {
             let ld_key_map = config.launchdarkly_key_map;
             let env_id = config.environment_id.clone();
             let metrics_registry = config.metrics_registry.clone();
@@ -506,7 +588,10 @@ impl Listeners {
         };
 
         // Initialize adapter.
-        let segment_client = config.segment_api_key.map(mz_segment::Client::new);
+        let segment_client = segment_api_key.map(|api_key| match config.segment_api_host {
+            Some(host) => mz_segment::Client::with_host(api_key, host),
+            None => mz_segment::Client::new(api_key),
+        });
         let (adapter_handle, adapter_client) = mz_adapter::serve(mz_adapter::Config {
             dataflow_client: controller,
             storage: adapter_storage,
@@ -536,6 +621,14 @@ impl Listeners {
         })
         .await?;
 
+        // Seed a freshly initialized catalog, before any client can connect.
+        // This is a no-op once the catalog records the file as fully applied.
+        if let Some(bootstrap_sql) = &config.bootstrap_sql {
+            bootstrap_sql::execute(&adapter_client, bootstrap_sql)
+                .await
+                .context("executing bootstrap SQL")?;
+        }
+
         // Install an adapter client in the internal HTTP server.
         internal_http_adapter_client_tx
             .send(adapter_client.clone())
@@ -551,6 +644,8 @@ impl Listeners {
                 metrics: metrics.clone(),
                 internal: false,
                 active_connection_count: Arc::clone(&active_connection_count),
+                idle_timeout: config.idle_connection_timeout,
+                slow_query_threshold: config.slow_query_threshold,
             });
             server::serve(sql_conns, sql_server)
         });
@@ -573,6 +668,8 @@ impl Listeners {
                 metrics,
                 internal: true,
                 active_connection_count: Arc::clone(&active_connection_count),
+                idle_timeout: None,
+                slow_query_threshold: config.slow_query_threshold,
             });
             server::serve(internal_sql_conns, internal_sql_server)
         });
@@ -597,6 +694,7 @@ impl Listeners {
                 segment_client,
                 adapter_client: adapter_client.clone(),
                 environment_id: config.environment_id,
+                enabled: !config.disable_telemetry_reporting,
             });
         }
 
@@ -641,6 +739,29 @@ impl Listeners {
     }
 }
 
+/// Keys for the external services that `environmentd` contacts.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+struct OutboundServiceKeys {
+    /// The API key for Segment, which receives audit events and telemetry.
+    segment_api_key: Option<String>,
+    /// The SDK key for LaunchDarkly, which supplies system parameters.
+    launchdarkly_sdk_key: Option<String>,
+}
+
+impl OutboundServiceKeys {
+    /// Discards all keys if `air_gapped` is set, so that no external service
+    /// is contacted.
+    fn air_gapped(self, air_gapped: bool) -> OutboundServiceKeys {
+        if !air_gapped {
+            return self;
+        }
+        if self != OutboundServiceKeys::default() {
+            tracing::info!("running in air-gapped mode; ignoring Segment and LaunchDarkly keys");
+        }
+        OutboundServiceKeys::default()
+    }
+}
+
 /// A running `environmentd` server.
 pub struct Server {
     // Drop order matters for these fields.
@@ -668,3 +789,29 @@ impl Server {
         self.internal_http_listener.local_addr()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::OutboundServiceKeys;
+
+    #[mz_ore::test]
+    fn test_air_gapped_outbound_service_keys() {
+        let keys = OutboundServiceKeys {
+            segment_api_key: Some("segment".into()),
+            launchdarkly_sdk_key: Some("launchdarkly".into()),
+        };
+        assert_eq!(keys.clone().air_gapped(false), keys);
+
+        // In air-gapped mode, no Segment client, and therefore no telemetry
+        // task, is created, whatever the configuration.
+        for segment_api_key in [None, Some("segment".to_string())] {
+            for launchdarkly_sdk_key in [None, Some("launchdarkly".to_string())] {
+                let keys = OutboundServiceKeys {
+                    segment_api_key: segment_api_key.clone(),
+                    launchdarkly_sdk_key,
+                };
+                assert_eq!(keys.air_gapped(true), OutboundServiceKeys::default());
+            }
+        }
+    }
+}
@@ -85,7 +85,7 @@ use mz_repr::adt::jsonb::Jsonb;
 use mz_sql::catalog::EnvironmentId;
 use serde_json::json;
 use tokio::time::{self, Duration};
-use tracing::warn;
+use tracing::{info, warn};
 
 /// How frequently to send a summary to Segment.
 const REPORT_INTERVAL: Duration = Duration::from_secs(3600);
@@ -99,6 +99,9 @@ pub struct Config {
     pub adapter_client: mz_adapter::Client,
     /// The ID of the environment for which to report data.
     pub environment_id: EnvironmentId,
+    /// Whether to report telemetry events. If false, the report loop exits
+    /// without contacting Segment.
+    pub enabled: bool,
 }
 
 /// Starts reporting telemetry events to Segment.
@@ -111,8 +114,14 @@ async fn report_loop(
         segment_client,
         adapter_client,
         environment_id,
+        enabled,
     }: Config,
 ) {
+    if !enabled {
+        info!("telemetry reporting disabled");
+        return;
+    }
+
     struct Stats {
         deletes: u64,
         inserts: u64,
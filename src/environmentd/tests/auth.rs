@@ -1614,6 +1614,65 @@ fn test_auth_intermediate_ca() {
     drop(server);
 }
 
+#[mz_ore::test]
+#[cfg_attr(miri, ignore)] // unsupported operation: can't call foreign function `OPENSSL_init_ssl` on OS `linux`
+fn test_internal_http_tls() {
+    let ca = Ca::new_root("test ca").unwrap();
+    let (server_cert, server_key) = ca
+        .request_cert("server", vec![IpAddr::V4(Ipv4Addr::LOCALHOST)])
+        .unwrap();
+
+    let config = util::Config::default()
+        .with_tls(&server_cert, &server_key)
+        .with_internal_http_tls(true);
+    let server = util::start_server(config).unwrap();
+    let addr = server.inner.internal_http_local_addr();
+
+    // Plain HTTP requests to the metrics endpoint are rejected.
+    let client = reqwest::blocking::Client::builder()
+        .danger_accept_invalid_certs(true)
+        .build()
+        .unwrap();
+    assert!(client.get(format!("http://{addr}/metrics")).send().is_err());
+
+    // HTTPS requests succeed.
+    let res = client
+        .get(format!("https://{addr}/metrics"))
+        .send()
+        .unwrap();
+    assert!(res.status().is_success());
+}
+
+#[mz_ore::test]
+#[cfg_attr(miri, ignore)] // unsupported operation: can't call foreign function `OPENSSL_init_ssl` on OS `linux`
+fn test_tls_in_memory() {
+    let ca = Ca::new_root("test ca").unwrap();
+    let (server_cert, server_key) = ca
+        .request_cert("server", vec![IpAddr::V4(Ipv4Addr::LOCALHOST)])
+        .unwrap();
+
+    // Hand the server the PEM-encoded certificate and key directly, rather
+    // than the paths to the files that contain them.
+    let config = util::Config::default().with_tls_pem(
+        fs::read(&server_cert).unwrap(),
+        fs::read(&server_key).unwrap(),
+    );
+    let server = util::start_server(config).unwrap();
+    let addr = server.inner.http_local_addr();
+
+    let client = reqwest::blocking::Client::builder()
+        .add_root_certificate(
+            reqwest::Certificate::from_pem(&fs::read(ca.ca_cert_path()).unwrap()).unwrap(),
+        )
+        .build()
+        .unwrap();
+    let res = client
+        .get(format!("https://{addr}/api/version"))
+        .send()
+        .unwrap();
+    assert!(res.status().is_success());
+}
+
 #[mz_ore::test]
 #[cfg_attr(miri, ignore)] // unsupported operation: can't call foreign function `OPENSSL_init_ssl` on OS `linux`
 fn test_auth_admin() {
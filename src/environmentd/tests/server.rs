@@ -77,11 +77,11 @@
 
 use std::collections::{BTreeMap, VecDeque};
 use std::fmt::Write;
-use std::net::Ipv4Addr;
+use std::net::{Ipv4Addr, TcpListener};
 use std::process::{Command, Stdio};
-use std::sync::{Arc, Mutex};
+use std::sync::{mpsc, Arc, Mutex};
 use std::time::{Duration, Instant};
-use std::{iter, thread};
+use std::{cmp, fs, iter, thread};
 
 use anyhow::bail;
 use chrono::{DateTime, Utc};
@@ -190,6 +190,154 @@ fn test_persistence() {
     );
 }
 
+// Test that the bootstrap SQL file is executed exactly once, when the catalog
+// is freshly initialized, and not when restarting against an existing catalog.
+#[mz_ore::test]
+fn test_bootstrap_sql() {
+    let data_dir = tempfile::tempdir().unwrap();
+    let bootstrap_sql = data_dir.path().join("bootstrap.sql");
+    fs::write(
+        &bootstrap_sql,
+        "CREATE TABLE bootstrapped (a int); INSERT INTO bootstrapped VALUES (1);",
+    )
+    .unwrap();
+    let config = util::Config::default()
+        .data_directory(data_dir.path())
+        .with_bootstrap_sql(&bootstrap_sql);
+
+    for _ in 0..2 {
+        let server = util::start_server(config.clone()).unwrap();
+        let mut client = server.connect_internal(postgres::NoTls).unwrap();
+        let count: i64 = client
+            .query_one("SELECT count(*) FROM bootstrapped", &[])
+            .unwrap()
+            .get(0);
+        assert_eq!(count, 1);
+    }
+
+    // A failing statement aborts startup.
+    fs::write(&bootstrap_sql, "CREATE VIEW v AS SELECT nonexistent").unwrap();
+    let err = util::start_server(util::Config::default().with_bootstrap_sql(&bootstrap_sql))
+        .err()
+        .expect("startup should fail");
+    assert!(
+        format!("{err:#}").contains("executing bootstrap SQL statement"),
+        "unexpected error: {err:#}"
+    );
+}
+
+// Test that a bootstrap SQL file that fails partway through is resumed from
+// the failing statement on the next restart, rather than being abandoned with
+// only its leading statements applied.
+#[mz_ore::test]
+fn test_bootstrap_sql_partial_failure() {
+    let data_dir = tempfile::tempdir().unwrap();
+    let bootstrap_sql = data_dir.path().join("bootstrap.sql");
+    fs::write(
+        &bootstrap_sql,
+        "CREATE TABLE t (a int); CREATE VIEW v AS SELECT nonexistent; INSERT INTO t VALUES (1);",
+    )
+    .unwrap();
+    let config = util::Config::default()
+        .data_directory(data_dir.path())
+        .with_bootstrap_sql(&bootstrap_sql);
+
+    let err = util::start_server(config.clone())
+        .err()
+        .expect("startup should fail");
+    assert!(
+        format!("{err:#}").contains("CREATE VIEW v AS SELECT nonexistent"),
+        "unexpected error: {err:#}"
+    );
+
+    // Fix the failing statement. The table was already created, so
+    // re-executing the first statement would fail startup again.
+    fs::write(
+        &bootstrap_sql,
+        "CREATE TABLE t (a int); CREATE VIEW v AS SELECT 1 AS b; INSERT INTO t VALUES (1);",
+    )
+    .unwrap();
+    for _ in 0..2 {
+        let server = util::start_server(config.clone()).unwrap();
+        let mut client = server.connect_internal(postgres::NoTls).unwrap();
+        let count: i64 = client
+            .query_one("SELECT count(*) FROM t", &[])
+            .unwrap()
+            .get(0);
+        assert_eq!(count, 1);
+        let b: i32 = client.query_one("SELECT b FROM v", &[]).unwrap().get(0);
+        assert_eq!(b, 1);
+    }
+}
+
+/// Starts a fake Segment API on a local listener. Returns its host and a
+/// channel that receives a message each time a client connects to it.
+fn mock_segment() -> (String, mpsc::Receiver<()>) {
+    let listener = TcpListener::bind((Ipv4Addr::LOCALHOST, 0)).unwrap();
+    let api_host = format!("http://{}", listener.local_addr().unwrap());
+    let (tx, rx) = mpsc::channel();
+    thread::spawn(move || {
+        for conn in listener.incoming() {
+            conn.expect("accepting connection");
+            if tx.send(()).is_err() {
+                break;
+            }
+        }
+    });
+    (api_host, rx)
+}
+
+/// Returns how long a server with telemetry reporting enabled should be
+/// given to contact Segment. The report loop sends its first report at
+/// startup, so this is a small multiple of how long an enabled server takes
+/// to contact a fake Segment API.
+fn telemetry_report_grace_period() -> Duration {
+    let (api_host, contacted) = mock_segment();
+    let start = Instant::now();
+    let _server =
+        util::start_server(util::Config::default().with_segment("key".into(), api_host)).unwrap();
+    contacted
+        .recv_timeout(Duration::from_secs(60))
+        .expect("segment not contacted");
+    cmp::max(start.elapsed() * 2, Duration::from_secs(1))
+}
+
+// Test that disabling telemetry reporting stops the server from contacting
+// Segment, even though a Segment API key is configured.
+#[mz_ore::test]
+fn test_telemetry_reporting_disabled() {
+    let grace = telemetry_report_grace_period();
+    let (api_host, contacted) = mock_segment();
+    let _server = util::start_server(
+        util::Config::default()
+            .with_segment("key".into(), api_host)
+            .with_disable_telemetry_reporting(true),
+    )
+    .unwrap();
+    assert_eq!(
+        contacted.recv_timeout(grace),
+        Err(mpsc::RecvTimeoutError::Timeout)
+    );
+}
+
+// Test that air-gapped mode ignores the Segment API key, so that no telemetry
+// task is started and Segment is never contacted.
+#[mz_ore::test]
+fn test_air_gapped_no_telemetry() {
+    let grace = telemetry_report_grace_period();
+    let (api_host, contacted) = mock_segment();
+    let _server = util::start_server(
+        util::Config::default()
+            .with_segment("key".into(), api_host)
+            .with_air_gapped(true),
+    )
+    .unwrap();
+    assert_eq!(
+        contacted.recv_timeout(grace),
+        Err(mpsc::RecvTimeoutError::Timeout)
+    );
+}
+
 // Test that sources and sinks require an explicit `SIZE` parameter outside of
 // unsafe mode.
 #[mz_ore::test]
@@ -1260,6 +1408,77 @@ fn test_http_options_param() {
         .contains(r#"startup setting not_a_session_var not set"#));
 }
 
+#[mz_ore::test]
+#[cfg_attr(miri, ignore)] // too slow
+fn test_idle_connection_timeout() {
+    let server = util::start_server(
+        util::Config::default().with_idle_connection_timeout(Duration::from_secs(2)),
+    )
+    .unwrap();
+
+    // A connection that remains active is not closed, even if it outlives the
+    // timeout.
+    let mut client = server.connect(postgres::NoTls).unwrap();
+    for _ in 0..3 {
+        client.batch_execute("SELECT 1").unwrap();
+        thread::sleep(Duration::from_secs(1));
+    }
+
+    // A connection that goes idle is closed.
+    thread::sleep(Duration::from_secs(4));
+    let err = client.batch_execute("SELECT 1").unwrap_err();
+    assert!(client.is_closed(), "{err}");
+
+    // Connections to the internal SQL server never time out.
+    let mut internal_client = server.connect_internal(postgres::NoTls).unwrap();
+    thread::sleep(Duration::from_secs(4));
+    internal_client.batch_execute("SELECT 1").unwrap();
+}
+
+#[mz_ore::test]
+#[cfg_attr(miri, ignore)] // too slow
+fn test_slow_query_threshold() {
+    let server = util::start_server(
+        util::Config::default().with_slow_query_threshold(Duration::from_millis(500)),
+    )
+    .unwrap();
+    let slow_queries = || {
+        let metric = server
+            .metrics_registry
+            .gather()
+            .into_iter()
+            .find(|metric| metric.get_name() == "mz_slow_queries_total")
+            .unwrap();
+        metric
+            .get_metric()
+            .iter()
+            .find(|m| {
+                m.get_label()
+                    .iter()
+                    .any(|l| l.get_name() == "source" && l.get_value() == "external_pgwire")
+            })
+            .unwrap()
+            .get_counter()
+            .get_value()
+    };
+
+    let mut client = server.connect(postgres::NoTls).unwrap();
+
+    // Fast statements are not reported.
+    client.batch_execute("SELECT 1").unwrap();
+    client.query("SELECT 1", &[]).unwrap();
+    assert_eq!(slow_queries(), 0.0);
+
+    // Slow statements are reported, whether they are executed via the simple
+    // or the extended query protocol.
+    client
+        .batch_execute("SELECT mz_internal.mz_sleep(1)")
+        .unwrap();
+    assert_eq!(slow_queries(), 1.0);
+    client.query("SELECT mz_internal.mz_sleep(1)", &[]).unwrap();
+    assert_eq!(slow_queries(), 2.0);
+}
+
 #[mz_ore::test]
 #[cfg_attr(miri, ignore)] // too slow
 fn test_max_connections_on_all_interfaces() {
@@ -1926,6 +2145,21 @@ fn test_github_20262() {
     }
 }
 
+#[mz_ore::test]
+#[cfg_attr(miri, ignore)] // too slow
+fn test_http_version() {
+    let server = util::start_server(util::Config::default()).unwrap();
+
+    let url = format!("http://{}/api/version", server.inner.http_local_addr());
+    let response = Client::new().get(url).send().unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body: serde_json::Value = response.json().unwrap();
+    assert_eq!(body["version"], env!("CARGO_PKG_VERSION"));
+    assert!(body["sha"].is_string());
+    assert!(body["time"].is_string());
+    assert!(body["target_triple"].is_string());
+}
+
 #[mz_ore::test]
 #[cfg_attr(miri, ignore)] // too slow
 fn test_http_metrics() {
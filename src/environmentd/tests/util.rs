@@ -83,7 +83,7 @@ use std::{env, thread};
 
 use anyhow::anyhow;
 use mz_controller::ControllerConfig;
-use mz_environmentd::{WebSocketAuth, WebSocketResponse};
+use mz_environmentd::{TlsMaterial, WebSocketAuth, WebSocketResponse};
 use mz_frontegg_auth::Authentication as FronteggAuthentication;
 use mz_orchestrator_process::{ProcessOrchestrator, ProcessOrchestratorConfig};
 use mz_ore::metrics::MetricsRegistry;
@@ -128,7 +128,10 @@ pub static KAFKA_ADDRS: Lazy<String> =
 pub struct Config {
     data_directory: Option<PathBuf>,
     tls: Option<mz_environmentd::TlsConfig>,
+    internal_http_tls: bool,
     frontegg: Option<FronteggAuthentication>,
+    idle_connection_timeout: Option<Duration>,
+    slow_query_threshold: Option<Duration>,
     unsafe_mode: bool,
     workers: usize,
     now: NowFn,
@@ -140,8 +143,13 @@ pub struct Config {
     propagate_crashes: bool,
     enable_tracing: bool,
     bootstrap_role: Option<String>,
+    bootstrap_sql: Option<PathBuf>,
     deploy_generation: Option<u64>,
     system_parameter_defaults: BTreeMap<String, String>,
+    segment_api_key: Option<String>,
+    segment_api_host: Option<String>,
+    disable_telemetry_reporting: bool,
+    air_gapped: bool,
 }
 
 impl Default for Config {
@@ -149,7 +157,10 @@ impl Default for Config {
         Config {
             data_directory: None,
             tls: None,
+            internal_http_tls: false,
             frontegg: None,
+            idle_connection_timeout: None,
+            slow_query_threshold: None,
             unsafe_mode: false,
             workers: 1,
             now: SYSTEM_TIME.clone(),
@@ -161,8 +172,13 @@ impl Default for Config {
             propagate_crashes: false,
             enable_tracing: false,
             bootstrap_role: Some("materialize".into()),
+            bootstrap_sql: None,
             deploy_generation: None,
             system_parameter_defaults: BTreeMap::new(),
+            segment_api_key: None,
+            segment_api_host: None,
+            disable_telemetry_reporting: false,
+            air_gapped: false,
         }
     }
 }
@@ -175,12 +191,25 @@ impl Config {
 
     pub fn with_tls(mut self, cert_path: impl Into<PathBuf>, key_path: impl Into<PathBuf>) -> Self {
         self.tls = Some(mz_environmentd::TlsConfig {
-            cert: cert_path.into(),
-            key: key_path.into(),
+            cert: TlsMaterial::File(cert_path.into()),
+            key: TlsMaterial::File(key_path.into()),
         });
         self
     }
 
+    pub fn with_tls_pem(mut self, cert_pem: Vec<u8>, key_pem: Vec<u8>) -> Self {
+        self.tls = Some(mz_environmentd::TlsConfig {
+            cert: TlsMaterial::Memory(cert_pem),
+            key: TlsMaterial::Memory(key_pem),
+        });
+        self
+    }
+
+    pub fn with_internal_http_tls(mut self, internal_http_tls: bool) -> Self {
+        self.internal_http_tls = internal_http_tls;
+        self
+    }
+
     pub fn unsafe_mode(mut self) -> Self {
         self.unsafe_mode = true;
         self
@@ -196,6 +225,16 @@ impl Config {
         self
     }
 
+    pub fn with_idle_connection_timeout(mut self, idle_connection_timeout: Duration) -> Self {
+        self.idle_connection_timeout = Some(idle_connection_timeout);
+        self
+    }
+
+    pub fn with_slow_query_threshold(mut self, slow_query_threshold: Duration) -> Self {
+        self.slow_query_threshold = Some(slow_query_threshold);
+        self
+    }
+
     pub fn with_now(mut self, now: NowFn) -> Self {
         self.now = now;
         self
@@ -248,6 +287,11 @@ impl Config {
         self
     }
 
+    pub fn with_bootstrap_sql(mut self, bootstrap_sql: impl Into<PathBuf>) -> Self {
+        self.bootstrap_sql = Some(bootstrap_sql.into());
+        self
+    }
+
     pub fn with_deploy_generation(mut self, deploy_generation: Option<u64>) -> Self {
         self.deploy_generation = deploy_generation;
         self
@@ -257,6 +301,22 @@ impl Config {
         self.system_parameter_defaults.insert(param, value);
         self
     }
+
+    pub fn with_segment(mut self, api_key: String, api_host: String) -> Self {
+        self.segment_api_key = Some(api_key);
+        self.segment_api_host = Some(api_host);
+        self
+    }
+
+    pub fn with_disable_telemetry_reporting(mut self, disable_telemetry_reporting: bool) -> Self {
+        self.disable_telemetry_reporting = disable_telemetry_reporting;
+        self
+    }
+
+    pub fn with_air_gapped(mut self, air_gapped: bool) -> Self {
+        self.air_gapped = air_gapped;
+        self
+    }
 }
 
 pub struct Listeners {
@@ -416,7 +476,10 @@ impl Listeners {
                     secrets_controller,
                     cloud_resource_controller: None,
                     tls: config.tls,
+                    internal_http_tls: config.internal_http_tls,
                     frontegg: config.frontegg,
+                    idle_connection_timeout: config.idle_connection_timeout,
+                    slow_query_threshold: config.slow_query_threshold,
                     unsafe_mode: config.unsafe_mode,
                     all_features: false,
                     metrics_registry: metrics_registry.clone(),
@@ -433,7 +496,10 @@ impl Listeners {
                     tracing_handle,
                     storage_usage_collection_interval: config.storage_usage_collection_interval,
                     storage_usage_retention_period: config.storage_usage_retention_period,
-                    segment_api_key: None,
+                    segment_api_key: config.segment_api_key,
+                    segment_api_host: config.segment_api_host,
+                    disable_telemetry_reporting: config.disable_telemetry_reporting,
+                    air_gapped: config.air_gapped,
                     egress_ips: vec![],
                     aws_account_id: None,
                     aws_privatelink_availability_zones: None,
@@ -441,6 +507,7 @@ impl Listeners {
                     launchdarkly_key_map: Default::default(),
                     config_sync_loop_interval: None,
                     bootstrap_role: config.bootstrap_role,
+                    bootstrap_sql: config.bootstrap_sql,
                     deploy_generation: config.deploy_generation,
                 })
                 .await
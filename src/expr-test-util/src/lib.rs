@@ -75,12 +75,15 @@
 
 use std::collections::BTreeMap;
 
-use mz_expr::{EvalError, Id, LocalId, MirRelationExpr, MirScalarExpr};
+use mz_expr::{
+    AggregateExpr, AggregateFunc, EvalError, Id, LocalId, MirRelationExpr, MirScalarExpr,
+};
 use mz_lowertest::*;
 use mz_ore::cast::CastFrom;
 use mz_ore::result::ResultExt;
 use mz_ore::str::separated;
-use mz_repr::explain::{DummyHumanizer, ExprHumanizer};
+use mz_repr::adt::numeric::NUMERIC_DATUM_MAX_PRECISION;
+use mz_repr::explain::ExprHumanizer;
 use mz_repr::{ColumnType, GlobalId, RelationType, Row, ScalarType};
 use mz_repr_test_util::*;
 use proc_macro2::TokenTree;
@@ -200,15 +203,28 @@ impl<'a> TestCatalog {
     ///
     /// Currently supported commands:
     /// * `(defsource [types_of_cols] [[optional_sets_of_key_cols]])` -
-    ///   insert a source into the catalog.
+    ///   insert a source into the catalog. Errors if a key refers to a column
+    ///   that the source does not have. Column types without parameters can
+    ///   also be written with the SQL names that the catalog renders them
+    ///   with, e.g. `int4` or `text`.
     pub fn handle_test_command(&mut self, spec: &str) -> Result<(), String> {
         let mut stream_iter = tokenize(spec)?.into_iter();
-        while let Some(command) = deserialize_optional_generic::<TestCatalogCommand, _>(
+        while let Some(command) = deserialize_optional::<TestCatalogCommand, _, _>(
             &mut stream_iter,
             "TestCatalogCommand",
+            &mut SqlScalarTypeDeserializeContext::default(),
         )? {
             match command {
                 TestCatalogCommand::Defsource { name, typ } => {
+                    let arity = typ.arity();
+                    for key in &typ.keys {
+                        if let Some(col) = key.iter().find(|col| **col >= arity) {
+                            return Err(format!(
+                                "Key {:?} of source {} refers to column {}, but the source has {} columns",
+                                key, name, col, arity
+                            ));
+                        }
+                    }
                     self.insert(&name, typ, false)?;
                 }
             }
@@ -244,8 +260,161 @@ impl ExprHumanizer for TestCatalog {
         self.names.get(&id).map(|s| s.to_string())
     }
 
+    /// Renders `ty` using the names that PostgreSQL uses for the type, e.g.
+    /// `int4` or `numeric(39,2)`.
     fn humanize_scalar_type(&self, ty: &ScalarType) -> String {
-        DummyHumanizer.humanize_scalar_type(ty)
+        match ty {
+            ScalarType::Bool => "bool".into(),
+            ScalarType::Int16 => "int2".into(),
+            ScalarType::Int32 => "int4".into(),
+            ScalarType::Int64 => "int8".into(),
+            ScalarType::UInt16 => "uint2".into(),
+            ScalarType::UInt32 => "uint4".into(),
+            ScalarType::UInt64 => "uint8".into(),
+            ScalarType::Float32 => "float4".into(),
+            ScalarType::Float64 => "float8".into(),
+            ScalarType::Numeric { max_scale: None } => "numeric".into(),
+            ScalarType::Numeric {
+                max_scale: Some(max_scale),
+            } => format!(
+                "numeric({},{})",
+                NUMERIC_DATUM_MAX_PRECISION,
+                max_scale.into_u8()
+            ),
+            ScalarType::Date => "date".into(),
+            ScalarType::Time => "time".into(),
+            ScalarType::Timestamp => "timestamp".into(),
+            ScalarType::TimestampTz => "timestamptz".into(),
+            ScalarType::Interval => "interval".into(),
+            ScalarType::PgLegacyChar => "\"char\"".into(),
+            ScalarType::PgLegacyName => "name".into(),
+            ScalarType::Bytes => "bytea".into(),
+            ScalarType::String => "text".into(),
+            ScalarType::Char { length: None } => "bpchar".into(),
+            ScalarType::Char {
+                length: Some(length),
+            } => format!("char({})", length.into_u32()),
+            ScalarType::VarChar { max_length: None } => "varchar".into(),
+            ScalarType::VarChar {
+                max_length: Some(max_length),
+            } => format!("varchar({})", max_length.into_u32()),
+            ScalarType::Jsonb => "jsonb".into(),
+            ScalarType::Uuid => "uuid".into(),
+            ScalarType::Array(element_type) => {
+                format!("{}[]", self.humanize_scalar_type(element_type))
+            }
+            ScalarType::List { element_type, .. } => {
+                format!("{} list", self.humanize_scalar_type(element_type))
+            }
+            ScalarType::Map { value_type, .. } => {
+                format!("map[text=>{}]", self.humanize_scalar_type(value_type))
+            }
+            ScalarType::Record { fields, .. } => format!(
+                "record({})",
+                separated(
+                    ",",
+                    fields.iter().map(|(name, typ)| format!(
+                        "{}: {}",
+                        name,
+                        self.humanize_column_type(typ)
+                    ))
+                )
+            ),
+            ScalarType::Oid => "oid".into(),
+            ScalarType::RegProc => "regproc".into(),
+            ScalarType::RegType => "regtype".into(),
+            ScalarType::RegClass => "regclass".into(),
+            ScalarType::Int2Vector => "int2vector".into(),
+            ScalarType::MzTimestamp => "mz_timestamp".into(),
+            ScalarType::Range { element_type } => match &**element_type {
+                ScalarType::Int32 => "int4range".into(),
+                ScalarType::Int64 => "int8range".into(),
+                ScalarType::Numeric { .. } => "numrange".into(),
+                ScalarType::Date => "daterange".into(),
+                ScalarType::Timestamp => "tsrange".into(),
+                ScalarType::TimestampTz => "tstzrange".into(),
+                element_type => format!("range({})", self.humanize_scalar_type(element_type)),
+            },
+            ScalarType::MzAclItem => "mz_aclitem".into(),
+        }
+    }
+}
+
+/// Extends the test case syntax for [ScalarType]s so that the types
+/// [TestCatalog] renders without parameters, e.g. `int4` or `text`, can be
+/// written as they are rendered.
+///
+/// Types with parameters or element types, e.g. `numeric(39,2)`, `char(5)`,
+/// `int4[]` or `int4 list`, must still be written in the default syntax, e.g.
+/// `(list int32 null)`.
+#[derive(Default)]
+struct SqlScalarTypeDeserializeContext;
+
+impl SqlScalarTypeDeserializeContext {
+    /// Returns the type that [TestCatalog] renders as `name`, if it has no
+    /// parameters.
+    fn scalar_type_from_sql_name(name: &str) -> Option<ScalarType> {
+        let ty = match name {
+            "bool" => ScalarType::Bool,
+            "int2" => ScalarType::Int16,
+            "int4" => ScalarType::Int32,
+            "int8" => ScalarType::Int64,
+            "uint2" => ScalarType::UInt16,
+            "uint4" => ScalarType::UInt32,
+            "uint8" => ScalarType::UInt64,
+            "float4" => ScalarType::Float32,
+            "float8" => ScalarType::Float64,
+            "numeric" => ScalarType::Numeric { max_scale: None },
+            "date" => ScalarType::Date,
+            "time" => ScalarType::Time,
+            "timestamp" => ScalarType::Timestamp,
+            "timestamptz" => ScalarType::TimestampTz,
+            "interval" => ScalarType::Interval,
+            "name" => ScalarType::PgLegacyName,
+            "bytea" => ScalarType::Bytes,
+            "text" => ScalarType::String,
+            "bpchar" => ScalarType::Char { length: None },
+            "varchar" => ScalarType::VarChar { max_length: None },
+            "jsonb" => ScalarType::Jsonb,
+            "uuid" => ScalarType::Uuid,
+            "oid" => ScalarType::Oid,
+            "regproc" => ScalarType::RegProc,
+            "regtype" => ScalarType::RegType,
+            "regclass" => ScalarType::RegClass,
+            "int2vector" => ScalarType::Int2Vector,
+            "mz_timestamp" => ScalarType::MzTimestamp,
+            "mz_aclitem" => ScalarType::MzAclItem,
+            _ => return None,
+        };
+        Some(ty)
+    }
+}
+
+impl TestDeserializeContext for SqlScalarTypeDeserializeContext {
+    fn override_syntax<I>(
+        &mut self,
+        first_arg: TokenTree,
+        _rest_of_stream: &mut I,
+        type_name: &str,
+    ) -> Result<Option<String>, String>
+    where
+        I: Iterator<Item = TokenTree>,
+    {
+        match first_arg {
+            TokenTree::Ident(ident) if type_name == "ScalarType" => {
+                match Self::scalar_type_from_sql_name(&ident.to_string()) {
+                    Some(ty) => Ok(Some(
+                        serde_json::to_string(&ty).map_err_to_string_with_causes()?,
+                    )),
+                    None => Ok(None),
+                }
+            }
+            _ => Ok(None),
+        }
+    }
+
+    fn reverse_syntax_override(&mut self, _: &Value, _: &str) -> Option<String> {
+        None
     }
 }
 
@@ -427,6 +596,13 @@ impl TestDeserializeContext for MirScalarExprDeserializeContext {
 /// Get -> the syntax is `(get x)`, where x is an ident that refers to a
 ///        pre-defined source or an ident defined in a let.
 /// Union -> the syntax is `(union <input1> .. <inputn>)`.
+/// Negate -> the syntax is `(negate <input>)`.
+/// Threshold -> the syntax is `(threshold <input>)`.
+/// Reduce -> the syntax is
+/// `(reduce <input> [<group_key1>..<group_keyn>] [<agg1>..<aggn>])`, where
+/// each aggregate is `(<AggregateFunc> <MirScalarExpr> [distinct])`.
+/// `monotonic` and `expected_group_size` can optionally follow the
+/// aggregates.
 /// Constant -> the syntax is
 /// ```ignore
 /// (constant
@@ -572,6 +748,77 @@ impl<'a> MirRelationExprDeserializeContext<'a> {
         })
     }
 
+    fn build_negate<I>(&mut self, stream_iter: &mut I) -> Result<MirRelationExpr, String>
+    where
+        I: Iterator<Item = TokenTree>,
+    {
+        let input: MirRelationExpr = deserialize(stream_iter, "MirRelationExpr", self)?;
+        Ok(MirRelationExpr::Negate {
+            input: Box::new(input),
+        })
+    }
+
+    fn build_threshold<I>(&mut self, stream_iter: &mut I) -> Result<MirRelationExpr, String>
+    where
+        I: Iterator<Item = TokenTree>,
+    {
+        let input: MirRelationExpr = deserialize(stream_iter, "MirRelationExpr", self)?;
+        Ok(MirRelationExpr::Threshold {
+            input: Box::new(input),
+        })
+    }
+
+    fn build_reduce<I>(&mut self, stream_iter: &mut I) -> Result<MirRelationExpr, String>
+    where
+        I: Iterator<Item = TokenTree>,
+    {
+        let input: MirRelationExpr = deserialize(stream_iter, "MirRelationExpr", self)?;
+        let group_key: Vec<MirScalarExpr> = deserialize(stream_iter, "Vec<MirScalarExpr>", self)?;
+        let aggregates: Vec<AggregateExpr> = deserialize(stream_iter, "Vec<AggregateExpr>", self)?;
+        let monotonic: Option<bool> = deserialize_optional(stream_iter, "bool", self)?;
+        let expected_group_size: Option<Option<u64>> =
+            deserialize_optional(stream_iter, "Option<u64>", self)?;
+        Ok(MirRelationExpr::Reduce {
+            input: Box::new(input),
+            group_key,
+            aggregates,
+            monotonic: monotonic.unwrap_or(false),
+            expected_group_size: expected_group_size.flatten(),
+        })
+    }
+
+    fn build_aggregate<I>(
+        &mut self,
+        first_arg: TokenTree,
+        rest_of_stream: &mut I,
+    ) -> Result<AggregateExpr, String>
+    where
+        I: Iterator<Item = TokenTree>,
+    {
+        let func: AggregateFunc = deserialize(
+            &mut std::iter::once(first_arg).chain(&mut *rest_of_stream),
+            "AggregateFunc",
+            self,
+        )?;
+        let expr: MirScalarExpr = deserialize(rest_of_stream, "MirScalarExpr", self)?;
+        let distinct = match rest_of_stream.next() {
+            None => false,
+            Some(TokenTree::Ident(ident)) if ident == "distinct" || ident == "true" => true,
+            Some(TokenTree::Ident(ident)) if ident == "false" => false,
+            invalid_token => {
+                return Err(format!(
+                    "Invalid aggregate specification {:?}",
+                    invalid_token.map(|token_tree| format!("`{}`", token_tree))
+                ))
+            }
+        };
+        Ok(AggregateExpr {
+            func,
+            expr,
+            distinct,
+        })
+    }
+
     fn build_special_mir_if_able<I>(
         &mut self,
         first_arg: TokenTree,
@@ -587,6 +834,9 @@ impl<'a> MirRelationExprDeserializeContext<'a> {
                 "get" => Some(self.build_get(rest_of_stream.next())?),
                 "let" => Some(self.build_let(rest_of_stream)?),
                 "union" => Some(self.build_union(rest_of_stream)?),
+                "negate" => Some(self.build_negate(rest_of_stream)?),
+                "threshold" => Some(self.build_threshold(rest_of_stream)?),
+                "reduce" => Some(self.build_reduce(rest_of_stream)?),
                 _ => None,
             });
         }
@@ -618,6 +868,11 @@ impl<'a> TestDeserializeContext for MirRelationExprDeserializeContext<'a> {
                             serde_json::to_string(&result).map_err_to_string_with_causes()?,
                         ));
                     }
+                } else if type_name == "AggregateExpr" {
+                    let result = self.build_aggregate(first_arg, rest_of_stream)?;
+                    return Ok(Some(
+                        serde_json::to_string(&result).map_err_to_string_with_causes()?,
+                    ));
                 } else if type_name == "usize" {
                     if let TokenTree::Punct(punct) = first_arg {
                         if punct.as_char() == '#' {
@@ -644,6 +899,19 @@ impl<'a> TestDeserializeContext for MirRelationExprDeserializeContext<'a> {
         match self.inner_ctx.reverse_syntax_override(json, type_name) {
             Some(result) => Some(result),
             None => {
+                if type_name == "AggregateExpr" {
+                    let map = json.as_object().unwrap();
+                    return Some(format!(
+                        "({} {}{})",
+                        serialize::<AggregateFunc, _>(&map["func"], "AggregateFunc", self),
+                        serialize::<MirScalarExpr, _>(&map["expr"], "MirScalarExpr", self),
+                        if map["distinct"].as_bool().unwrap() {
+                            " distinct"
+                        } else {
+                            ""
+                        }
+                    ));
+                }
                 if type_name == "MirRelationExpr" {
                     let map = json.as_object().unwrap();
                     // Each enum instance only belows to one variant.
@@ -748,6 +1016,47 @@ impl<'a> TestDeserializeContext for MirRelationExprDeserializeContext<'a> {
                                     )
                                 ));
                             }
+                            "Reduce" => {
+                                let mut result = format!(
+                                    "(reduce {} {} {}",
+                                    serialize::<MirRelationExpr, _>(
+                                        &inner_map["input"],
+                                        "MirRelationExpr",
+                                        self
+                                    ),
+                                    serialize::<Vec<MirScalarExpr>, _>(
+                                        &inner_map["group_key"],
+                                        "Vec<MirScalarExpr>",
+                                        self
+                                    ),
+                                    serialize::<Vec<AggregateExpr>, _>(
+                                        &inner_map["aggregates"],
+                                        "Vec<AggregateExpr>",
+                                        self
+                                    ),
+                                );
+                                let monotonic = inner_map["monotonic"].as_bool().unwrap();
+                                let expected_group_size = &inner_map["expected_group_size"];
+                                if monotonic || !expected_group_size.is_null() {
+                                    result.push_str(&format!(" {}", monotonic));
+                                }
+                                if !expected_group_size.is_null() {
+                                    result.push_str(&format!(" {}", expected_group_size));
+                                }
+                                result.push(')');
+                                return Some(result);
+                            }
+                            "Negate" | "Threshold" => {
+                                return Some(format!(
+                                    "({} {})",
+                                    variant.to_lowercase(),
+                                    serialize::<MirRelationExpr, _>(
+                                        &inner_map["input"],
+                                        "MirRelationExpr",
+                                        self
+                                    )
+                                ));
+                            }
                             _ => {}
                         }
                     }
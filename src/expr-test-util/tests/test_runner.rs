@@ -77,6 +77,9 @@ mod test {
     use mz_expr_test_util::*;
     use mz_lowertest::{serialize, MzReflect, TestDeserializeContext};
     use mz_ore::result::ResultExt;
+    use mz_repr::adt::numeric::NumericMaxScale;
+    use mz_repr::explain::ExprHumanizer;
+    use mz_repr::ScalarType;
     use serde::de::DeserializeOwned;
     use serde::Serialize;
 
@@ -196,4 +199,79 @@ mod test {
             })
         });
     }
+
+    #[mz_ore::test]
+    fn humanize_scalar_types() {
+        let catalog = TestCatalog::default();
+        let numeric = ScalarType::Numeric {
+            max_scale: Some(NumericMaxScale::try_from(2i64).unwrap()),
+        };
+        for (ty, expected) in [
+            (ScalarType::Int32, "int4"),
+            (ScalarType::Int64, "int8"),
+            (ScalarType::String, "text"),
+            (ScalarType::Numeric { max_scale: None }, "numeric"),
+            (numeric.clone(), "numeric(39,2)"),
+            (ScalarType::Array(Box::new(ScalarType::Int16)), "int2[]"),
+            (
+                ScalarType::List {
+                    element_type: Box::new(numeric),
+                    custom_id: None,
+                },
+                "numeric(39,2) list",
+            ),
+        ] {
+            assert_eq!(catalog.humanize_scalar_type(&ty), expected);
+        }
+        assert_eq!(
+            catalog.humanize_column_type(&ScalarType::Bool.nullable(true)),
+            "bool?"
+        );
+    }
+
+    #[mz_ore::test]
+    fn parse_sql_scalar_type_names() {
+        let names = [
+            "bool",
+            "int2",
+            "int4",
+            "int8",
+            "uint2",
+            "uint4",
+            "uint8",
+            "float4",
+            "float8",
+            "numeric",
+            "date",
+            "time",
+            "timestamp",
+            "timestamptz",
+            "interval",
+            "name",
+            "bytea",
+            "text",
+            "bpchar",
+            "varchar",
+            "jsonb",
+            "uuid",
+            "oid",
+            "regproc",
+            "regtype",
+            "regclass",
+            "int2vector",
+            "mz_timestamp",
+            "mz_aclitem",
+        ];
+        let mut catalog = TestCatalog::default();
+        catalog
+            .handle_test_command(&format!("(defsource s ([{}]))", names.join(" ")))
+            .unwrap();
+        let typ = build_rel("(get s)", &catalog).unwrap().typ();
+        let humanized = typ
+            .column_types
+            .iter()
+            .map(|ty| catalog.humanize_scalar_type(&ty.scalar_type))
+            .collect::<Vec<_>>();
+        assert_eq!(humanized, names);
+    }
 }
@@ -96,6 +96,11 @@ pub enum UnmaterializableFunc {
     MzVersionNum,
     PgBackendPid,
     PgPostmasterStartTime,
+    /// A uniformly distributed random value in `[0, 1)`.
+    ///
+    /// Unlike other unmaterializable functions, this is not replaced by a
+    /// constant before evaluation, and yields a new value for every row.
+    Random,
     SessionUser,
     Version,
     ViewableVariables,
@@ -136,6 +141,7 @@ impl UnmaterializableFunc {
             UnmaterializableFunc::MzVersionNum => ScalarType::Int32.nullable(false),
             UnmaterializableFunc::PgBackendPid => ScalarType::Int32.nullable(false),
             UnmaterializableFunc::PgPostmasterStartTime => ScalarType::TimestampTz.nullable(false),
+            UnmaterializableFunc::Random => ScalarType::Float64.nullable(false),
             UnmaterializableFunc::SessionUser => ScalarType::String.nullable(false),
             UnmaterializableFunc::Version => ScalarType::String.nullable(false),
             UnmaterializableFunc::ViewableVariables => ScalarType::Map {
@@ -169,6 +175,7 @@ impl fmt::Display for UnmaterializableFunc {
             UnmaterializableFunc::MzVersionNum => f.write_str("mz_version_num"),
             UnmaterializableFunc::PgBackendPid => f.write_str("pg_backend_pid"),
             UnmaterializableFunc::PgPostmasterStartTime => f.write_str("pg_postmaster_start_time"),
+            UnmaterializableFunc::Random => f.write_str("random"),
             UnmaterializableFunc::SessionUser => f.write_str("session_user"),
             UnmaterializableFunc::Version => f.write_str("version"),
             UnmaterializableFunc::ViewableVariables => f.write_str("viewable_variables"),
@@ -198,6 +205,7 @@ impl RustType<ProtoUnmaterializableFunc> for UnmaterializableFunc {
             UnmaterializableFunc::MzVersionNum => MzVersionNum(()),
             UnmaterializableFunc::PgBackendPid => PgBackendPid(()),
             UnmaterializableFunc::PgPostmasterStartTime => PgPostmasterStartTime(()),
+            UnmaterializableFunc::Random => Random(()),
             UnmaterializableFunc::SessionUser => SessionUser(()),
             UnmaterializableFunc::Version => Version(()),
         };
@@ -228,6 +236,7 @@ impl RustType<ProtoUnmaterializableFunc> for UnmaterializableFunc {
                 MzVersionNum(()) => Ok(UnmaterializableFunc::MzVersionNum),
                 PgBackendPid(()) => Ok(UnmaterializableFunc::PgBackendPid),
                 PgPostmasterStartTime(()) => Ok(UnmaterializableFunc::PgPostmasterStartTime),
+                Random(()) => Ok(UnmaterializableFunc::Random),
                 SessionUser(()) => Ok(UnmaterializableFunc::SessionUser),
                 Version(()) => Ok(UnmaterializableFunc::Version),
             }
@@ -1754,6 +1754,11 @@ impl MirScalarExpr {
                 Ok(row) => Ok(row.unpack_first()),
                 Err(e) => Err(e.clone()),
             },
+            // `random` is the exception, as it produces a new value for every
+            // row.
+            MirScalarExpr::CallUnmaterializable(UnmaterializableFunc::Random) => {
+                Ok(Datum::from(rand::random::<f64>()))
+            }
             // Unmaterializable functions must be transformed away before
             // evaluation. Their purpose is as a placeholder for data that is
             // not known at plan time but can be inlined before runtime.
@@ -1790,6 +1795,19 @@ impl MirScalarExpr {
         contains
     }
 
+    /// True iff the expression contains
+    /// `UnmaterializableFunc::Random`.
+    pub fn contains_random(&self) -> bool {
+        let mut contains = false;
+        #[allow(deprecated)]
+        self.visit_post_nolimit(&mut |e| {
+            if let MirScalarExpr::CallUnmaterializable(UnmaterializableFunc::Random) = e {
+                contains = true;
+            }
+        });
+        contains
+    }
+
     /// True iff the expression contains an `UnmaterializableFunc`.
     pub fn contains_unmaterializable(&self) -> bool {
         let mut contains = false;
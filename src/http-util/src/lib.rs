@@ -75,26 +75,74 @@
 
 //! HTTP utilities.
 
+use std::fmt;
+use std::future::{ready, Future, Ready};
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
 use askama::Template;
+use axum::body::StreamBody;
 use axum::http::status::StatusCode;
 use axum::http::HeaderValue;
 use axum::response::{Html, IntoResponse};
 use axum::{Json, TypedHeader};
+use futures::stream::{self, StreamExt};
 use headers::ContentType;
+use http::header::{HeaderName, CACHE_CONTROL};
+use http::{Request, Response};
 use mz_ore::metrics::MetricsRegistry;
 use mz_ore::tracing::TracingHandle;
+use pin_project::pin_project;
 use prometheus::Encoder;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
+use tower::{Layer, Service};
 use tower_http::cors::AllowOrigin;
+use tower_http::limit::RequestBodyLimitLayer;
+use tracing::error;
 use tracing_subscriber::EnvFilter;
+use uuid::Uuid;
 
 /// Renders a template into an HTTP response.
-pub fn template_response<T>(template: T) -> Html<String>
+///
+/// If rendering fails, returns a [`TemplateError`], which converts into a 500
+/// response.
+pub fn template_response<T>(template: T) -> Result<Html<String>, TemplateError>
 where
     T: Template,
 {
-    Html(template.render().expect("template rendering cannot fail"))
+    Ok(Html(template.render().map_err(TemplateError)?))
+}
+
+/// Like [`template_response`], but panics if rendering fails.
+pub fn template_response_or_panic<T>(template: T) -> Html<String>
+where
+    T: Template,
+{
+    template_response(template).expect("template rendering cannot fail")
+}
+
+/// An error that occurred while rendering a template.
+#[derive(Debug)]
+pub struct TemplateError(pub askama::Error);
+
+impl fmt::Display for TemplateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "template rendering failed: {}", self.0)
+    }
+}
+
+impl std::error::Error for TemplateError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+impl IntoResponse for TemplateError {
+    fn into_response(self) -> axum::response::Response {
+        error!("{}", self);
+        (StatusCode::INTERNAL_SERVER_ERROR, self.to_string()).into_response()
+    }
 }
 
 #[macro_export]
@@ -170,14 +218,97 @@ pub async fn handle_liveness_check() -> impl IntoResponse {
 /// Serves metrics from the selected metrics registry variant.
 #[allow(clippy::unused_async)]
 pub async fn handle_prometheus(registry: &MetricsRegistry) -> impl IntoResponse {
+    encode_prometheus(registry.gather())
+}
+
+/// Like [`handle_prometheus`], but streams the response body.
+///
+/// Each metric family is encoded only when the response body is polled, so
+/// the encoded form of the full registry is never held in memory at once.
+/// Prefer this variant for registries that may be large.
+#[allow(clippy::unused_async)]
+pub async fn handle_prometheus_streaming(registry: &MetricsRegistry) -> impl IntoResponse {
+    stream_prometheus(registry.gather())
+}
+
+/// The number of time series above which [`handle_prometheus_adaptive`]
+/// streams its response.
+pub const PROMETHEUS_STREAMING_THRESHOLD: usize = 10_000;
+
+/// Like [`handle_prometheus`], but streams the response body, as
+/// [`handle_prometheus_streaming`] does, if the registry contains more than
+/// `streaming_threshold` time series.
+///
+/// Small registries are cheap to encode in one go, and a buffered response
+/// carries a `Content-Length` that some scrapers rely on.
+#[allow(clippy::unused_async)]
+pub async fn handle_prometheus_adaptive(
+    registry: &MetricsRegistry,
+    streaming_threshold: usize,
+) -> axum::response::Response {
+    let families = registry.gather();
+    let series: usize = families.iter().map(|f| f.get_metric().len()).sum();
+    if series > streaming_threshold {
+        stream_prometheus(families).into_response()
+    } else {
+        encode_prometheus(families).into_response()
+    }
+}
+
+fn encode_prometheus(families: Vec<prometheus::proto::MetricFamily>) -> impl IntoResponse {
     let mut buffer = Vec::new();
     let encoder = prometheus::TextEncoder::new();
     encoder
-        .encode(&registry.gather(), &mut buffer)
+        .encode(&families, &mut buffer)
         .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
     Ok::<_, (StatusCode, String)>((TypedHeader(ContentType::text()), buffer))
 }
 
+fn stream_prometheus(families: Vec<prometheus::proto::MetricFamily>) -> impl IntoResponse {
+    let encoder = prometheus::TextEncoder::new();
+    let chunks = stream::iter(families).map(move |family| {
+        let mut buffer = Vec::new();
+        encoder.encode(&[family], &mut buffer)?;
+        Ok::<_, prometheus::Error>(buffer)
+    });
+    (TypedHeader(ContentType::text()), StreamBody::new(chunks))
+}
+
+/// The `Cache-Control` directive sent with documents served by
+/// [`serve_openapi`].
+///
+/// The document is embedded in the binary and so can only change across
+/// deploys; clients may cache it for a day without revalidating.
+const OPENAPI_CACHE_CONTROL: &str = "public, max-age=86400";
+
+/// Constructs a handler that serves a static OpenAPI document.
+///
+/// The document is served verbatim with an `application/json` content type
+/// and a `Cache-Control` header that permits clients and proxies to cache it.
+///
+/// # Panics
+///
+/// Panics if `doc` is not valid JSON. The document is validated when the
+/// handler is constructed, so an invalid document is caught while building
+/// the router at startup rather than when a client first requests it.
+pub fn serve_openapi(
+    doc: &'static str,
+) -> impl Fn() -> Ready<axum::response::Response> + Clone + Send + Sync + 'static {
+    if let Err(e) = serde_json::from_str::<serde_json::Value>(doc) {
+        panic!("OpenAPI document is not valid JSON: {e}");
+    }
+    move || {
+        ready(
+            (
+                TypedHeader(ContentType::json()),
+                [(CACHE_CONTROL, OPENAPI_CACHE_CONTROL)],
+                doc,
+            )
+                .into_response(),
+        )
+    }
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct DynamicFilterTarget {
     targets: String,
@@ -210,6 +341,118 @@ pub async fn handle_tracing() -> impl IntoResponse {
     )
 }
 
+/// Constructs a layer that limits request bodies to `max_bytes` bytes.
+///
+/// Requests that declare a `Content-Length` over the limit are rejected with
+/// `413 Payload Too Large` without invoking the wrapped service. Bodies that
+/// do not declare their length are cut off once they exceed the limit; axum's
+/// body extractors, like [`Json`], turn the resulting error into a `413`
+/// response as well.
+///
+/// Unlike [`axum::extract::DefaultBodyLimit`], which only axum's extractors
+/// consult, the limit applies to handlers that read the body directly. The
+/// wrapped service sees request bodies of type `http_body::Limited<B>`.
+pub fn request_body_limit(max_bytes: usize) -> RequestBodyLimitLayer {
+    RequestBodyLimitLayer::new(max_bytes)
+}
+
+/// The name of the header that carries request IDs.
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// The ID of an HTTP request, as assigned by [`RequestIdLayer`].
+///
+/// Available as a request extension to services wrapped by the layer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub HeaderValue);
+
+/// A layer that assigns an ID to each request.
+///
+/// The ID is taken from the request's `X-Request-Id` header, if present, and
+/// is otherwise a freshly generated UUID. The ID is stored in the request's
+/// extensions as a [`RequestId`] and echoed in the `X-Request-Id` header of
+/// the response, so that log lines for a request can be correlated across
+/// services.
+#[derive(Debug, Clone, Default)]
+pub struct RequestIdLayer;
+
+impl RequestIdLayer {
+    pub fn new() -> Self {
+        RequestIdLayer
+    }
+}
+
+impl<S> Layer<S> for RequestIdLayer {
+    type Service = RequestIdService<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        RequestIdService { service }
+    }
+}
+
+/// The service produced by [`RequestIdLayer`].
+#[derive(Debug, Clone)]
+pub struct RequestIdService<S> {
+    service: S,
+}
+
+impl<S, B, ResBody> Service<Request<B>> for RequestIdService<S>
+where
+    S: Service<Request<B>, Response = Response<ResBody>>,
+{
+    type Error = S::Error;
+    type Response = S::Response;
+    type Future = RequestIdFuture<S::Future>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<B>) -> Self::Future {
+        let id = match req.headers().get(REQUEST_ID_HEADER) {
+            Some(id) => id.clone(),
+            None => {
+                let id = Uuid::new_v4().to_string();
+                HeaderValue::try_from(id).expect("UUIDs are valid header values")
+            }
+        };
+        req.extensions_mut().insert(RequestId(id.clone()));
+        RequestIdFuture {
+            id: Some(id),
+            fut: self.service.call(req),
+        }
+    }
+}
+
+/// The future returned by [`RequestIdService`].
+#[pin_project]
+pub struct RequestIdFuture<F> {
+    /// The ID to echo in the response.
+    id: Option<HeaderValue>,
+    /// Inner request future.
+    #[pin]
+    fut: F,
+}
+
+impl<F, ResBody, E> Future for RequestIdFuture<F>
+where
+    F: Future<Output = Result<Response<ResBody>, E>>,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.project();
+        let mut res = match this.fut.poll(cx) {
+            Poll::Ready(Ok(res)) => res,
+            other => return other,
+        };
+        if let Some(id) = this.id.take() {
+            res.headers_mut()
+                .insert(HeaderName::from_static(REQUEST_ID_HEADER), id);
+        }
+        Poll::Ready(Ok(res))
+    }
+}
+
 /// Construct a CORS policy to allow origins to query us via HTTP. If any bare
 /// '*' is passed, this allows any origin; otherwise, allows a list of origins,
 /// which can include wildcard subdomains. If the allowed origin starts with a
@@ -236,13 +479,257 @@ where
     }
 }
 
+/// Parses an allowed origin for [`build_cors_allowed_origin`].
+///
+/// The origin must be a bare `*`, a wildcard subdomain like `*.domain.com`,
+/// or an origin like `https://domain.com:8080` with a scheme, host, and
+/// optional port but no path.
+pub fn parse_cors_allowed_origin(s: &str) -> Result<HeaderValue, anyhow::Error> {
+    const EXPECTED_ORIGIN: &str = "expected an origin like \"https://domain.com\"";
+    let invalid = |reason: &str| anyhow::anyhow!("invalid CORS origin {s:?}: {reason}");
+    let value = HeaderValue::from_str(s).map_err(|_| invalid("not a valid header value"))?;
+    if s == "*" {
+        return Ok(value);
+    }
+    if let Some(suffix) = s.strip_prefix("*.") {
+        if suffix.is_empty() || suffix.contains(['*', '/', ':']) {
+            return Err(invalid(
+                "wildcard origins must have the form \"*.domain.com\"",
+            ));
+        }
+        return Ok(value);
+    }
+    if s.contains('*') {
+        return Err(invalid("wildcards are only permitted as a leading \"*.\""));
+    }
+    let uri: http::Uri = s.parse().map_err(|_| invalid(EXPECTED_ORIGIN))?;
+    let authority = match (uri.scheme(), uri.authority()) {
+        (Some(_), Some(authority)) => authority,
+        _ => return Err(invalid(EXPECTED_ORIGIN)),
+    };
+    if uri.path() != "/" || uri.query().is_some() || s.ends_with('/') {
+        return Err(invalid("origins must not include a path"));
+    }
+    if authority.as_str().contains('@') {
+        return Err(invalid("origins must not include user information"));
+    }
+    Ok(value)
+}
+
 #[cfg(test)]
 mod tests {
-    use http::header::{ACCESS_CONTROL_ALLOW_ORIGIN, ORIGIN};
-    use http::{HeaderValue, Method, Request, Response};
+    use std::fmt;
+
+    use askama::Template;
+    use axum::response::IntoResponse;
+    use axum::{routing, Json, Router};
+    use http::header::{
+        ACCESS_CONTROL_ALLOW_ORIGIN, CACHE_CONTROL, CONTENT_LENGTH, CONTENT_TYPE, ORIGIN,
+    };
+    use http::{HeaderValue, Method, Request, Response, StatusCode};
+    use hyper::body::HttpBody;
     use hyper::Body;
+    use mz_ore::cast::CastFrom;
+    use mz_ore::metric;
+    use mz_ore::metrics::raw::IntCounterVec;
+    use mz_ore::metrics::MetricsRegistry;
     use tower::{Service, ServiceBuilder, ServiceExt};
     use tower_http::cors::CorsLayer;
+    use uuid::Uuid;
+
+    use super::{
+        parse_cors_allowed_origin, DynamicFilterTarget, RequestId, RequestIdLayer,
+        REQUEST_ID_HEADER,
+    };
+
+    #[mz_ore::test(tokio::test)]
+    async fn test_template_response_error() {
+        /// A value whose `Display` implementation always fails.
+        struct Failing;
+
+        impl fmt::Display for Failing {
+            fn fmt(&self, _: &mut fmt::Formatter) -> fmt::Result {
+                Err(fmt::Error)
+            }
+        }
+
+        /// A template that renders a value, which may fail.
+        struct ValueTemplate<T>(T);
+
+        impl<T: fmt::Display> fmt::Display for ValueTemplate<T> {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                self.render_into(f).map_err(|_| fmt::Error)
+            }
+        }
+
+        impl<T: fmt::Display> Template for ValueTemplate<T> {
+            fn render_into(&self, writer: &mut (impl fmt::Write + ?Sized)) -> askama::Result<()> {
+                write!(writer, "{}", self.0)?;
+                Ok(())
+            }
+
+            const EXTENSION: Option<&'static str> = Some("html");
+            const SIZE_HINT: usize = 0;
+            const MIME_TYPE: &'static str = "text/html";
+        }
+
+        let response = super::template_response(ValueTemplate("hello")).into_response();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(body, "hello");
+
+        let response = super::template_response(ValueTemplate(Failing)).into_response();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[mz_ore::test(tokio::test)]
+    async fn test_prometheus_streaming() {
+        let registry = MetricsRegistry::new();
+        let counter: IntCounterVec = registry.register(metric!(
+            name: "mz_test_requests",
+            help: "number of test requests",
+            var_labels: ["path"],
+        ));
+        counter.with_label_values(&["/a"]).inc();
+        counter.with_label_values(&["/b"]).inc_by(2);
+        let other: IntCounterVec = registry.register(metric!(
+            name: "mz_test_other",
+            help: "another test metric",
+            var_labels: ["kind"],
+        ));
+        other.with_label_values(&["x"]).inc();
+
+        let buffered = super::handle_prometheus(&registry).await.into_response();
+        assert_eq!(buffered.status(), StatusCode::OK);
+        let buffered = hyper::body::to_bytes(buffered.into_body()).await.unwrap();
+
+        let streamed = super::handle_prometheus_streaming(&registry)
+            .await
+            .into_response();
+        assert_eq!(streamed.status(), StatusCode::OK);
+        let streamed = hyper::body::to_bytes(streamed.into_body()).await.unwrap();
+
+        assert!(!buffered.is_empty());
+        assert_eq!(buffered, streamed);
+    }
+
+    #[mz_ore::test(tokio::test)]
+    async fn test_prometheus_adaptive() {
+        let registry = MetricsRegistry::new();
+        let counter: IntCounterVec = registry.register(metric!(
+            name: "mz_test_requests",
+            help: "number of test requests",
+            var_labels: ["path"],
+        ));
+        for path in ["/a", "/b", "/c"] {
+            counter.with_label_values(&[path]).inc();
+        }
+        let expected = super::handle_prometheus(&registry).await.into_response();
+        let expected = hyper::body::to_bytes(expected.into_body()).await.unwrap();
+
+        // At or below the threshold, the response is buffered, and so has a
+        // known length.
+        let buffered = super::handle_prometheus_adaptive(&registry, 3).await;
+        assert_eq!(buffered.status(), StatusCode::OK);
+        assert_eq!(
+            buffered.body().size_hint().exact(),
+            Some(u64::cast_from(expected.len()))
+        );
+        let buffered = hyper::body::to_bytes(buffered.into_body()).await.unwrap();
+        assert_eq!(buffered, expected);
+
+        // Above the threshold, the response is streamed.
+        let streamed = super::handle_prometheus_adaptive(&registry, 2).await;
+        assert_eq!(streamed.status(), StatusCode::OK);
+        assert_eq!(streamed.body().size_hint().exact(), None);
+        let streamed = hyper::body::to_bytes(streamed.into_body()).await.unwrap();
+        assert_eq!(streamed, expected);
+    }
+
+    #[mz_ore::test(tokio::test)]
+    async fn test_serve_openapi() {
+        const DOC: &str = r#"{"openapi": "3.0.0", "info": {"title": "test", "version": "1"}}"#;
+
+        let handler = super::serve_openapi(DOC);
+        let response = handler().await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[CONTENT_TYPE], "application/json");
+        assert_eq!(response.headers()[CACHE_CONTROL], "public, max-age=86400");
+        let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+        assert_eq!(body, DOC);
+    }
+
+    #[mz_ore::test]
+    #[should_panic(expected = "OpenAPI document is not valid JSON")]
+    fn test_serve_openapi_invalid() {
+        let _ = super::serve_openapi(r#"{"openapi": "3.0.0""#);
+    }
+
+    #[mz_ore::test(tokio::test)]
+    async fn test_request_body_limit() {
+        async fn test_request(body: &'static str, content_length: bool) -> StatusCode {
+            let router = Router::new()
+                .route(
+                    "/",
+                    routing::put(|Json(cfg): Json<DynamicFilterTarget>| async { cfg.targets }),
+                )
+                .layer(super::request_body_limit(32));
+            let mut request = Request::builder()
+                .method(Method::PUT)
+                .header(CONTENT_TYPE, "application/json");
+            if content_length {
+                request = request.header(CONTENT_LENGTH, body.len());
+            }
+            let request = request.body(Body::from(body)).unwrap();
+            router.oneshot(request).await.unwrap().status()
+        }
+
+        const SMALL: &str = r#"{"targets": "info"}"#;
+        const LARGE: &str = r#"{"targets": "info,mz_http_util=debug,mz_ore=trace"}"#;
+
+        for content_length in [true, false] {
+            assert_eq!(test_request(SMALL, content_length).await, StatusCode::OK);
+            assert_eq!(
+                test_request(LARGE, content_length).await,
+                StatusCode::PAYLOAD_TOO_LARGE
+            );
+        }
+    }
+
+    #[mz_ore::test(tokio::test)]
+    async fn test_request_id() {
+        async fn test_request(id: Option<&'static str>) -> (HeaderValue, HeaderValue) {
+            let mut service = ServiceBuilder::new()
+                .layer(RequestIdLayer::new())
+                .service_fn(|req: Request<Body>| async move {
+                    // Report the ID that the layer stored in the request
+                    // extensions in the response body.
+                    let RequestId(id) = req.extensions().get::<RequestId>().unwrap();
+                    Ok::<_, anyhow::Error>(Response::new(Body::from(id.as_bytes().to_vec())))
+                });
+            let mut request = Request::builder();
+            if let Some(id) = id {
+                request = request.header(REQUEST_ID_HEADER, id);
+            }
+            let request = request.body(Body::empty()).unwrap();
+            let response = service.ready().await.unwrap().call(request).await.unwrap();
+            let echoed = response.headers().get(REQUEST_ID_HEADER).unwrap().clone();
+            let body = hyper::body::to_bytes(response.into_body()).await.unwrap();
+            (echoed, HeaderValue::from_bytes(&body).unwrap())
+        }
+
+        // A request without an ID is assigned a fresh UUID.
+        let (echoed, extension) = test_request(None).await;
+        assert_eq!(echoed, extension);
+        Uuid::parse_str(echoed.to_str().unwrap()).unwrap();
+        let (echoed2, _) = test_request(None).await;
+        assert_ne!(echoed, echoed2);
+
+        // A request with an ID keeps it.
+        let (echoed, extension) = test_request(Some("my-request")).await;
+        assert_eq!(echoed, "my-request");
+        assert_eq!(extension, "my-request");
+    }
 
     #[mz_ore::test(tokio::test)]
     async fn test_cors() {
@@ -362,4 +849,54 @@ mod tests {
             }
         }
     }
+
+    #[mz_ore::test]
+    fn test_parse_cors_allowed_origin() {
+        for valid in [
+            "*",
+            "*.materialize.com",
+            "https://materialize.com",
+            "http://localhost:6876",
+            "http://[::1]:6876",
+        ] {
+            let origin = parse_cors_allowed_origin(valid).unwrap();
+            assert_eq!(origin, valid);
+        }
+
+        for (invalid, reason) in [
+            ("", "expected an origin like \"https://domain.com\""),
+            (
+                "materialize.com",
+                "expected an origin like \"https://domain.com\"",
+            ),
+            (
+                "https://materialize.com/",
+                "origins must not include a path",
+            ),
+            (
+                "https://materialize.com/login",
+                "origins must not include a path",
+            ),
+            (
+                "https://user@materialize.com",
+                "origins must not include user information",
+            ),
+            (
+                "https://*.materialize.com",
+                "wildcards are only permitted as a leading \"*.\"",
+            ),
+            ("*.", "wildcard origins must have the form \"*.domain.com\""),
+            (
+                "*.materialize.com/login",
+                "wildcard origins must have the form \"*.domain.com\"",
+            ),
+            ("https://materialize.com\n", "not a valid header value"),
+        ] {
+            let err = parse_cors_allowed_origin(invalid).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!("invalid CORS origin {invalid:?}: {reason}")
+            );
+        }
+    }
 }
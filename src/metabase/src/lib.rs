@@ -89,9 +89,9 @@
 use std::fmt;
 use std::time::Duration;
 
-use reqwest::{IntoUrl, Url};
+use reqwest::{IntoUrl, StatusCode, Url};
 use serde::de::DeserializeOwned;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 /// A Metabase API client.
 #[derive(Debug)]
@@ -108,6 +108,18 @@ impl Client {
     ///
     /// [cannot-be-a-base]: https://url.spec.whatwg.org/#url-cannot-be-a-base-url-flag
     pub fn new<U>(url: U) -> Result<Self, Error>
+    where
+        U: IntoUrl,
+    {
+        Self::with_http_client(reqwest::Client::new(), url)
+    }
+
+    /// Like [`Client::new`], but issues requests using the provided HTTP
+    /// client.
+    ///
+    /// This permits configuring the underlying HTTP client, e.g. to route
+    /// requests through a proxy or to trust additional root certificates.
+    pub fn with_http_client<U>(inner: reqwest::Client, url: U) -> Result<Self, Error>
     where
         U: IntoUrl,
     {
@@ -120,7 +132,7 @@ impl Client {
             .expect("cannot-be-a-base checked to be false")
             .push("api");
         Ok(Client {
-            inner: reqwest::Client::new(),
+            inner,
             url,
             session_id: None,
         })
@@ -164,6 +176,21 @@ impl Client {
         self.send_request(self.inner.post(url).json(request)).await
     }
 
+    /// Creates a new database connection.
+    ///
+    /// Unlike [`Client::setup`], this may be called on a Metabase instance that
+    /// has already been set up, but requires that the client be authenticated
+    /// with a session ID.
+    ///
+    /// The underlying API call is `POST /database`.
+    pub async fn create_database(
+        &self,
+        request: &CreateDatabaseRequest,
+    ) -> Result<Database, reqwest::Error> {
+        let url = self.api_url(&["database"]);
+        self.send_request(self.inner.post(url).json(request)).await
+    }
+
     /// Fetches the list of databases known to Metabase.
     ///
     /// The underlying API call is `GET /database`.
@@ -181,6 +208,18 @@ impl Client {
         self.send_request(self.inner.get(url)).await
     }
 
+    /// Triggers a manual sync of the schema of a particular database.
+    ///
+    /// The sync happens asynchronously; this method returns as soon as
+    /// Metabase has accepted the request.
+    ///
+    /// The underlying API call is `POST /database/:id/sync_schema`.
+    pub async fn sync_database_schema(&self, id: usize) -> Result<(), reqwest::Error> {
+        let url = self.api_url(&["database", &id.to_string(), "sync_schema"]);
+        self.send_request_raw(self.inner.post(url)).await?;
+        Ok(())
+    }
+
     fn api_url(&self, endpoint: &[&str]) -> Url {
         let mut url = self.url.clone();
         url.path_segments_mut()
@@ -189,16 +228,37 @@ impl Client {
         url
     }
 
-    async fn send_request<T>(&self, mut req: reqwest::RequestBuilder) -> Result<T, reqwest::Error>
+    async fn send_request<T>(&self, req: reqwest::RequestBuilder) -> Result<T, reqwest::Error>
+    where
+        T: DeserializeOwned,
+    {
+        let (res, _status) = self.send_request_with_status(req).await?;
+        Ok(res)
+    }
+
+    /// Like `send_request`, but additionally returns the HTTP status of the
+    /// successful response.
+    async fn send_request_with_status<T>(
+        &self,
+        req: reqwest::RequestBuilder,
+    ) -> Result<(T, StatusCode), reqwest::Error>
     where
         T: DeserializeOwned,
     {
+        let res = self.send_request_raw(req).await?;
+        let status = res.status();
+        Ok((res.json().await?, status))
+    }
+
+    async fn send_request_raw(
+        &self,
+        mut req: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, reqwest::Error> {
         req = req.timeout(Duration::from_secs(5));
         if let Some(session_id) = &self.session_id {
             req = req.header("X-Metabase-Session", session_id);
         }
-        let res = req.send().await?.error_for_status()?;
-        res.json().await
+        req.send().await?.error_for_status()
     }
 }
 
@@ -260,12 +320,72 @@ pub struct SetupRequest {
 /// A database to create as part of a [`SetupRequest`].
 #[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
 pub struct SetupDatabase {
-    pub engine: String,
+    pub engine: DatabaseEngine,
     pub name: String,
     pub details: SetupDatabaseDetails,
 }
 
-/// Details for a [`SetupDatabase`].
+/// The engine of a [`SetupDatabase`] or [`CreateDatabaseRequest`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum DatabaseEngine {
+    /// PostgreSQL.
+    Postgres,
+    /// Materialize, via the Materialize Metabase driver.
+    Materialize,
+    /// MySQL.
+    Mysql,
+    /// Any other engine, identified by its Metabase engine name.
+    Other(String),
+}
+
+impl DatabaseEngine {
+    /// Returns the name that Metabase uses for the engine.
+    pub fn as_str(&self) -> &str {
+        match self {
+            DatabaseEngine::Postgres => "postgres",
+            DatabaseEngine::Materialize => "materialize",
+            DatabaseEngine::Mysql => "mysql",
+            DatabaseEngine::Other(name) => name,
+        }
+    }
+}
+
+impl From<String> for DatabaseEngine {
+    fn from(name: String) -> DatabaseEngine {
+        match name.as_str() {
+            "postgres" => DatabaseEngine::Postgres,
+            "materialize" => DatabaseEngine::Materialize,
+            "mysql" => DatabaseEngine::Mysql,
+            _ => DatabaseEngine::Other(name),
+        }
+    }
+}
+
+impl fmt::Display for DatabaseEngine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl Serialize for DatabaseEngine {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for DatabaseEngine {
+    fn deserialize<D>(deserializer: D) -> Result<DatabaseEngine, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        String::deserialize(deserializer).map(DatabaseEngine::from)
+    }
+}
+
+/// Details for a [`SetupDatabase`] or [`CreateDatabaseRequest`].
 #[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
 pub struct SetupDatabaseDetails {
     pub host: String,
@@ -274,6 +394,14 @@ pub struct SetupDatabaseDetails {
     pub user: String,
 }
 
+/// The request for [`Client::create_database`].
+#[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
+pub struct CreateDatabaseRequest {
+    pub engine: DatabaseEngine,
+    pub name: String,
+    pub details: SetupDatabaseDetails,
+}
+
 /// Preferences for a [`SetupRequest`].
 #[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
 pub struct SetupPrefs {
@@ -303,7 +431,8 @@ pub struct LoginResponse {
     pub id: String,
 }
 
-/// A database returned by [`Client::databases`].
+/// A database returned by [`Client::databases`] or
+/// [`Client::create_database`].
 #[derive(Clone, Debug, Deserialize, Serialize, Eq, PartialEq)]
 pub struct Database {
     pub name: String,
@@ -332,3 +461,40 @@ pub struct TableField {
     pub base_type: String,
     pub special_type: Option<String>,
 }
+
+#[cfg(test)]
+mod tests {
+    use hyper::server::conn::AddrIncoming;
+    use hyper::{service, Body, Response, Server};
+
+    use super::*;
+
+    #[mz_ore::test(tokio::test)]
+    #[cfg_attr(miri, ignore)] // unsupported operation: can't call foreign function `socket` on OS `linux`
+    async fn test_send_request_with_status() -> Result<(), anyhow::Error> {
+        let incoming = AddrIncoming::bind(&([127, 0, 0, 1], 0).into())?;
+        let addr = incoming.local_addr();
+        let server = Server::builder(incoming).serve(service::make_service_fn(|_conn| async {
+            Ok::<_, hyper::Error>(service::service_fn(|_req| async {
+                Response::builder()
+                    .status(202)
+                    .body(Body::from(r#"{"setup-token": null}"#))
+            }))
+        }));
+        mz_ore::task::spawn(|| "start_server", async {
+            if let Err(err) = server.await {
+                eprintln!("server error: {}", err);
+            }
+        });
+
+        let client = Client::new(format!("http://{}", addr))?;
+        let url = client.api_url(&["session", "properties"]);
+        let (res, status) = client
+            .send_request_with_status::<SessionPropertiesResponse>(client.inner.get(url))
+            .await?;
+        assert_eq!(res, SessionPropertiesResponse { setup_token: None });
+        assert_eq!(status, StatusCode::ACCEPTED);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,257 @@
+// Copyright Materialize, Inc. and contributors. All rights reserved.
+//
+// Use of this software is governed by the Business Source License
+// included in the LICENSE file.
+//
+// As of the Change Date specified in that file, in accordance with
+// the Business Source License, use of this software will be governed
+// by the Apache License, Version 2.0.
+
+// BEGIN LINT CONFIG
+// DO NOT EDIT. Automatically generated by bin/gen-lints.
+// Have complaints about the noise? See the note in misc/python/materialize/cli/gen-lints.py first.
+#![allow(clippy::style)]
+#![allow(clippy::complexity)]
+#![allow(clippy::large_enum_variant)]
+#![allow(clippy::mutable_key_type)]
+#![allow(clippy::stable_sort_primitive)]
+#![allow(clippy::map_entry)]
+#![allow(clippy::box_default)]
+#![warn(clippy::bool_comparison)]
+#![warn(clippy::clone_on_ref_ptr)]
+#![warn(clippy::no_effect)]
+#![warn(clippy::unnecessary_unwrap)]
+#![warn(clippy::dbg_macro)]
+#![warn(clippy::todo)]
+#![warn(clippy::wildcard_dependencies)]
+#![warn(clippy::zero_prefixed_literal)]
+#![warn(clippy::borrowed_box)]
+#![warn(clippy::deref_addrof)]
+#![warn(clippy::double_must_use)]
+#![warn(clippy::double_parens)]
+#![warn(clippy::extra_unused_lifetimes)]
+#![warn(clippy::needless_borrow)]
+#![warn(clippy::needless_question_mark)]
+#![warn(clippy::needless_return)]
+#![warn(clippy::redundant_pattern)]
+#![warn(clippy::redundant_slicing)]
+#![warn(clippy::redundant_static_lifetimes)]
+#![warn(clippy::single_component_path_imports)]
+#![warn(clippy::unnecessary_cast)]
+#![warn(clippy::useless_asref)]
+#![warn(clippy::useless_conversion)]
+#![warn(clippy::builtin_type_shadow)]
+#![warn(clippy::duplicate_underscore_argument)]
+#![warn(clippy::double_neg)]
+#![warn(clippy::unnecessary_mut_passed)]
+#![warn(clippy::wildcard_in_or_patterns)]
+#![warn(clippy::crosspointer_transmute)]
+#![warn(clippy::excessive_precision)]
+#![warn(clippy::overflow_check_conditional)]
+#![warn(clippy::as_conversions)]
+#![warn(clippy::match_overlapping_arm)]
+#![warn(clippy::zero_divided_by_zero)]
+#![warn(clippy::must_use_unit)]
+#![warn(clippy::suspicious_assignment_formatting)]
+#![warn(clippy::suspicious_else_formatting)]
+#![warn(clippy::suspicious_unary_op_formatting)]
+#![warn(clippy::mut_mutex_lock)]
+#![warn(clippy::print_literal)]
+#![warn(clippy::same_item_push)]
+#![warn(clippy::useless_format)]
+#![warn(clippy::write_literal)]
+#![warn(clippy::redundant_closure)]
+#![warn(clippy::redundant_closure_call)]
+#![warn(clippy::unnecessary_lazy_evaluations)]
+#![warn(clippy::partialeq_ne_impl)]
+#![warn(clippy::redundant_field_names)]
+#![warn(clippy::transmutes_expressible_as_ptr_casts)]
+#![warn(clippy::unused_async)]
+#![warn(clippy::disallowed_methods)]
+#![warn(clippy::disallowed_macros)]
+#![warn(clippy::disallowed_types)]
+#![warn(clippy::from_over_into)]
+// END LINT CONFIG
+
+//! Tests for the Metabase API client against a mock Metabase server.
+
+use std::sync::{Arc, Mutex};
+
+use hyper::server::conn::AddrIncoming;
+use hyper::{service, Body, Method, Response, Server};
+use mz_metabase::{
+    Client, CreateDatabaseRequest, Database, DatabaseEngine, Error, SessionPropertiesResponse,
+    SetupDatabaseDetails,
+};
+
+/// A request received by the mock server started by [`start_server`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ReceivedRequest {
+    method: Method,
+    path: String,
+    session_id: Option<String>,
+}
+
+/// Starts a mock Metabase server that responds to every request with `body`.
+///
+/// Returns a client targeting the server, and a log of the requests the server
+/// has received.
+fn start_server(body: &'static str) -> (Client, Arc<Mutex<Vec<ReceivedRequest>>>) {
+    let (url, requests) = start_server_raw(body);
+    (Client::new(url).unwrap(), requests)
+}
+
+/// Like [`start_server`], but returns the URL of the server rather than a
+/// client.
+fn start_server_raw(body: &'static str) -> (String, Arc<Mutex<Vec<ReceivedRequest>>>) {
+    let requests = Arc::new(Mutex::new(vec![]));
+    let addr = {
+        let requests = Arc::clone(&requests);
+        let incoming = AddrIncoming::bind(&([127, 0, 0, 1], 0).into()).unwrap();
+        let addr = incoming.local_addr();
+        let server = Server::builder(incoming).serve(service::make_service_fn(move |_conn| {
+            let requests = Arc::clone(&requests);
+            async move {
+                Ok::<_, hyper::Error>(service::service_fn(move |req| {
+                    let requests = Arc::clone(&requests);
+                    async move {
+                        requests.lock().unwrap().push(ReceivedRequest {
+                            method: req.method().clone(),
+                            path: req.uri().path().into(),
+                            session_id: req
+                                .headers()
+                                .get("X-Metabase-Session")
+                                .map(|v| v.to_str().unwrap().into()),
+                        });
+                        Response::builder().body(Body::from(body))
+                    }
+                }))
+            }
+        }));
+        mz_ore::task::spawn(|| "start_server", async {
+            match server.await {
+                Ok(()) => (),
+                Err(err) => eprintln!("server error: {}", err),
+            }
+        });
+        addr
+    };
+    (format!("http://{}", addr), requests)
+}
+
+#[mz_ore::test]
+fn test_database_engine_serialization() {
+    for (engine, name) in [
+        (DatabaseEngine::Postgres, "postgres"),
+        (DatabaseEngine::Materialize, "materialize"),
+        (DatabaseEngine::Mysql, "mysql"),
+        (DatabaseEngine::Other("h2".into()), "h2"),
+    ] {
+        let json = serde_json::to_string(&engine).unwrap();
+        assert_eq!(json, format!("\"{}\"", name));
+        assert_eq!(
+            serde_json::from_str::<DatabaseEngine>(&json).unwrap(),
+            engine
+        );
+    }
+}
+
+#[mz_ore::test(tokio::test)]
+#[cfg_attr(miri, ignore)] // unsupported operation: can't call foreign function `socket` on OS `linux`
+async fn test_create_database() -> Result<(), anyhow::Error> {
+    let (mut client, requests) = start_server(
+        r#"{
+            "id": 2,
+            "name": "Materialize",
+            "engine": "postgres",
+            "details": {
+                "host": "materialized",
+                "port": 6875,
+                "dbname": "materialize",
+                "user": "materialize"
+            },
+            "is_full_sync": true,
+            "features": ["basic-aggregations", "standard-deviation-aggregations"]
+        }"#,
+    );
+    client.set_session_id("session".into());
+
+    let database = client
+        .create_database(&CreateDatabaseRequest {
+            engine: DatabaseEngine::Postgres,
+            name: "Materialize".into(),
+            details: SetupDatabaseDetails {
+                host: "materialized".into(),
+                port: 6875,
+                dbname: "materialize".into(),
+                user: "materialize".into(),
+            },
+        })
+        .await?;
+    assert_eq!(
+        database,
+        Database {
+            name: "Materialize".into(),
+            id: 2,
+        }
+    );
+    assert_eq!(
+        *requests.lock().unwrap(),
+        vec![ReceivedRequest {
+            method: Method::POST,
+            path: "/api/database".into(),
+            session_id: Some("session".into()),
+        }]
+    );
+
+    Ok(())
+}
+
+#[mz_ore::test(tokio::test)]
+#[cfg_attr(miri, ignore)] // unsupported operation: can't call foreign function `socket` on OS `linux`
+async fn test_sync_database_schema() -> Result<(), anyhow::Error> {
+    let (client, requests) = start_server("");
+
+    client.sync_database_schema(7).await?;
+    assert_eq!(
+        *requests.lock().unwrap(),
+        vec![ReceivedRequest {
+            method: Method::POST,
+            path: "/api/database/7/sync_schema".into(),
+            session_id: None,
+        }]
+    );
+
+    Ok(())
+}
+
+#[mz_ore::test(tokio::test)]
+#[cfg_attr(miri, ignore)] // unsupported operation: can't call foreign function `socket` on OS `linux`
+async fn test_with_http_client() -> Result<(), anyhow::Error> {
+    let (url, requests) = start_server_raw(r#"{"setup-token": "token"}"#);
+    let inner = reqwest::Client::builder().no_proxy().build()?;
+
+    let client = Client::with_http_client(inner.clone(), url)?;
+    assert_eq!(
+        client.session_properties().await?,
+        SessionPropertiesResponse {
+            setup_token: Some("token".into()),
+        }
+    );
+    assert_eq!(
+        *requests.lock().unwrap(),
+        vec![ReceivedRequest {
+            method: Method::GET,
+            path: "/api/session/properties".into(),
+            session_id: None,
+        }]
+    );
+
+    // The URL is validated just as it is by `Client::new`.
+    match Client::with_http_client(inner, "http://metabase:3000/path") {
+        Err(Error::InvalidUrl(_)) => (),
+        res => panic!("expected Error::InvalidUrl, got {:?}", res),
+    }
+
+    Ok(())
+}
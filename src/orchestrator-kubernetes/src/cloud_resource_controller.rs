@@ -13,14 +13,14 @@ use std::collections::BTreeSet;
 use std::str::FromStr;
 
 use async_trait::async_trait;
-use kube::api::{DeleteParams, ListParams, ObjectMeta, Patch, PatchParams};
+use kube::api::{DeleteParams, ListParams, ObjectMeta, Patch};
 use kube::ResourceExt;
 use maplit::btreemap;
 use mz_cloud_resources::crd::vpc_endpoint::v1::{VpcEndpoint, VpcEndpointSpec};
 use mz_cloud_resources::{CloudResourceController, VpcEndpointConfig};
 use mz_repr::GlobalId;
 
-use crate::{KubernetesOrchestrator, FIELD_MANAGER};
+use crate::KubernetesOrchestrator;
 
 #[async_trait]
 impl CloudResourceController for KubernetesOrchestrator {
@@ -58,7 +58,7 @@ impl CloudResourceController for KubernetesOrchestrator {
         self.vpc_endpoint_api
             .patch(
                 &name,
-                &PatchParams::apply(FIELD_MANAGER).force(),
+                &self.config.apply_params(),
                 &Patch::Apply(vpc_endpoint),
             )
             .await?;
@@ -75,46 +75,70 @@
 
 use std::collections::BTreeMap;
 use std::fmt;
+use std::future::Future;
+use std::str::FromStr;
 use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use anyhow::{anyhow, Context};
+use async_stream::stream;
 use async_trait::async_trait;
 use chrono::Utc;
 use clap::ArgEnum;
-use futures::stream::{BoxStream, StreamExt};
-use k8s_openapi::api::apps::v1::{StatefulSet, StatefulSetSpec};
+use futures::stream::{BoxStream, Stream, StreamExt, TryStreamExt};
+use k8s_openapi::api::apps::v1::{
+    RollingUpdateStatefulSetStrategy, StatefulSet, StatefulSetSpec, StatefulSetUpdateStrategy,
+};
 use k8s_openapi::api::core::v1::{
-    Affinity, Container, ContainerPort, ContainerState, EnvVar, EnvVarSource,
-    EphemeralVolumeSource, ObjectFieldSelector, PersistentVolumeClaim, PersistentVolumeClaimSpec,
-    PersistentVolumeClaimTemplate, Pod, PodAffinityTerm, PodAntiAffinity, PodSecurityContext,
-    PodSpec, PodTemplateSpec, ResourceRequirements, Secret, Service as K8sService, ServicePort,
-    ServiceSpec, Toleration, Volume, VolumeMount,
+    Affinity, Capabilities, Container, ContainerPort, ContainerState, EmptyDirVolumeSource, EnvVar,
+    EnvVarSource, EphemeralVolumeSource, HostAlias, ObjectFieldSelector, PersistentVolumeClaim,
+    PersistentVolumeClaimSpec, PersistentVolumeClaimTemplate, Pod, PodAffinityTerm,
+    PodAntiAffinity, PodSecurityContext, PodSpec, PodTemplateSpec, ResourceRequirements, Secret,
+    SecurityContext, Service as K8sService, ServicePort, ServiceSpec, Toleration, Volume,
+    VolumeMount,
 };
+use k8s_openapi::api::policy::v1::{PodDisruptionBudget, PodDisruptionBudgetSpec};
 use k8s_openapi::apimachinery::pkg::api::resource::Quantity;
 use k8s_openapi::apimachinery::pkg::apis::meta::v1::{LabelSelector, LabelSelectorRequirement};
-use kube::api::{Api, DeleteParams, ListParams, ObjectMeta, Patch, PatchParams};
+use k8s_openapi::apimachinery::pkg::util::intstr::IntOrString;
+use kube::api::{
+    Api, DeleteParams, ListParams, LogParams, ObjectList, ObjectMeta, Patch, PatchParams,
+    WatchEvent,
+};
 use kube::client::Client;
 use kube::error::Error;
-use kube::runtime::{watcher, WatchStreamExt};
 use kube::ResourceExt;
 use maplit::btreemap;
 use mz_cloud_resources::crd::vpc_endpoint::v1::VpcEndpoint;
 use mz_cloud_resources::AwsExternalIdPrefix;
 use mz_orchestrator::{
-    DiskLimit, LabelSelectionLogic, LabelSelector as MzLabelSelector, NamespacedOrchestrator,
-    NotReadyReason, Orchestrator, Service, ServiceConfig, ServiceEvent, ServiceProcessMetrics,
-    ServiceStatus,
+    CpuLimit, DiskLimit, DisruptionBudget, LabelSelectionLogic, LabelSelector as MzLabelSelector,
+    MemoryLimit, NamespacedOrchestrator, NotReadyReason, Orchestrator, ScratchVolume, Service,
+    ServiceConfig, ServiceEvent, ServiceProcessMetrics, ServiceStatus, ServiceType, SidecarSpec,
 };
+use mz_ore::retry::{Retry, RetryResult};
 use serde::Deserialize;
 use sha2::{Digest, Sha256};
-use tracing::warn;
+use tracing::{info, warn};
 
 pub mod cloud_resource_controller;
 pub mod secrets;
 pub mod util;
 
-const FIELD_MANAGER: &str = "environmentd";
 const NODE_FAILURE_THRESHOLD_SECONDS: i64 = 30;
+const POD_TEMPLATE_HASH_ANNOTATION: &str = "environmentd.materialize.cloud/pod-template-hash";
+/// The maximum number of concurrent requests to issue when replacing pods
+/// whose template is out of date.
+const MAX_CONCURRENT_POD_REPLACEMENTS: usize = 16;
+/// The maximum number of attempts to make when applying an object fails with
+/// a transient error.
+const MAX_APPLY_TRIES: usize = 5;
+/// The initial backoff between attempts to apply an object.
+const APPLY_INITIAL_BACKOFF: Duration = Duration::from_millis(100);
+/// The initial backoff between attempts to resume a failed pod watch.
+const WATCH_INITIAL_BACKOFF: Duration = Duration::from_millis(800);
+/// The maximum backoff between attempts to resume a failed pod watch.
+const WATCH_MAX_BACKOFF: Duration = Duration::from_secs(30);
 
 /// Configures a [`KubernetesOrchestrator`].
 #[derive(Debug, Clone)]
@@ -124,6 +148,9 @@ pub struct KubernetesOrchestratorConfig {
     pub context: String,
     /// The name of a non-default Kubernetes scheduler to use, if any.
     pub scheduler_name: Option<String>,
+    /// The name of the Kubernetes PriorityClass to assign to the pods of each
+    /// service, if any.
+    pub priority_class_name: Option<String>,
     /// Labels to install on every service created by the orchestrator.
     pub service_labels: BTreeMap<String, String>,
     /// Node selector to install on every service created by the orchestrator.
@@ -145,6 +172,124 @@ pub struct KubernetesOrchestratorConfig {
     pub ephemeral_volume_storage_class: Option<String>,
     /// The optional fs group for service's pods' `securityContext`.
     pub service_fs_group: Option<i64>,
+    /// Additional settings for the `securityContext` of each service's pods.
+    pub pod_security_context: KubernetesPodSecurityContext,
+    /// Settings for the `securityContext` of each container of each
+    /// service's pods.
+    pub container_security_context: KubernetesContainerSecurityContext,
+    /// The strategy to use when replacing the pods of a service whose
+    /// configuration has changed.
+    pub update_strategy: KubernetesUpdateStrategy,
+    /// A template for the address at which each process of a service is
+    /// reachable, for use when the cluster-internal DNS names of services
+    /// are not routable by their peers.
+    ///
+    /// The placeholders `{name}`, `{index}`, and `{namespace}` are replaced
+    /// with the name of the service's stateful set, the index of the
+    /// process, and the Kubernetes namespace, respectively. If unspecified,
+    /// the cluster-internal DNS name of each pod is used.
+    pub announce_address: Option<String>,
+    /// The DNS policy to set on the pods of each service, if not the
+    /// Kubernetes default (e.g., `ClusterFirstWithHostNet` or `None`).
+    pub dns_policy: Option<String>,
+    /// Additional `/etc/hosts` entries for the pods of each service, as pairs
+    /// of an IP address and the hostnames that should resolve to it.
+    pub host_aliases: Vec<(String, Vec<String>)>,
+    /// The topology key across which the pods of services with anti-affinity
+    /// are spread. Defaults to [`DEFAULT_ANTI_AFFINITY_TOPOLOGY_KEY`], i.e.,
+    /// one pod per node.
+    pub anti_affinity_topology_key: Option<String>,
+    /// The field manager to identify as when applying Kubernetes objects.
+    /// Defaults to [`DEFAULT_FIELD_MANAGER`].
+    pub field_manager: Option<String>,
+    /// Whether to compute, but not apply, the Kubernetes objects for each
+    /// service.
+    ///
+    /// In dry-run mode, `ensure_service` and `drop_service` log the objects
+    /// they would have applied or deleted instead of calling the Kubernetes
+    /// API.
+    pub dry_run: bool,
+}
+
+impl KubernetesOrchestratorConfig {
+    /// Returns the parameters for a server-side apply of an object managed by
+    /// the orchestrator.
+    fn apply_params(&self) -> PatchParams {
+        let field_manager = self
+            .field_manager
+            .as_deref()
+            .unwrap_or(DEFAULT_FIELD_MANAGER);
+        PatchParams::apply(field_manager).force()
+    }
+}
+
+/// The default field manager for objects applied by the orchestrator.
+pub const DEFAULT_FIELD_MANAGER: &str = "environmentd";
+
+/// The default topology key for service anti-affinity, which spreads pods
+/// across nodes.
+pub const DEFAULT_ANTI_AFFINITY_TOPOLOGY_KEY: &str = "kubernetes.io/hostname";
+
+/// Security settings for the pods of each service.
+#[derive(Debug, Clone, Default)]
+pub struct KubernetesPodSecurityContext {
+    /// Whether to require that the containers of each pod run as a non-root
+    /// user.
+    pub run_as_non_root: bool,
+    /// The UID to run the entrypoint of each container as, if not the default
+    /// specified by its image.
+    pub run_as_user: Option<i64>,
+    /// The GID to run the entrypoint of each container as, if not the
+    /// default of the container runtime.
+    pub run_as_group: Option<i64>,
+}
+
+impl KubernetesPodSecurityContext {
+    /// Returns the `securityContext` to install on each pod, or `None` if
+    /// there is nothing to configure.
+    fn to_k8s(&self, fs_group: Option<i64>) -> Option<PodSecurityContext> {
+        if !self.run_as_non_root
+            && self.run_as_user.is_none()
+            && self.run_as_group.is_none()
+            && fs_group.is_none()
+        {
+            return None;
+        }
+        Some(PodSecurityContext {
+            fs_group,
+            run_as_non_root: self.run_as_non_root.then_some(true),
+            run_as_user: self.run_as_user,
+            run_as_group: self.run_as_group,
+            ..Default::default()
+        })
+    }
+}
+
+/// Security settings for each container of each service.
+#[derive(Debug, Clone, Default)]
+pub struct KubernetesContainerSecurityContext {
+    /// Whether to mount the root filesystem of each container as read-only.
+    pub read_only_root_filesystem: bool,
+    /// The Linux capabilities to drop from each container, e.g., `ALL`.
+    pub drop_capabilities: Vec<String>,
+}
+
+impl KubernetesContainerSecurityContext {
+    /// Returns the `securityContext` to install on each container, or `None`
+    /// if there is nothing to configure.
+    fn to_k8s(&self) -> Option<SecurityContext> {
+        if !self.read_only_root_filesystem && self.drop_capabilities.is_empty() {
+            return None;
+        }
+        Some(SecurityContext {
+            read_only_root_filesystem: self.read_only_root_filesystem.then_some(true),
+            capabilities: (!self.drop_capabilities.is_empty()).then(|| Capabilities {
+                add: None,
+                drop: Some(self.drop_capabilities.clone()),
+            }),
+            ..Default::default()
+        })
+    }
 }
 
 /// Specifies whether Kubernetes should pull Docker images when creating pods.
@@ -168,6 +313,93 @@ impl fmt::Display for KubernetesImagePullPolicy {
     }
 }
 
+/// Specifies how Kubernetes should replace the pods of a service whose
+/// configuration has changed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KubernetesUpdateStrategy {
+    /// Replace pods automatically, one at a time.
+    ///
+    /// If `partition` is specified, only pods whose ordinal is greater than or
+    /// equal to `partition` are replaced.
+    RollingUpdate { partition: Option<i32> },
+    /// Replace pods only when they are deleted.
+    OnDelete,
+}
+
+impl KubernetesUpdateStrategy {
+    /// Reports whether the pod with the given ordinal should be replaced when
+    /// its configuration is out of date.
+    fn replaces(&self, ordinal: i32) -> bool {
+        match self {
+            KubernetesUpdateStrategy::RollingUpdate {
+                partition: Some(partition),
+            } => ordinal >= *partition,
+            KubernetesUpdateStrategy::RollingUpdate { partition: None }
+            | KubernetesUpdateStrategy::OnDelete => true,
+        }
+    }
+
+    fn to_k8s(&self) -> StatefulSetUpdateStrategy {
+        match self {
+            KubernetesUpdateStrategy::RollingUpdate { partition } => StatefulSetUpdateStrategy {
+                type_: Some("RollingUpdate".into()),
+                rolling_update: Some(RollingUpdateStatefulSetStrategy {
+                    partition: *partition,
+                    ..Default::default()
+                }),
+            },
+            KubernetesUpdateStrategy::OnDelete => StatefulSetUpdateStrategy {
+                type_: Some("OnDelete".into()),
+                rolling_update: None,
+            },
+        }
+    }
+}
+
+impl Default for KubernetesUpdateStrategy {
+    fn default() -> KubernetesUpdateStrategy {
+        KubernetesUpdateStrategy::RollingUpdate { partition: None }
+    }
+}
+
+impl FromStr for KubernetesUpdateStrategy {
+    type Err = anyhow::Error;
+
+    /// Parses an update strategy of the form `on-delete`, `rolling-update`, or
+    /// `rolling-update:PARTITION`.
+    fn from_str(s: &str) -> Result<KubernetesUpdateStrategy, anyhow::Error> {
+        match s.split_once(':') {
+            None if s == "on-delete" => Ok(KubernetesUpdateStrategy::OnDelete),
+            None if s == "rolling-update" => {
+                Ok(KubernetesUpdateStrategy::RollingUpdate { partition: None })
+            }
+            Some(("rolling-update", partition)) => {
+                let partition = partition
+                    .parse()
+                    .with_context(|| format!("invalid partition: {partition}"))?;
+                Ok(KubernetesUpdateStrategy::RollingUpdate {
+                    partition: Some(partition),
+                })
+            }
+            _ => Err(anyhow!("invalid update strategy: {s}")),
+        }
+    }
+}
+
+impl fmt::Display for KubernetesUpdateStrategy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            KubernetesUpdateStrategy::RollingUpdate { partition: None } => {
+                f.write_str("rolling-update")
+            }
+            KubernetesUpdateStrategy::RollingUpdate {
+                partition: Some(partition),
+            } => write!(f, "rolling-update:{partition}"),
+            KubernetesUpdateStrategy::OnDelete => f.write_str("on-delete"),
+        }
+    }
+}
+
 /// An orchestrator backed by Kubernetes.
 pub struct KubernetesOrchestrator {
     client: Client,
@@ -210,10 +442,12 @@ impl Orchestrator for KubernetesOrchestrator {
                 service_api: Api::default_namespaced(self.client.clone()),
                 stateful_set_api: Api::default_namespaced(self.client.clone()),
                 pod_api: Api::default_namespaced(self.client.clone()),
+                pod_disruption_budget_api: Api::default_namespaced(self.client.clone()),
                 kubernetes_namespace: self.kubernetes_namespace.clone(),
                 namespace: namespace.into(),
                 config: self.config.clone(),
                 service_scales: std::sync::Mutex::new(BTreeMap::new()),
+                service_budgets: std::sync::Mutex::new(BTreeMap::new()),
             })
         }))
     }
@@ -224,10 +458,15 @@ struct NamespacedKubernetesOrchestrator {
     service_api: Api<K8sService>,
     stateful_set_api: Api<StatefulSet>,
     pod_api: Api<Pod>,
+    pod_disruption_budget_api: Api<PodDisruptionBudget>,
     kubernetes_namespace: String,
     namespace: String,
     config: KubernetesOrchestratorConfig,
     service_scales: std::sync::Mutex<BTreeMap<String, u16>>,
+    /// Whether each service is known to have a `PodDisruptionBudget`.
+    /// Services that are absent may or may not have one, e.g. if it was
+    /// created by a previous process.
+    service_budgets: std::sync::Mutex<BTreeMap<String, bool>>,
 }
 
 impl fmt::Debug for NamespacedKubernetesOrchestrator {
@@ -283,249 +522,81 @@ impl k8s_openapi::Metadata for PodMetrics {
 }
 
 impl NamespacedKubernetesOrchestrator {
-    /// Return a `ListParams` instance that limits results to the namespace
-    /// assigned to this orchestrator.
-    fn list_pod_params(&self) -> ListParams {
-        let ns_selector = format!(
-            "environmentd.materialize.cloud/namespace={}",
-            self.namespace
-        );
-        ListParams::default().labels(&ns_selector)
-    }
-    /// Convert a higher-level label key to the actual one we
-    /// will give to Kubernetes
-    fn make_label_key(&self, key: &str) -> String {
-        format!("{}.environmentd.materialize.cloud/{}", self.namespace, key)
-    }
-    fn label_selector_to_k8s(
+    /// Deletes the `PodDisruptionBudget` of the identified service, named
+    /// `name`, unless the service is known not to have one.
+    ///
+    /// If we are not permitted to delete budgets, we could not have created
+    /// one either, so that is not treated as an error.
+    async fn delete_pod_disruption_budget(
         &self,
-        MzLabelSelector { label_name, logic }: MzLabelSelector,
-    ) -> Result<LabelSelectorRequirement, anyhow::Error> {
-        let (operator, values) = match logic {
-            LabelSelectionLogic::Eq { value } => Ok(("In", vec![value])),
-            LabelSelectionLogic::NotEq { value } => Ok(("NotIn", vec![value])),
-            LabelSelectionLogic::Exists => Ok(("Exists", vec![])),
-            LabelSelectionLogic::NotExists => Ok(("DoesNotExist", vec![])),
-            LabelSelectionLogic::InSet { values } => {
-                if values.is_empty() {
-                    Err(anyhow!(
-                        "Invalid selector logic for {label_name}: empty `in` set"
-                    ))
-                } else {
-                    Ok(("In", values))
-                }
-            }
-            LabelSelectionLogic::NotInSet { values } => {
-                if values.is_empty() {
-                    Err(anyhow!(
-                        "Invalid selector logic for {label_name}: empty `notin` set"
-                    ))
-                } else {
-                    Ok(("NotIn", values))
-                }
-            }
-        }?;
-        let lsr = LabelSelectorRequirement {
-            key: self.make_label_key(&label_name),
-            operator: operator.to_string(),
-            values: Some(values),
-        };
-        Ok(lsr)
-    }
-}
-
-#[derive(Debug)]
-struct ScaledQuantity {
-    integral_part: u64,
-    exponent: i8,
-    base10: bool,
-}
-
-impl ScaledQuantity {
-    pub fn try_to_integer(&self, scale: i8, base10: bool) -> Option<u64> {
-        if base10 != self.base10 {
-            return None;
+        id: &str,
+        name: &str,
+    ) -> Result<(), anyhow::Error> {
+        let known_budget = self
+            .service_budgets
+            .lock()
+            .expect("poisoned lock")
+            .get(id)
+            .copied();
+        if known_budget == Some(false) {
+            return Ok(());
         }
-        let exponent = self.exponent - scale;
-        let mut result = self.integral_part;
-        let base = if self.base10 { 10 } else { 2 };
-        if exponent < 0 {
-            for _ in exponent..0 {
-                result /= base;
-            }
-        } else {
-            for _ in 0..exponent {
-                result = result.checked_mul(2)?;
+        let res = self
+            .pod_disruption_budget_api
+            .delete(name, &DeleteParams::default())
+            .await;
+        match res {
+            Ok(_) => (),
+            Err(Error::Api(e)) if e.code == 404 => (),
+            Err(Error::Api(e)) if e.code == 403 => {
+                warn!("not permitted to delete pod disruption budget {name}: {e}");
             }
+            Err(e) => return Err(e.into()),
         }
-        Some(result)
-    }
-}
-
-// Parse a k8s `Quantity` object
-// into a numeric value.
-//
-// This is intended to support collecting CPU and Memory data.
-// Thus, there are a few that things Kubernetes attempts to do, that we don't,
-// because I've never observed metrics-server specifically sending them:
-// (1) Handle negative numbers (because it's not useful for that use-case)
-// (2) Handle non-integers (because I have never observed them being actually sent)
-// (3) Handle scientific notation (e.g. 1.23e2)
-fn parse_k8s_quantity(s: &str) -> Result<ScaledQuantity, anyhow::Error> {
-    const DEC_SUFFIXES: &[(&str, i8)] = &[
-        ("n", -9),
-        ("u", -6),
-        ("m", -3),
-        ("", 0),
-        ("k", 3), // yep, intentionally lowercase.
-        ("M", 6),
-        ("G", 9),
-        ("T", 12),
-        ("P", 15),
-        ("E", 18),
-    ];
-    const BIN_SUFFIXES: &[(&str, i8)] = &[
-        ("", 0),
-        ("Ki", 10),
-        ("Mi", 20),
-        ("Gi", 30),
-        ("Ti", 40),
-        ("Pi", 50),
-        ("Ei", 60),
-    ];
-
-    let (positive, s) = match s.chars().next() {
-        Some('+') => (true, &s[1..]),
-        Some('-') => (false, &s[1..]),
-        _ => (true, s),
-    };
-
-    if !positive {
-        anyhow::bail!("Negative numbers not supported")
-    }
-
-    fn is_suffix_char(ch: char) -> bool {
-        "numkMGTPEKi".contains(ch)
+        self.service_budgets
+            .lock()
+            .expect("poisoned lock")
+            .insert(id.to_string(), false);
+        Ok(())
     }
-    let (num, suffix) = match s.find(is_suffix_char) {
-        None => (s, ""),
-        Some(idx) => s.split_at(idx),
-    };
-    let num: u64 = num.parse()?;
-    let (exponent, base10) = if let Some((_, exponent)) =
-        DEC_SUFFIXES.iter().find(|(target, _)| suffix == *target)
-    {
-        (exponent, true)
-    } else if let Some((_, exponent)) = BIN_SUFFIXES.iter().find(|(target, _)| suffix == *target) {
-        (exponent, false)
-    } else {
-        anyhow::bail!("Unrecognized suffix: {suffix}");
-    };
-    Ok(ScaledQuantity {
-        integral_part: num,
-        exponent: *exponent,
-        base10,
-    })
-}
 
-#[async_trait]
-impl NamespacedOrchestrator for NamespacedKubernetesOrchestrator {
-    async fn fetch_service_metrics(
+    /// Constructs the Kubernetes objects that make up the identified service.
+    fn service_objects(
         &self,
         id: &str,
-    ) -> Result<Vec<ServiceProcessMetrics>, anyhow::Error> {
-        let Some(&scale) = self.service_scales.lock().expect("poisoned lock").get(id) else {
-            // This should have been set in `ensure_service`.
-            tracing::error!("Failed to get scale for {id}");
-            anyhow::bail!("Failed to get scale for {id}");
-        };
-        /// Get metrics for a particular service and process, converting them into a sane (i.e., numeric) format.
-        ///
-        /// Note that we want to keep going even if a lookup fails for whatever reason,
-        /// so this function is infallible. If we fail to get cpu or memory for a particular pod,
-        /// we just log a warning and install `None` in the returned struct.
-        async fn get_metrics(
-            self_: &NamespacedKubernetesOrchestrator,
-            id: &str,
-            i: usize,
-        ) -> ServiceProcessMetrics {
-            let name = format!("{}-{id}-{i}", self_.namespace);
-            let metrics = match self_.metrics_api.get(&name).await {
-                Ok(metrics) => metrics,
-                Err(e) => {
-                    warn!("Failed to get metrics for {name}: {e}");
-                    return ServiceProcessMetrics::default();
-                }
-            };
-            let Some(PodMetricsContainer { usage: PodMetricsContainerUsage { cpu: Quantity(cpu_str), memory: Quantity(mem_str) }, .. }) = metrics.containers.get(0) else {
-                warn!("metrics result contained no containers for {name}");
-                return ServiceProcessMetrics::default();
-            };
-
-            let cpu = match parse_k8s_quantity(cpu_str) {
-                Ok(q) => match q.try_to_integer(-9, true) {
-                    Some(i) => Some(i),
-                    None => {
-                        tracing::error!("CPU value {q:? }out of range");
-                        None
-                    }
-                },
-                Err(e) => {
-                    tracing::error!("Failed to parse CPU value {cpu_str}: {e}");
-                    None
-                }
-            };
-            let memory = match parse_k8s_quantity(mem_str) {
-                Ok(q) => match q.try_to_integer(0, false) {
-                    Some(i) => Some(i),
-                    None => {
-                        tracing::error!("Memory value {q:?} out of range");
-                        None
-                    }
-                },
-                Err(e) => {
-                    tracing::error!("Failed to parse memory value {mem_str}: {e}");
-                    None
-                }
-            };
-
-            ServiceProcessMetrics {
-                cpu_nano_cores: cpu,
-                memory_bytes: memory,
-            }
-        }
-        let ret = futures::future::join_all((0..scale).map(|i| get_metrics(self, id, i.into())));
-
-        Ok(ret.await)
-    }
-
-    async fn ensure_service(
-        &self,
-        id: &str,
-        ServiceConfig {
-            image,
-            init_container_image,
-            args,
-            ports: ports_in,
-            memory_limit,
-            cpu_limit,
-            scale,
-            labels: labels_in,
-            availability_zone,
-            anti_affinity,
-            disk,
-            disk_limit,
-        }: ServiceConfig<'_>,
-    ) -> Result<Box<dyn Service>, anyhow::Error> {
-        let name = format!("{}-{id}", self.namespace);
-        // The match labels should be the minimal set of labels that uniquely
-        // identify the pods in the stateful set. Changing these after the
-        // `StatefulSet` is created is not permitted by Kubernetes, and we're
-        // not yet smart enough to handle deleting and recreating the
-        // `StatefulSet`.
-        let match_labels = btreemap! {
-            "environmentd.materialize.cloud/namespace".into() => self.namespace.clone(),
-            "environmentd.materialize.cloud/service-id".into() => id.into(),
+        ServiceConfig {
+            image,
+            init_container_image,
+            args,
+            env: env_in,
+            ports: ports_in,
+            memory_limit,
+            cpu_limit,
+            scale,
+            labels: labels_in,
+            pod_labels,
+            availability_zone,
+            anti_affinity,
+            disruption_budget,
+            sidecars,
+            metrics_port,
+            metrics_path,
+            scratch_volume,
+            service_type,
+            headless,
+            disk,
+            disk_limit,
+        }: ServiceConfig<'_>,
+    ) -> Result<ServiceObjects, anyhow::Error> {
+        let name = format!("{}-{id}", self.namespace);
+        // The match labels should be the minimal set of labels that uniquely
+        // identify the pods in the stateful set. Changing these after the
+        // `StatefulSet` is created is not permitted by Kubernetes, and we're
+        // not yet smart enough to handle deleting and recreating the
+        // `StatefulSet`.
+        let match_labels = btreemap! {
+            "environmentd.materialize.cloud/namespace".into() => self.namespace.clone(),
+            "environmentd.materialize.cloud/service-id".into() => id.into(),
         };
         let mut labels = match_labels.clone();
         for (key, value) in labels_in {
@@ -540,19 +611,9 @@ impl NamespacedOrchestrator for NamespacedKubernetesOrchestrator {
         for (key, value) in &self.config.service_labels {
             labels.insert(key.clone(), value.clone());
         }
-        let mut limits = BTreeMap::new();
-        if let Some(memory_limit) = memory_limit {
-            limits.insert(
-                "memory".into(),
-                Quantity(memory_limit.0.as_u64().to_string()),
-            );
-        }
-        if let Some(cpu_limit) = cpu_limit {
-            limits.insert(
-                "cpu".into(),
-                Quantity(format!("{}m", cpu_limit.as_millicpus())),
-            );
-        }
+        add_pod_labels(&mut labels, &match_labels, pod_labels)?;
+        let limits = resource_limits(memory_limit, cpu_limit);
+        let (service_type, cluster_ip) = service_type_and_cluster_ip(service_type, headless)?;
         let service = K8sService {
             metadata: ObjectMeta {
                 name: Some(name.clone()),
@@ -569,21 +630,20 @@ impl NamespacedOrchestrator for NamespacedKubernetesOrchestrator {
                         })
                         .collect(),
                 ),
-                cluster_ip: None,
+                type_: Some(service_type),
+                cluster_ip,
                 selector: Some(match_labels.clone()),
                 ..Default::default()
             }),
             status: None,
         };
 
-        let hosts = (0..scale)
-            .map(|i| {
-                format!(
-                    "{name}-{i}.{name}.{}.svc.cluster.local",
-                    self.kubernetes_namespace
-                )
-            })
-            .collect::<Vec<_>>();
+        let hosts = service_hosts(
+            &name,
+            &self.kubernetes_namespace,
+            scale,
+            self.config.announce_address.as_deref(),
+        );
         let ports = ports_in
             .iter()
             .map(|p| (p.name.clone(), p.port_hint))
@@ -605,22 +665,16 @@ impl NamespacedOrchestrator for NamespacedKubernetesOrchestrator {
                     .into_iter()
                     .map(|ls| self.label_selector_to_k8s(ls))
                     .collect::<Result<Vec<_>, _>>()?;
-                let ls = LabelSelector {
-                    match_expressions: Some(label_selector_requirements),
-                    ..Default::default()
-                };
-                let pat = PodAffinityTerm {
-                    label_selector: Some(ls),
-                    topology_key: "kubernetes.io/hostname".to_string(),
-                    ..Default::default()
-                };
-                Ok(PodAntiAffinity {
-                    required_during_scheduling_ignored_during_execution: Some(vec![pat]),
-                    ..Default::default()
-                })
+                Ok(pod_anti_affinity(
+                    label_selector_requirements,
+                    self.config
+                        .anti_affinity_topology_key
+                        .as_deref()
+                        .unwrap_or(DEFAULT_ANTI_AFFINITY_TOPOLOGY_KEY),
+                ))
             })
             .transpose()?;
-        let pod_annotations = btreemap! {
+        let mut pod_annotations = btreemap! {
             // Prevent the cluster-autoscaler from evicting these pods in attempts to scale down
             // and terminate nodes.
             // This will cost us more money, but should give us better uptime.
@@ -628,6 +682,13 @@ impl NamespacedOrchestrator for NamespacedKubernetesOrchestrator {
             // cluster-autoscaler. Notably, eviction of pods for resource overuse is still enabled.
             "cluster-autoscaler.kubernetes.io/safe-to-evict".to_owned() => "false".to_string(),
         };
+        if let Some(metrics_port) = metrics_port {
+            pod_annotations.extend(prometheus_annotations(
+                &ports_in,
+                &metrics_port,
+                metrics_path.as_deref(),
+            )?);
+        }
 
         let mut node_selector: BTreeMap<String, String> = self
             .config
@@ -652,6 +713,8 @@ impl NamespacedOrchestrator for NamespacedKubernetesOrchestrator {
             .context("`image` is not ORG/NAME:VERSION")?
             .to_string();
 
+        let container_security_context = self.config.container_security_context.to_k8s();
+
         let init_containers = init_container_image.map(|image| {
             vec![Container {
                 name: "init".to_string(),
@@ -663,54 +726,17 @@ impl NamespacedOrchestrator for NamespacedKubernetesOrchestrator {
                     limits: Some(limits.clone()),
                     requests: Some(limits.clone()),
                 }),
-                env: Some(vec![
-                    EnvVar {
-                        name: "MZ_NAMESPACE".to_string(),
-                        value_from: Some(EnvVarSource {
-                            field_ref: Some(ObjectFieldSelector {
-                                field_path: "metadata.namespace".to_string(),
-                                ..Default::default()
-                            }),
-                            ..Default::default()
-                        }),
-                        ..Default::default()
-                    },
-                    EnvVar {
-                        name: "MZ_POD_NAME".to_string(),
-                        value_from: Some(EnvVarSource {
-                            field_ref: Some(ObjectFieldSelector {
-                                field_path: "metadata.name".to_string(),
-                                ..Default::default()
-                            }),
-                            ..Default::default()
-                        }),
-                        ..Default::default()
-                    },
-                    EnvVar {
-                        name: "MZ_NODE_NAME".to_string(),
-                        value_from: Some(EnvVarSource {
-                            field_ref: Some(ObjectFieldSelector {
-                                field_path: "spec.nodeName".to_string(),
-                                ..Default::default()
-                            }),
-                            ..Default::default()
-                        }),
-                        ..Default::default()
-                    },
-                ]),
+                env: Some(downward_api_env()),
+                security_context: container_security_context.clone(),
                 ..Default::default()
             }]
         });
 
-        let env = if self.config.coverage {
-            Some(vec![EnvVar {
-                name: "LLVM_PROFILE_FILE".to_string(),
-                value: Some(format!("/coverage/{}-%p-%9m%c.profraw", self.namespace)),
-                ..Default::default()
-            }])
-        } else {
-            None
-        };
+        let coverage_profile_file = self
+            .config
+            .coverage
+            .then(|| format!("/coverage/{}-%p-%9m%c.profraw", self.namespace));
+        let env = main_container_env(env_in, coverage_profile_file);
 
         let mut volume_mounts = vec![];
 
@@ -769,6 +795,16 @@ impl NamespacedOrchestrator for NamespacedKubernetesOrchestrator {
             }
             (false, _) => None,
         };
+        let volumes = match scratch_volume {
+            Some(scratch_volume) => {
+                let (volume, volume_mount) = scratch_empty_dir(scratch_volume);
+                volume_mounts.push(volume_mount);
+                let mut volumes = volumes.unwrap_or_default();
+                volumes.push(volume);
+                Some(volumes)
+            }
+            None => volumes,
+        };
 
         let volume_claim_templates = if self.config.coverage {
             Some(vec![PersistentVolumeClaim {
@@ -793,14 +829,10 @@ impl NamespacedOrchestrator for NamespacedKubernetesOrchestrator {
             None
         };
 
-        let security_context = if let Some(fs_group) = self.config.service_fs_group {
-            Some(PodSecurityContext {
-                fs_group: Some(fs_group),
-                ..Default::default()
-            })
-        } else {
-            None
-        };
+        let security_context = self
+            .config
+            .pod_security_context
+            .to_k8s(self.config.service_fs_group);
 
         let tolerations = Some(vec![
             // When the node becomes `NotReady` it indicates there is a problem
@@ -831,40 +863,49 @@ impl NamespacedOrchestrator for NamespacedKubernetesOrchestrator {
             }),
             spec: Some(PodSpec {
                 init_containers,
-                containers: vec![Container {
-                    name: container_name,
-                    image: Some(image),
-                    args: Some(args),
-                    image_pull_policy: Some(self.config.image_pull_policy.to_string()),
-                    ports: Some(
-                        ports_in
-                            .iter()
-                            .map(|port| ContainerPort {
-                                container_port: port.port_hint.into(),
-                                name: Some(port.name.clone()),
-                                ..Default::default()
-                            })
-                            .collect(),
-                    ),
-                    resources: Some(ResourceRequirements {
-                        // Set both limits and requests to the same values, to ensure a
-                        // `Guaranteed` QoS class for the pod.
-                        limits: Some(limits.clone()),
-                        requests: Some(limits),
-                    }),
-                    volume_mounts: if !volume_mounts.is_empty() {
-                        Some(volume_mounts)
-                    } else {
-                        None
+                containers: pod_containers(
+                    Container {
+                        name: container_name,
+                        image: Some(image),
+                        args: Some(args),
+                        image_pull_policy: Some(self.config.image_pull_policy.to_string()),
+                        ports: Some(
+                            ports_in
+                                .iter()
+                                .map(|port| ContainerPort {
+                                    container_port: port.port_hint.into(),
+                                    name: Some(port.name.clone()),
+                                    ..Default::default()
+                                })
+                                .collect(),
+                        ),
+                        resources: Some(ResourceRequirements {
+                            // Set both limits and requests to the same values, to ensure a
+                            // `Guaranteed` QoS class for the pod.
+                            limits: Some(limits.clone()),
+                            requests: Some(limits),
+                        }),
+                        volume_mounts: if !volume_mounts.is_empty() {
+                            Some(volume_mounts)
+                        } else {
+                            None
+                        },
+                        env: Some(env),
+                        security_context: container_security_context.clone(),
+                        ..Default::default()
                     },
-                    env,
-                    ..Default::default()
-                }],
+                    sidecars,
+                    self.config.image_pull_policy,
+                    container_security_context,
+                ),
                 volumes,
                 security_context,
                 node_selector: Some(node_selector),
                 scheduler_name: self.config.scheduler_name.clone(),
+                priority_class_name: self.config.priority_class_name.clone(),
                 service_account: self.config.service_account.clone(),
+                dns_policy: self.config.dns_policy.clone(),
+                host_aliases: host_aliases(&self.config.host_aliases),
                 affinity: Some(Affinity {
                     pod_anti_affinity: anti_affinity,
                     ..Default::default()
@@ -895,11 +936,7 @@ impl NamespacedOrchestrator for NamespacedKubernetesOrchestrator {
                 ..Default::default()
             }),
         };
-        let pod_template_json = serde_json::to_string(&pod_template_spec).unwrap();
-        let mut hasher = Sha256::new();
-        hasher.update(pod_template_json);
-        let pod_template_hash = format!("{:x}", hasher.finalize());
-        let pod_template_hash_annotation = "environmentd.materialize.cloud/pod-template-hash";
+        let pod_template_hash = pod_template_hash(&pod_template_spec);
         pod_template_spec
             .metadata
             .as_mut()
@@ -908,10 +945,13 @@ impl NamespacedOrchestrator for NamespacedKubernetesOrchestrator {
             .as_mut()
             .unwrap()
             .insert(
-                pod_template_hash_annotation.to_owned(),
+                POD_TEMPLATE_HASH_ANNOTATION.to_owned(),
                 pod_template_hash.clone(),
             );
 
+        let pod_disruption_budget = disruption_budget
+            .map(|budget| pod_disruption_budget(name.clone(), match_labels.clone(), budget));
+
         let stateful_set = StatefulSet {
             metadata: ObjectMeta {
                 name: Some(name.clone()),
@@ -926,105 +966,386 @@ impl NamespacedOrchestrator for NamespacedKubernetesOrchestrator {
                 replicas: Some(scale.into()),
                 template: pod_template_spec,
                 pod_management_policy: Some("Parallel".to_string()),
+                update_strategy: Some(self.config.update_strategy.to_k8s()),
                 volume_claim_templates,
                 ..Default::default()
             }),
             status: None,
         };
 
-        self.service_api
-            .patch(
-                &name,
-                &PatchParams::apply(FIELD_MANAGER).force(),
-                &Patch::Apply(service),
-            )
-            .await?;
-        self.stateful_set_api
-            .patch(
-                &name,
-                &PatchParams::apply(FIELD_MANAGER).force(),
-                &Patch::Apply(stateful_set),
-            )
-            .await?;
-        // Explicitly delete any pods in the stateful set that don't match the
-        // template. In theory, Kubernetes would do this automatically, but
-        // in practice we have observed that it does not.
-        // See: https://github.com/kubernetes/kubernetes/issues/67250
-        for pod_id in 0..scale {
-            let pod_name = format!("{}-{}", &name, pod_id);
-            let pod = match self.pod_api.get(&pod_name).await {
-                Ok(pod) => pod,
-                // Pod already doesn't exist.
-                Err(kube::Error::Api(e)) if e.code == 404 => continue,
-                Err(e) => return Err(e.into()),
-            };
-            if pod.annotations().get(pod_template_hash_annotation) != Some(&pod_template_hash) {
-                match self
-                    .pod_api
-                    .delete(&pod_name, &DeleteParams::default())
-                    .await
-                {
-                    Ok(_) => (),
-                    // Pod got deleted while we were looking at it.
-                    Err(kube::Error::Api(e)) if e.code == 404 => (),
-                    Err(e) => return Err(e.into()),
+        Ok(ServiceObjects {
+            name,
+            service,
+            stateful_set,
+            pod_disruption_budget,
+            pod_template_hash,
+            hosts,
+            ports,
+        })
+    }
+
+    /// Return a `ListParams` instance that limits results to the namespace
+    /// assigned to this orchestrator.
+    fn list_pod_params(&self) -> ListParams {
+        let ns_selector = format!(
+            "environmentd.materialize.cloud/namespace={}",
+            self.namespace
+        );
+        ListParams::default().labels(&ns_selector)
+    }
+    /// Convert a higher-level label key to the actual one we
+    /// will give to Kubernetes
+    fn make_label_key(&self, key: &str) -> String {
+        format!("{}.environmentd.materialize.cloud/{}", self.namespace, key)
+    }
+    fn label_selector_to_k8s(
+        &self,
+        MzLabelSelector { label_name, logic }: MzLabelSelector,
+    ) -> Result<LabelSelectorRequirement, anyhow::Error> {
+        let (operator, values) = match logic {
+            LabelSelectionLogic::Eq { value } => Ok(("In", vec![value])),
+            LabelSelectionLogic::NotEq { value } => Ok(("NotIn", vec![value])),
+            LabelSelectionLogic::Exists => Ok(("Exists", vec![])),
+            LabelSelectionLogic::NotExists => Ok(("DoesNotExist", vec![])),
+            LabelSelectionLogic::InSet { values } => {
+                if values.is_empty() {
+                    Err(anyhow!(
+                        "Invalid selector logic for {label_name}: empty `in` set"
+                    ))
+                } else {
+                    Ok(("In", values))
                 }
             }
-        }
-        self.service_scales
-            .lock()
-            .expect("poisoned lock")
-            .insert(id.to_string(), scale);
-        Ok(Box::new(KubernetesService { hosts, ports }))
+            LabelSelectionLogic::NotInSet { values } => {
+                if values.is_empty() {
+                    Err(anyhow!(
+                        "Invalid selector logic for {label_name}: empty `notin` set"
+                    ))
+                } else {
+                    Ok(("NotIn", values))
+                }
+            }
+        }?;
+        let lsr = LabelSelectorRequirement {
+            key: self.make_label_key(&label_name),
+            operator: operator.to_string(),
+            values: Some(values),
+        };
+        Ok(lsr)
     }
+}
 
-    /// Drops the identified service, if it exists.
-    async fn drop_service(&self, id: &str) -> Result<(), anyhow::Error> {
-        fail::fail_point!("kubernetes_drop_service", |_| Err(anyhow!("failpoint")));
-        self.service_scales
-            .lock()
-            .expect("poisoned lock")
-            .remove(id);
-        let name = format!("{}-{id}", self.namespace);
-        let res = self
-            .stateful_set_api
-            .delete(&name, &DeleteParams::default())
-            .await;
-        match res {
-            Ok(_) => (),
-            Err(Error::Api(e)) if e.code == 404 => (),
-            Err(e) => return Err(e.into()),
-        }
+#[derive(Debug)]
+struct ScaledQuantity {
+    integral_part: u64,
+    exponent: i8,
+    base10: bool,
+}
 
-        let res = self
-            .service_api
-            .delete(&name, &DeleteParams::default())
-            .await;
-        match res {
-            Ok(_) => Ok(()),
-            Err(Error::Api(e)) if e.code == 404 => Ok(()),
-            Err(e) => Err(e.into()),
+impl ScaledQuantity {
+    pub fn try_to_integer(&self, scale: i8, base10: bool) -> Option<u64> {
+        if base10 != self.base10 {
+            return None;
         }
+        let exponent = self.exponent - scale;
+        let mut result = self.integral_part;
+        let base = if self.base10 { 10 } else { 2 };
+        if exponent < 0 {
+            for _ in exponent..0 {
+                result /= base;
+            }
+        } else {
+            for _ in 0..exponent {
+                result = result.checked_mul(2)?;
+            }
+        }
+        Some(result)
+    }
+}
+
+// Parse a k8s `Quantity` object
+// into a numeric value.
+//
+// This is intended to support collecting CPU and Memory data.
+// Thus, there are a few that things Kubernetes attempts to do, that we don't,
+// because I've never observed metrics-server specifically sending them:
+// (1) Handle negative numbers (because it's not useful for that use-case)
+// (2) Handle non-integers (because I have never observed them being actually sent)
+// (3) Handle scientific notation (e.g. 1.23e2)
+fn parse_k8s_quantity(s: &str) -> Result<ScaledQuantity, anyhow::Error> {
+    const DEC_SUFFIXES: &[(&str, i8)] = &[
+        ("n", -9),
+        ("u", -6),
+        ("m", -3),
+        ("", 0),
+        ("k", 3), // yep, intentionally lowercase.
+        ("M", 6),
+        ("G", 9),
+        ("T", 12),
+        ("P", 15),
+        ("E", 18),
+    ];
+    const BIN_SUFFIXES: &[(&str, i8)] = &[
+        ("", 0),
+        ("Ki", 10),
+        ("Mi", 20),
+        ("Gi", 30),
+        ("Ti", 40),
+        ("Pi", 50),
+        ("Ei", 60),
+    ];
+
+    let (positive, s) = match s.chars().next() {
+        Some('+') => (true, &s[1..]),
+        Some('-') => (false, &s[1..]),
+        _ => (true, s),
+    };
+
+    if !positive {
+        anyhow::bail!("Negative numbers not supported")
+    }
+
+    fn is_suffix_char(ch: char) -> bool {
+        "numkMGTPEKi".contains(ch)
+    }
+    let (num, suffix) = match s.find(is_suffix_char) {
+        None => (s, ""),
+        Some(idx) => s.split_at(idx),
+    };
+    let num: u64 = num.parse()?;
+    let (exponent, base10) = if let Some((_, exponent)) =
+        DEC_SUFFIXES.iter().find(|(target, _)| suffix == *target)
+    {
+        (exponent, true)
+    } else if let Some((_, exponent)) = BIN_SUFFIXES.iter().find(|(target, _)| suffix == *target) {
+        (exponent, false)
+    } else {
+        anyhow::bail!("Unrecognized suffix: {suffix}");
+    };
+    Ok(ScaledQuantity {
+        integral_part: num,
+        exponent: *exponent,
+        base10,
+    })
+}
+
+#[async_trait]
+impl NamespacedOrchestrator for NamespacedKubernetesOrchestrator {
+    async fn fetch_service_metrics(
+        &self,
+        id: &str,
+    ) -> Result<Vec<ServiceProcessMetrics>, anyhow::Error> {
+        let Some(&scale) = self.service_scales.lock().expect("poisoned lock").get(id) else {
+            // This should have been set in `ensure_service`.
+            tracing::error!("Failed to get scale for {id}");
+            anyhow::bail!("Failed to get scale for {id}");
+        };
+        /// Get metrics for a particular service and process, converting them into a sane (i.e., numeric) format.
+        ///
+        /// Note that we want to keep going even if a lookup fails for whatever reason,
+        /// so this function is infallible. If we fail to get cpu or memory for a particular pod,
+        /// we just log a warning and install `None` in the returned struct.
+        async fn get_metrics(
+            self_: &NamespacedKubernetesOrchestrator,
+            id: &str,
+            i: usize,
+        ) -> ServiceProcessMetrics {
+            let name = format!("{}-{id}-{i}", self_.namespace);
+            let metrics = match self_.metrics_api.get(&name).await {
+                Ok(metrics) => metrics,
+                Err(e) => {
+                    warn!("Failed to get metrics for {name}: {e}");
+                    return ServiceProcessMetrics::default();
+                }
+            };
+            let Some(PodMetricsContainer { usage: PodMetricsContainerUsage { cpu: Quantity(cpu_str), memory: Quantity(mem_str) }, .. }) = metrics.containers.get(0) else {
+                warn!("metrics result contained no containers for {name}");
+                return ServiceProcessMetrics::default();
+            };
+
+            let cpu = match parse_k8s_quantity(cpu_str) {
+                Ok(q) => match q.try_to_integer(-9, true) {
+                    Some(i) => Some(i),
+                    None => {
+                        tracing::error!("CPU value {q:? }out of range");
+                        None
+                    }
+                },
+                Err(e) => {
+                    tracing::error!("Failed to parse CPU value {cpu_str}: {e}");
+                    None
+                }
+            };
+            let memory = match parse_k8s_quantity(mem_str) {
+                Ok(q) => match q.try_to_integer(0, false) {
+                    Some(i) => Some(i),
+                    None => {
+                        tracing::error!("Memory value {q:?} out of range");
+                        None
+                    }
+                },
+                Err(e) => {
+                    tracing::error!("Failed to parse memory value {mem_str}: {e}");
+                    None
+                }
+            };
+
+            ServiceProcessMetrics {
+                cpu_nano_cores: cpu,
+                memory_bytes: memory,
+            }
+        }
+        let ret = futures::future::join_all((0..scale).map(|i| get_metrics(self, id, i.into())));
+
+        Ok(ret.await)
+    }
+
+    async fn ensure_service(
+        &self,
+        id: &str,
+        config: ServiceConfig<'_>,
+    ) -> Result<Box<dyn Service>, anyhow::Error> {
+        let scale = config.scale;
+        let ServiceObjects {
+            name,
+            service,
+            stateful_set,
+            pod_disruption_budget,
+            pod_template_hash,
+            hosts,
+            ports,
+        } = self.service_objects(id, config)?;
+
+        if self.config.dry_run {
+            info!(
+                "dry run: not applying objects for service {name}: \
+                 service: {}, stateful set: {}, pod disruption budget: {}",
+                serde_json::to_string(&service)?,
+                serde_json::to_string(&stateful_set)?,
+                serde_json::to_string(&pod_disruption_budget)?,
+            );
+            self.service_scales
+                .lock()
+                .expect("poisoned lock")
+                .insert(id.to_string(), scale);
+            return Ok(Box::new(KubernetesService { hosts, ports }));
+        }
+
+        let apply_params = self.config.apply_params();
+        retry_transient(|| {
+            self.service_api
+                .patch(&name, &apply_params, &Patch::Apply(&service))
+        })
+        .await?;
+        retry_transient(|| {
+            self.stateful_set_api
+                .patch(&name, &apply_params, &Patch::Apply(&stateful_set))
+        })
+        .await?;
+        match pod_disruption_budget {
+            Some(pod_disruption_budget) => {
+                retry_transient(|| {
+                    self.pod_disruption_budget_api.patch(
+                        &name,
+                        &apply_params,
+                        &Patch::Apply(&pod_disruption_budget),
+                    )
+                })
+                .await?;
+                self.service_budgets
+                    .lock()
+                    .expect("poisoned lock")
+                    .insert(id.to_string(), true);
+            }
+            // Remove any budget left over from a previous configuration of
+            // the service.
+            None => self.delete_pod_disruption_budget(id, &name).await?,
+        }
+        // Explicitly delete any pods in the stateful set that don't match the
+        // template. In theory, Kubernetes would do this automatically, but
+        // in practice we have observed that it does not. With the `OnDelete`
+        // update strategy, this is the only mechanism by which pods are
+        // replaced.
+        // See: https://github.com/kubernetes/kubernetes/issues/67250
+        let pod_names = (0..scale)
+            .filter(|pod_id| self.config.update_strategy.replaces((*pod_id).into()))
+            .map(|pod_id| format!("{}-{}", &name, pod_id));
+        delete_outdated_pods(&self.pod_api, pod_names, &pod_template_hash).await?;
+        self.service_scales
+            .lock()
+            .expect("poisoned lock")
+            .insert(id.to_string(), scale);
+        Ok(Box::new(KubernetesService { hosts, ports }))
+    }
+
+    /// Fetches the logs of the main container of the identified process of
+    /// the service.
+    async fn fetch_service_logs(
+        &self,
+        id: &str,
+        process: usize,
+        lines: Option<usize>,
+    ) -> Result<String, anyhow::Error> {
+        let name = format!("{}-{id}-{process}", self.namespace);
+        fetch_pod_logs(&self.pod_api, &name, lines).await
+    }
+
+    /// Drops the identified service, if it exists.
+    async fn drop_service(&self, id: &str) -> Result<(), anyhow::Error> {
+        fail::fail_point!("kubernetes_drop_service", |_| Err(anyhow!("failpoint")));
+        self.service_scales
+            .lock()
+            .expect("poisoned lock")
+            .remove(id);
+        let name = format!("{}-{id}", self.namespace);
+        if self.config.dry_run {
+            info!("dry run: not deleting objects for service {name}");
+            return Ok(());
+        }
+        let res = self
+            .stateful_set_api
+            .delete(&name, &DeleteParams::default())
+            .await;
+        match res {
+            Ok(_) => (),
+            Err(Error::Api(e)) if e.code == 404 => (),
+            Err(e) => return Err(e.into()),
+        }
+
+        self.delete_pod_disruption_budget(id, &name).await?;
+        self.service_budgets
+            .lock()
+            .expect("poisoned lock")
+            .remove(id);
+
+        let res = self
+            .service_api
+            .delete(&name, &DeleteParams::default())
+            .await;
+        match res {
+            Ok(_) => Ok(()),
+            Err(Error::Api(e)) if e.code == 404 => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Lists the identifiers of all known services.
+    async fn list_services(&self) -> Result<Vec<String>, anyhow::Error> {
+        list_service_ids(&self.stateful_set_api, &self.namespace).await
     }
 
-    /// Lists the identifiers of all known services.
-    async fn list_services(&self) -> Result<Vec<String>, anyhow::Error> {
-        let stateful_sets = self.stateful_set_api.list(&Default::default()).await?;
-        let name_prefix = format!("{}-", self.namespace);
-        Ok(stateful_sets
-            .into_iter()
-            .filter_map(|ss| {
-                ss.metadata
-                    .name
-                    .unwrap()
-                    .strip_prefix(&name_prefix)
-                    .map(Into::into)
-            })
-            .collect())
-    }
-
     fn watch_services(&self) -> BoxStream<'static, Result<ServiceEvent, anyhow::Error>> {
-        fn into_service_event(pod: Pod) -> Result<ServiceEvent, anyhow::Error> {
+        self.watch_services_from(None)
+    }
+
+    fn watch_services_from(
+        &self,
+        resource_version: Option<String>,
+    ) -> BoxStream<'static, Result<ServiceEvent, anyhow::Error>> {
+        fn into_service_event(
+            pod: Pod,
+            resource_version: Option<String>,
+        ) -> Result<ServiceEvent, anyhow::Error> {
             let process_id = pod.name_any().split('-').last().unwrap().parse()?;
             let service_id_label = "environmentd.materialize.cloud/service-id";
             let service_id = pod
@@ -1089,38 +1410,1956 @@ impl NamespacedOrchestrator for NamespacedKubernetesOrchestrator {
                 process_id,
                 status,
                 time,
+                resource_version,
             })
         }
 
-        let stream = watcher(self.pod_api.clone(), self.list_pod_params())
-            .touched_objects()
-            .filter_map(|object| async move {
-                match object {
-                    Ok(pod) => Some(into_service_event(pod)),
-                    Err(error) => {
-                        // We assume that errors returned by Kubernetes are usually transient, so we
-                        // just log a warning and ignore them otherwise.
-                        tracing::warn!("service watch error: {error}");
-                        None
+        let stream = watch_pods_from(
+            self.pod_api.clone(),
+            self.list_pod_params(),
+            resource_version,
+        )
+        .map(|event| {
+            let (pod, resource_version) = event?;
+            into_service_event(pod, resource_version)
+        });
+        Box::pin(stream)
+    }
+}
+
+/// Returns the address of each of the `scale` processes of the service whose
+/// stateful set is named `name`.
+///
+/// See [`KubernetesOrchestratorConfig::announce_address`] for the meaning of
+/// `announce_address`.
+fn service_hosts(
+    name: &str,
+    namespace: &str,
+    scale: u16,
+    announce_address: Option<&str>,
+) -> Vec<String> {
+    (0..scale)
+        .map(|i| match announce_address {
+            Some(template) => template
+                .replace("{name}", name)
+                .replace("{index}", &i.to_string())
+                .replace("{namespace}", namespace),
+            None => format!("{name}-{i}.{name}.{namespace}.svc.cluster.local"),
+        })
+        .collect()
+}
+
+/// Runs the Kubernetes API request produced by `f`, retrying with backoff if
+/// it fails with a transient error.
+///
+/// Conflicts (409), which arise when another actor updates an object
+/// concurrently, and throttling (429) are considered transient. Any other
+/// error is returned immediately, as is the last error once
+/// [`MAX_APPLY_TRIES`] attempts have been made.
+async fn retry_transient<F, U, T>(mut f: F) -> Result<T, kube::Error>
+where
+    F: FnMut() -> U,
+    U: Future<Output = Result<T, kube::Error>>,
+{
+    Retry::default()
+        .initial_backoff(APPLY_INITIAL_BACKOFF)
+        .max_tries(MAX_APPLY_TRIES)
+        .retry_async(|state| {
+            let request = f();
+            async move {
+                match request.await {
+                    Ok(t) => RetryResult::Ok(t),
+                    Err(kube::Error::Api(e)) if e.code == 409 || e.code == 429 => {
+                        if state.next_backoff.is_some() {
+                            warn!("transient kubernetes API error, retrying: {e}");
+                        }
+                        RetryResult::RetryableErr(kube::Error::Api(e))
                     }
+                    Err(e) => RetryResult::FatalErr(e),
                 }
-            });
-        Box::pin(stream)
+            }
+        })
+        .await
+}
+
+/// The subset of the Kubernetes pod API used by [`delete_outdated_pods`] and
+/// [`fetch_pod_logs`].
+#[async_trait]
+trait PodApi: Sync {
+    /// Fetches the named pod.
+    async fn get_pod(&self, name: &str) -> Result<Pod, kube::Error>;
+    /// Deletes the named pod.
+    async fn delete_pod(&self, name: &str) -> Result<(), kube::Error>;
+    /// Fetches the logs of the named pod.
+    async fn pod_logs(&self, name: &str, lp: &LogParams) -> Result<String, kube::Error>;
+}
+
+#[async_trait]
+impl PodApi for Api<Pod> {
+    async fn get_pod(&self, name: &str) -> Result<Pod, kube::Error> {
+        self.get(name).await
+    }
+
+    async fn delete_pod(&self, name: &str) -> Result<(), kube::Error> {
+        self.delete(name, &DeleteParams::default()).await?;
+        Ok(())
+    }
+
+    async fn pod_logs(&self, name: &str, lp: &LogParams) -> Result<String, kube::Error> {
+        self.logs(name, lp).await
     }
 }
 
-#[derive(Debug, Clone)]
-struct KubernetesService {
-    hosts: Vec<String>,
-    ports: BTreeMap<String, u16>,
+/// Fetches the logs of the main container of the named pod.
+///
+/// If `lines` is `Some(n)`, only the last `n` lines of the logs are returned.
+async fn fetch_pod_logs<P>(
+    pod_api: &P,
+    name: &str,
+    lines: Option<usize>,
+) -> Result<String, anyhow::Error>
+where
+    P: PodApi,
+{
+    let pod = match pod_api.get_pod(name).await {
+        Ok(pod) => pod,
+        Err(kube::Error::Api(e)) if e.code == 404 => anyhow::bail!("pod {name} does not exist"),
+        Err(e) => return Err(e.into()),
+    };
+    // Kubernetes refuses to fetch logs without a container name if the pod
+    // has sidecars. The main container is always the first.
+    let container = pod
+        .spec
+        .and_then(|spec| spec.containers.into_iter().next())
+        .map(|container| container.name);
+    let lp = LogParams {
+        container,
+        tail_lines: lines.map(i64::try_from).transpose()?,
+        ..Default::default()
+    };
+    match pod_api.pod_logs(name, &lp).await {
+        Ok(logs) => Ok(logs),
+        Err(kube::Error::Api(e)) if e.code == 404 => anyhow::bail!("pod {name} does not exist"),
+        Err(e) => Err(e.into()),
+    }
 }
 
-impl Service for KubernetesService {
-    fn addresses(&self, port: &str) -> Vec<String> {
-        let port = self.ports[port];
-        self.hosts
-            .iter()
-            .map(|host| format!("{host}:{port}"))
-            .collect()
+/// The subset of the Kubernetes stateful set API used by
+/// [`list_service_ids`].
+#[async_trait]
+trait StatefulSetApi: Sync {
+    /// Lists all stateful sets.
+    async fn list_stateful_sets(&self) -> Result<Vec<StatefulSet>, kube::Error>;
+}
+
+#[async_trait]
+impl StatefulSetApi for Api<StatefulSet> {
+    async fn list_stateful_sets(&self) -> Result<Vec<StatefulSet>, kube::Error> {
+        Ok(self.list(&Default::default()).await?.items)
+    }
+}
+
+/// Lists the IDs of the services in `namespace`, in sorted order.
+async fn list_service_ids<S>(
+    stateful_set_api: &S,
+    namespace: &str,
+) -> Result<Vec<String>, anyhow::Error>
+where
+    S: StatefulSetApi,
+{
+    let stateful_sets = stateful_set_api.list_stateful_sets().await?;
+    let name_prefix = format!("{namespace}-");
+    let mut ids: Vec<String> = stateful_sets
+        .into_iter()
+        .filter_map(|ss| {
+            ss.metadata
+                .name
+                .unwrap()
+                .strip_prefix(&name_prefix)
+                .map(Into::into)
+        })
+        .collect();
+    // The Kubernetes API does not guarantee any particular order.
+    ids.sort();
+    Ok(ids)
+}
+
+/// Deletes those of the named pods whose pod template hash annotation does
+/// not match `pod_template_hash`.
+///
+/// Pods are inspected concurrently, with at most
+/// [`MAX_CONCURRENT_POD_REPLACEMENTS`] requests in flight at once. Pods that
+/// do not exist are ignored.
+async fn delete_outdated_pods<P, I>(
+    pod_api: &P,
+    pod_names: I,
+    pod_template_hash: &str,
+) -> Result<(), anyhow::Error>
+where
+    P: PodApi,
+    I: IntoIterator<Item = String>,
+{
+    futures::stream::iter(pod_names)
+        .map(Ok)
+        .try_for_each_concurrent(MAX_CONCURRENT_POD_REPLACEMENTS, |pod_name| async move {
+            let pod = match pod_api.get_pod(&pod_name).await {
+                Ok(pod) => pod,
+                // Pod already doesn't exist.
+                Err(kube::Error::Api(e)) if e.code == 404 => return Ok(()),
+                Err(e) => return Err(e.into()),
+            };
+            let hash = pod.annotations().get(POD_TEMPLATE_HASH_ANNOTATION);
+            if hash.map(|h| h.as_str()) != Some(pod_template_hash) {
+                match pod_api.delete_pod(&pod_name).await {
+                    Ok(()) => (),
+                    // Pod got deleted while we were looking at it.
+                    Err(kube::Error::Api(e)) if e.code == 404 => (),
+                    Err(e) => return Err(e.into()),
+                }
+            }
+            Ok(())
+        })
+        .await
+}
+
+/// The subset of the Kubernetes pod API used by [`watch_pods_from`].
+#[async_trait]
+trait PodWatcher: Send + Sync + 'static {
+    /// Lists the pods matching `lp`.
+    async fn list_pods(&self, lp: &ListParams) -> Result<ObjectList<Pod>, kube::Error>;
+    /// Watches for changes to the pods matching `lp` that occurred after
+    /// `version`.
+    async fn watch_pods(
+        &self,
+        lp: &ListParams,
+        version: &str,
+    ) -> Result<BoxStream<'static, Result<WatchEvent<Pod>, kube::Error>>, kube::Error>;
+}
+
+#[async_trait]
+impl PodWatcher for Api<Pod> {
+    async fn list_pods(&self, lp: &ListParams) -> Result<ObjectList<Pod>, kube::Error> {
+        self.list(lp).await
+    }
+
+    async fn watch_pods(
+        &self,
+        lp: &ListParams,
+        version: &str,
+    ) -> Result<BoxStream<'static, Result<WatchEvent<Pod>, kube::Error>>, kube::Error> {
+        Ok(self.watch(lp, version).await?.boxed())
+    }
+}
+
+/// Watches the pods matching `lp`, starting after `resource_version`.
+///
+/// Each added, modified, or deleted pod is emitted along with the resource
+/// version at which the change was observed. Passing that version to a later
+/// call resumes the watch without gaps and without replaying events.
+///
+/// If `resource_version` is `None`, or if Kubernetes reports that the
+/// requested version is too old to resume from, the current state of all pods
+/// is listed and emitted before watching resumes. The watch resumes after the
+/// whole list, so only the last listed pod carries the list's version; the
+/// others carry `None`, as resuming from them would skip the rest of the list.
+///
+/// Errors are emitted into the stream, after which the watch is retried from
+/// the last observed version. Consecutive failures back off exponentially,
+/// from [`WATCH_INITIAL_BACKOFF`] up to [`WATCH_MAX_BACKOFF`], like the
+/// default backoff of `kube_runtime`'s watcher.
+fn watch_pods_from<W>(
+    api: W,
+    lp: ListParams,
+    resource_version: Option<String>,
+) -> impl Stream<Item = Result<(Pod, Option<String>), kube::Error>>
+where
+    W: PodWatcher,
+{
+    stream! {
+        let mut resource_version = resource_version;
+        let mut backoff = WATCH_INITIAL_BACKOFF;
+        loop {
+            let mut version = match resource_version.take() {
+                Some(version) => version,
+                None => match api.list_pods(&lp).await {
+                    Ok(list) => {
+                        backoff = WATCH_INITIAL_BACKOFF;
+                        let version = list.metadata.resource_version.unwrap_or_default();
+                        let mut pods = list.items.into_iter().peekable();
+                        while let Some(pod) = pods.next() {
+                            let resume_version = pods.peek().is_none().then(|| version.clone());
+                            yield Ok((pod, resume_version));
+                        }
+                        version
+                    }
+                    Err(e) => {
+                        yield Err(e);
+                        tokio::time::sleep(backoff).await;
+                        backoff = (backoff * 2).min(WATCH_MAX_BACKOFF);
+                        continue;
+                    }
+                },
+            };
+
+            // Whether `version` has expired and the pods must be relisted.
+            let mut expired = false;
+            // Whether the watch ended with an error.
+            let mut failed = true;
+            match api.watch_pods(&lp, &version).await {
+                Ok(mut events) => {
+                    failed = false;
+                    while let Some(event) = events.next().await {
+                        match event {
+                            Ok(
+                                WatchEvent::Added(pod)
+                                | WatchEvent::Modified(pod)
+                                | WatchEvent::Deleted(pod),
+                            ) => {
+                                backoff = WATCH_INITIAL_BACKOFF;
+                                if let Some(pod_version) = pod.resource_version() {
+                                    version = pod_version;
+                                }
+                                yield Ok((pod, Some(version.clone())));
+                            }
+                            Ok(WatchEvent::Bookmark(bookmark)) => {
+                                backoff = WATCH_INITIAL_BACKOFF;
+                                version = bookmark.metadata.resource_version;
+                            }
+                            Ok(WatchEvent::Error(e)) => {
+                                // 410 Gone indicates that `version` is too old
+                                // to resume from.
+                                expired = e.code == 410;
+                                failed = true;
+                                yield Err(kube::Error::Api(e));
+                                break;
+                            }
+                            Err(e) => {
+                                failed = true;
+                                yield Err(e);
+                                break;
+                            }
+                        }
+                    }
+                }
+                Err(e) => {
+                    expired = matches!(&e, kube::Error::Api(e) if e.code == 410);
+                    yield Err(e);
+                }
+            }
+            if !expired {
+                resource_version = Some(version);
+            }
+            if failed {
+                tokio::time::sleep(backoff).await;
+                backoff = (backoff * 2).min(WATCH_MAX_BACKOFF);
+            }
+        }
+    }
+}
+
+/// Returns the environment variables that expose the pod's namespace, name,
+/// and node name via the downward API.
+fn downward_api_env() -> Vec<EnvVar> {
+    [
+        ("MZ_NAMESPACE", "metadata.namespace"),
+        ("MZ_POD_NAME", "metadata.name"),
+        ("MZ_NODE_NAME", "spec.nodeName"),
+    ]
+    .into_iter()
+    .map(|(name, field_path)| EnvVar {
+        name: name.to_string(),
+        value_from: Some(EnvVarSource {
+            field_ref: Some(ObjectFieldSelector {
+                field_path: field_path.to_string(),
+                ..Default::default()
+            }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    })
+    .collect()
+}
+
+/// Returns the environment variables for a service's main container: the
+/// downward API variables, followed by the service-specified variables in
+/// `env`, followed by the LLVM profile file location, if coverage is enabled.
+fn main_container_env(
+    env: Vec<(String, String)>,
+    coverage_profile_file: Option<String>,
+) -> Vec<EnvVar> {
+    let mut vars = downward_api_env();
+    vars.extend(env.into_iter().map(|(name, value)| EnvVar {
+        name,
+        value: Some(value),
+        ..Default::default()
+    }));
+    if let Some(file) = coverage_profile_file {
+        vars.push(EnvVar {
+            name: "LLVM_PROFILE_FILE".to_string(),
+            value: Some(file),
+            ..Default::default()
+        });
+    }
+    vars
+}
+
+/// Constructs the annotations that allow Prometheus to discover and scrape
+/// the metrics that a service exposes on the port named `metrics_port`.
+fn prometheus_annotations(
+    ports: &[mz_orchestrator::ServicePort],
+    metrics_port: &str,
+    metrics_path: Option<&str>,
+) -> Result<BTreeMap<String, String>, anyhow::Error> {
+    let port = ports
+        .iter()
+        .find(|port| port.name == metrics_port)
+        .ok_or_else(|| anyhow!("metrics port {metrics_port} is not a port of the service"))?;
+    Ok(btreemap! {
+        "prometheus.io/scrape".into() => "true".into(),
+        "prometheus.io/port".into() => port.port_hint.to_string(),
+        "prometheus.io/path".into() => metrics_path.unwrap_or("/metrics").into(),
+    })
+}
+
+/// Constructs the resource limits for a container from the given memory and CPU
+/// limits.
+fn resource_limits(
+    memory_limit: Option<MemoryLimit>,
+    cpu_limit: Option<CpuLimit>,
+) -> BTreeMap<String, Quantity> {
+    let mut limits = BTreeMap::new();
+    if let Some(memory_limit) = memory_limit {
+        limits.insert(
+            "memory".into(),
+            Quantity(memory_limit.0.as_u64().to_string()),
+        );
+    }
+    if let Some(cpu_limit) = cpu_limit {
+        limits.insert(
+            "cpu".into(),
+            Quantity(format!("{}m", cpu_limit.as_millicpus())),
+        );
+    }
+    limits
+}
+
+/// Returns the containers for a service's pod: the main container, followed
+/// by a container for each sidecar.
+fn pod_containers(
+    main_container: Container,
+    sidecars: Vec<SidecarSpec>,
+    image_pull_policy: KubernetesImagePullPolicy,
+    security_context: Option<SecurityContext>,
+) -> Vec<Container> {
+    let mut containers = vec![main_container];
+    for sidecar in sidecars {
+        let limits = resource_limits(sidecar.memory_limit, sidecar.cpu_limit);
+        containers.push(Container {
+            name: sidecar.name,
+            image: Some(sidecar.image),
+            args: Some(sidecar.args),
+            image_pull_policy: Some(image_pull_policy.to_string()),
+            ports: Some(
+                sidecar
+                    .ports
+                    .into_iter()
+                    .map(|port| ContainerPort {
+                        container_port: port.port_hint.into(),
+                        name: Some(port.name),
+                        ..Default::default()
+                    })
+                    .collect(),
+            ),
+            resources: Some(ResourceRequirements {
+                // As with the main container, set both limits and requests to
+                // the same values to preserve the `Guaranteed` QoS class.
+                limits: Some(limits.clone()),
+                requests: Some(limits),
+            }),
+            env: Some(downward_api_env()),
+            security_context: security_context.clone(),
+            ..Default::default()
+        });
+    }
+    containers
+}
+
+/// Builds an anti-affinity that prevents pods matching all of the given
+/// requirements from being scheduled in the same topology domain.
+fn pod_anti_affinity(
+    label_selector_requirements: Vec<LabelSelectorRequirement>,
+    topology_key: &str,
+) -> PodAntiAffinity {
+    let ls = LabelSelector {
+        match_expressions: Some(label_selector_requirements),
+        ..Default::default()
+    };
+    let pat = PodAffinityTerm {
+        label_selector: Some(ls),
+        topology_key: topology_key.to_string(),
+        ..Default::default()
+    };
+    PodAntiAffinity {
+        required_during_scheduling_ignored_during_execution: Some(vec![pat]),
+        ..Default::default()
+    }
+}
+
+/// Converts the configured host aliases into their Kubernetes representation,
+/// or `None` if there are none, so that the field is omitted from the pod spec.
+fn host_aliases(aliases: &[(String, Vec<String>)]) -> Option<Vec<HostAlias>> {
+    if aliases.is_empty() {
+        return None;
+    }
+    Some(
+        aliases
+            .iter()
+            .map(|(ip, hostnames)| HostAlias {
+                ip: Some(ip.clone()),
+                hostnames: Some(hostnames.clone()),
+            })
+            .collect(),
+    )
+}
+
+/// Returns the `type` and `clusterIP` of the Kubernetes service backing a
+/// service of the given type.
+///
+/// Note that Kubernetes does not permit changing the `clusterIP` of an
+/// existing service, so a service cannot be made headless, or vice versa,
+/// after it is created.
+fn service_type_and_cluster_ip(
+    service_type: ServiceType,
+    headless: bool,
+) -> Result<(String, Option<String>), anyhow::Error> {
+    let type_ = match service_type {
+        ServiceType::ClusterIp => "ClusterIP",
+        ServiceType::NodePort => "NodePort",
+    };
+    let cluster_ip = match (service_type, headless) {
+        (_, false) => None,
+        (ServiceType::ClusterIp, true) => Some("None".to_string()),
+        (ServiceType::NodePort, true) => {
+            anyhow::bail!("headless services must have type ClusterIP, not NodePort")
+        }
+    };
+    Ok((type_.to_string(), cluster_ip))
+}
+
+/// Builds an `emptyDir` volume backing the given scratch volume, along with
+/// the mount for the main container.
+fn scratch_empty_dir(scratch_volume: ScratchVolume) -> (Volume, VolumeMount) {
+    let volume = Volume {
+        name: "scratch-empty-dir".to_string(),
+        empty_dir: Some(EmptyDirVolumeSource {
+            size_limit: scratch_volume
+                .size_limit
+                .map(|limit| Quantity(limit.0.as_u64().to_string())),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    let volume_mount = VolumeMount {
+        name: "scratch-empty-dir".to_string(),
+        mount_path: scratch_volume.mount_path,
+        ..Default::default()
+    };
+    (volume, volume_mount)
+}
+
+/// Computes the hash of a pod template, used to detect pods whose
+/// configuration is out of date.
+fn pod_template_hash(pod_template_spec: &PodTemplateSpec) -> String {
+    let pod_template_json = serde_json::to_string(pod_template_spec).unwrap();
+    let mut hasher = Sha256::new();
+    hasher.update(pod_template_json);
+    format!("{:x}", hasher.finalize())
+}
+
+/// Adds the service's `pod_labels` to the labels of its pod template.
+///
+/// The pod labels must not override any of the `match_labels`, as the pods
+/// would then no longer be selected by their own stateful set.
+fn add_pod_labels(
+    labels: &mut BTreeMap<String, String>,
+    match_labels: &BTreeMap<String, String>,
+    pod_labels: BTreeMap<String, String>,
+) -> Result<(), anyhow::Error> {
+    for (key, value) in pod_labels {
+        if match_labels.contains_key(&key) {
+            anyhow::bail!("pod label {key} conflicts with a match label");
+        }
+        labels.insert(key, value);
+    }
+    Ok(())
+}
+
+/// Constructs a `PodDisruptionBudget` named `name` that applies `budget` to
+/// the pods matching `match_labels`.
+fn pod_disruption_budget(
+    name: String,
+    match_labels: BTreeMap<String, String>,
+    budget: DisruptionBudget,
+) -> PodDisruptionBudget {
+    let (min_available, max_unavailable) = match budget {
+        DisruptionBudget::MinAvailable(n) => (Some(IntOrString::Int(n.into())), None),
+        DisruptionBudget::MaxUnavailable(n) => (None, Some(IntOrString::Int(n.into()))),
+    };
+    PodDisruptionBudget {
+        metadata: ObjectMeta {
+            name: Some(name),
+            ..Default::default()
+        },
+        spec: Some(PodDisruptionBudgetSpec {
+            min_available,
+            max_unavailable,
+            selector: Some(LabelSelector {
+                match_labels: Some(match_labels),
+                ..Default::default()
+            }),
+        }),
+        status: None,
+    }
+}
+
+/// The Kubernetes objects that make up a service, as constructed by
+/// [`NamespacedKubernetesOrchestrator::service_objects`].
+struct ServiceObjects {
+    /// The name shared by all of the objects.
+    name: String,
+    service: K8sService,
+    stateful_set: StatefulSet,
+    pod_disruption_budget: Option<PodDisruptionBudget>,
+    /// The hash of the stateful set's pod template.
+    pod_template_hash: String,
+    /// The addresses of the service's processes.
+    hosts: Vec<String>,
+    /// The service's ports, by name.
+    ports: BTreeMap<String, u16>,
+}
+
+#[derive(Debug, Clone)]
+struct KubernetesService {
+    hosts: Vec<String>,
+    ports: BTreeMap<String, u16>,
+}
+
+impl Service for KubernetesService {
+    fn addresses(&self, port: &str) -> Vec<String> {
+        let port = self.ports[port];
+        self.hosts
+            .iter()
+            .map(|host| format!("{host}:{port}"))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::ListMeta;
+
+    use super::*;
+
+    #[mz_ore::test]
+    fn test_update_strategy() {
+        for (input, strategy, replaces_pod_0) in [
+            (
+                "rolling-update",
+                KubernetesUpdateStrategy::RollingUpdate { partition: None },
+                true,
+            ),
+            (
+                "rolling-update:1",
+                KubernetesUpdateStrategy::RollingUpdate { partition: Some(1) },
+                false,
+            ),
+            ("on-delete", KubernetesUpdateStrategy::OnDelete, true),
+        ] {
+            assert_eq!(input.parse::<KubernetesUpdateStrategy>().unwrap(), strategy);
+            assert_eq!(strategy.to_string(), input);
+            assert_eq!(strategy.replaces(0), replaces_pod_0);
+            assert!(strategy.replaces(1));
+        }
+
+        for input in [
+            "",
+            "rolling",
+            "on-delete:1",
+            "rolling-update:",
+            "rolling-update:x",
+        ] {
+            assert!(input.parse::<KubernetesUpdateStrategy>().is_err());
+        }
+
+        let spec = KubernetesUpdateStrategy::OnDelete.to_k8s();
+        assert_eq!(spec.type_.as_deref(), Some("OnDelete"));
+        assert_eq!(spec.rolling_update, None);
+
+        let spec = KubernetesUpdateStrategy::RollingUpdate { partition: Some(2) }.to_k8s();
+        assert_eq!(spec.type_.as_deref(), Some("RollingUpdate"));
+        assert_eq!(spec.rolling_update.unwrap().partition, Some(2));
+    }
+
+    /// A fake [`PodApi`] backed by an in-memory map of pod names to pod
+    /// template hashes, and of pod and container names to canned logs.
+    ///
+    /// Every pod has a `clusterd` container and a `metrics` sidecar.
+    struct FakePodApi {
+        pods: Mutex<BTreeMap<String, String>>,
+        deleted: Mutex<Vec<String>>,
+        logs: BTreeMap<(String, String), String>,
+    }
+
+    #[async_trait]
+    impl PodApi for FakePodApi {
+        async fn get_pod(&self, name: &str) -> Result<Pod, kube::Error> {
+            let pods = self.pods.lock().expect("poisoned lock");
+            match pods.get(name) {
+                Some(hash) => Ok(Pod {
+                    metadata: ObjectMeta {
+                        name: Some(name.into()),
+                        annotations: Some(btreemap! {
+                            POD_TEMPLATE_HASH_ANNOTATION.into() => hash.clone(),
+                        }),
+                        ..Default::default()
+                    },
+                    spec: Some(PodSpec {
+                        containers: ["clusterd", "metrics"]
+                            .into_iter()
+                            .map(|name| Container {
+                                name: name.into(),
+                                ..Default::default()
+                            })
+                            .collect(),
+                        ..Default::default()
+                    }),
+                    ..Default::default()
+                }),
+                None => Err(not_found()),
+            }
+        }
+
+        async fn delete_pod(&self, name: &str) -> Result<(), kube::Error> {
+            let mut pods = self.pods.lock().expect("poisoned lock");
+            match pods.remove(name) {
+                Some(_) => {
+                    self.deleted
+                        .lock()
+                        .expect("poisoned lock")
+                        .push(name.into());
+                    Ok(())
+                }
+                None => Err(not_found()),
+            }
+        }
+
+        async fn pod_logs(&self, name: &str, lp: &LogParams) -> Result<String, kube::Error> {
+            if !self.pods.lock().expect("poisoned lock").contains_key(name) {
+                return Err(not_found());
+            }
+            let Some(container) = &lp.container else {
+                return Err(kube::Error::Api(kube::error::ErrorResponse {
+                    status: "Failure".into(),
+                    message: format!("a container name must be specified for pod {name}"),
+                    reason: "BadRequest".into(),
+                    code: 400,
+                }));
+            };
+            let logs = self
+                .logs
+                .get(&(name.into(), container.clone()))
+                .ok_or_else(not_found)?;
+            let lines: Vec<_> = logs.lines().collect();
+            let skip = match lp.tail_lines {
+                Some(n) => lines.len().saturating_sub(usize::try_from(n).unwrap()),
+                None => 0,
+            };
+            Ok(lines[skip..]
+                .iter()
+                .map(|line| format!("{line}\n"))
+                .collect())
+        }
+    }
+
+    fn not_found() -> kube::Error {
+        kube::Error::Api(kube::error::ErrorResponse {
+            status: "Failure".into(),
+            message: "not found".into(),
+            reason: "NotFound".into(),
+            code: 404,
+        })
+    }
+
+    #[mz_ore::test]
+    fn test_delete_outdated_pods() {
+        // Every third pod is outdated, and pod 7 does not exist.
+        let mut pods = BTreeMap::new();
+        let mut expected = vec![];
+        for i in 0..50 {
+            let name = format!("cluster-u1-{i}");
+            if i == 7 {
+                continue;
+            } else if i % 3 == 0 {
+                pods.insert(name.clone(), "old".to_string());
+                expected.push(name);
+            } else {
+                pods.insert(name, "new".to_string());
+            }
+        }
+        let api = FakePodApi {
+            pods: Mutex::new(pods),
+            deleted: Mutex::new(vec![]),
+            logs: BTreeMap::new(),
+        };
+
+        let pod_names = (0..50).map(|i| format!("cluster-u1-{i}"));
+        futures::executor::block_on(delete_outdated_pods(&api, pod_names, "new")).unwrap();
+
+        let mut deleted = api.deleted.into_inner().unwrap();
+        deleted.sort();
+        expected.sort();
+        assert_eq!(deleted, expected);
+        let remaining = api.pods.into_inner().unwrap();
+        assert_eq!(remaining.len(), 49 - expected.len());
+        assert!(remaining.values().all(|hash| hash == "new"));
+    }
+
+    #[mz_ore::test]
+    fn test_fetch_pod_logs() {
+        let api = FakePodApi {
+            pods: Mutex::new(btreemap! { "cluster-u1-0".into() => "hash".into() }),
+            deleted: Mutex::new(vec![]),
+            logs: btreemap! {
+                ("cluster-u1-0".into(), "clusterd".into()) =>
+                    "starting\nlistening\nready\n".into(),
+                ("cluster-u1-0".into(), "metrics".into()) => "scraped\n".into(),
+            },
+        };
+
+        let logs = futures::executor::block_on(fetch_pod_logs(&api, "cluster-u1-0", None));
+        assert_eq!(logs.unwrap(), "starting\nlistening\nready\n");
+
+        let logs = futures::executor::block_on(fetch_pod_logs(&api, "cluster-u1-0", Some(2)));
+        assert_eq!(logs.unwrap(), "listening\nready\n");
+
+        let err = futures::executor::block_on(fetch_pod_logs(&api, "cluster-u1-1", None));
+        assert_eq!(
+            err.unwrap_err().to_string(),
+            "pod cluster-u1-1 does not exist"
+        );
+    }
+
+    /// A fake [`StatefulSetApi`] that lists stateful sets with the given
+    /// names, in the given order.
+    struct FakeStatefulSetApi(Vec<&'static str>);
+
+    #[async_trait]
+    impl StatefulSetApi for FakeStatefulSetApi {
+        async fn list_stateful_sets(&self) -> Result<Vec<StatefulSet>, kube::Error> {
+            Ok(self
+                .0
+                .iter()
+                .map(|name| StatefulSet {
+                    metadata: ObjectMeta {
+                        name: Some(name.to_string()),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .collect())
+        }
+    }
+
+    #[mz_ore::test]
+    fn test_list_service_ids() {
+        let api = FakeStatefulSetApi(vec![
+            "cluster-u3",
+            "cluster-s1",
+            "other-u0",
+            "cluster-u10",
+            "cluster-u1",
+        ]);
+        let ids = futures::executor::block_on(list_service_ids(&api, "cluster"));
+        assert_eq!(ids.unwrap(), ["s1", "u1", "u10", "u3"]);
+    }
+
+    /// A fake API request whose first attempts fail with the specified status
+    /// codes and whose subsequent attempts succeed.
+    struct FakeApply {
+        failures: Mutex<Vec<u16>>,
+        attempts: Mutex<usize>,
+    }
+
+    impl FakeApply {
+        fn new(failures: &[u16]) -> FakeApply {
+            FakeApply {
+                failures: Mutex::new(failures.iter().rev().copied().collect()),
+                attempts: Mutex::new(0),
+            }
+        }
+
+        async fn apply(&self) -> Result<(), kube::Error> {
+            *self.attempts.lock().expect("poisoned lock") += 1;
+            match self.failures.lock().expect("poisoned lock").pop() {
+                Some(code) => Err(kube::Error::Api(kube::error::ErrorResponse {
+                    status: "Failure".into(),
+                    message: format!("failed with {code}"),
+                    reason: "Test".into(),
+                    code,
+                })),
+                None => Ok(()),
+            }
+        }
+
+        fn attempts(&self) -> usize {
+            *self.attempts.lock().expect("poisoned lock")
+        }
+    }
+
+    #[mz_ore::test(tokio::test)]
+    async fn test_retry_transient() {
+        // Conflicts and throttling are retried until the request succeeds.
+        let api = FakeApply::new(&[409]);
+        retry_transient(|| api.apply()).await.unwrap();
+        assert_eq!(api.attempts(), 2);
+
+        let api = FakeApply::new(&[429, 409]);
+        retry_transient(|| api.apply()).await.unwrap();
+        assert_eq!(api.attempts(), 3);
+
+        // Other errors are returned immediately.
+        let api = FakeApply::new(&[500]);
+        match retry_transient(|| api.apply()).await {
+            Err(kube::Error::Api(e)) => assert_eq!(e.code, 500),
+            res => panic!("unexpected result: {res:?}"),
+        }
+        assert_eq!(api.attempts(), 1);
+
+        // Persistent conflicts are eventually returned.
+        let api = FakeApply::new(&[409; MAX_APPLY_TRIES]);
+        match retry_transient(|| api.apply()).await {
+            Err(kube::Error::Api(e)) => assert_eq!(e.code, 409),
+            res => panic!("unexpected result: {res:?}"),
+        }
+        assert_eq!(api.attempts(), MAX_APPLY_TRIES);
+    }
+
+    /// A fake [`PodWatcher`] backed by an in-memory history of pod changes.
+    ///
+    /// Each entry in the history is assigned the resource version one greater
+    /// than the previous entry, starting at 1.
+    #[derive(Clone)]
+    struct FakePodWatcher {
+        history: Arc<Mutex<Vec<WatchEvent<Pod>>>>,
+        lists: Arc<Mutex<usize>>,
+    }
+
+    impl FakePodWatcher {
+        /// Records a change to the named pod, followed by a bookmark.
+        fn push(&self, name: &str) {
+            let mut history = self.history.lock().expect("poisoned lock");
+            let version = history.len() + 1;
+            history.push(WatchEvent::Modified(Pod {
+                metadata: ObjectMeta {
+                    name: Some(name.into()),
+                    resource_version: Some(version.to_string()),
+                    ..Default::default()
+                },
+                ..Default::default()
+            }));
+            let bookmark = serde_json::json!({
+                "apiVersion": "v1",
+                "kind": "Pod",
+                "metadata": {
+                    "resourceVersion": (version + 1).to_string(),
+                    "annotations": {},
+                },
+            });
+            history.push(WatchEvent::Bookmark(
+                serde_json::from_value(bookmark).unwrap(),
+            ));
+        }
+    }
+
+    #[async_trait]
+    impl PodWatcher for FakePodWatcher {
+        async fn list_pods(&self, _lp: &ListParams) -> Result<ObjectList<Pod>, kube::Error> {
+            *self.lists.lock().expect("poisoned lock") += 1;
+            let history = self.history.lock().expect("poisoned lock");
+            let items = history
+                .iter()
+                .filter_map(|event| match event {
+                    WatchEvent::Modified(pod) => Some(pod.clone()),
+                    _ => None,
+                })
+                .collect();
+            Ok(ObjectList {
+                metadata: ListMeta {
+                    resource_version: Some(history.len().to_string()),
+                    ..Default::default()
+                },
+                items,
+            })
+        }
+
+        async fn watch_pods(
+            &self,
+            _lp: &ListParams,
+            version: &str,
+        ) -> Result<BoxStream<'static, Result<WatchEvent<Pod>, kube::Error>>, kube::Error> {
+            let version: usize = version.parse().unwrap();
+            let history = self.history.lock().expect("poisoned lock");
+            let events: Vec<_> = history[version..].iter().cloned().map(Ok).collect();
+            Ok(futures::stream::iter(events).boxed())
+        }
+    }
+
+    #[mz_ore::test]
+    fn test_watch_pods_from() {
+        let api = FakePodWatcher {
+            history: Arc::new(Mutex::new(vec![])),
+            lists: Arc::new(Mutex::new(0)),
+        };
+        let lp = ListParams::default();
+        type Watch = BoxStream<'static, Result<(Pod, Option<String>), kube::Error>>;
+        let next = |watch: &mut Watch| {
+            let (pod, version) = futures::executor::block_on(watch.next()).unwrap().unwrap();
+            (pod.name_any(), version)
+        };
+
+        // A fresh watch lists the existing pods, then emits subsequent
+        // changes. Only the last listed pod carries a version to resume from.
+        api.push("p-0");
+        api.push("p-1");
+        api.push("p-2");
+        let mut watch = watch_pods_from(api.clone(), lp.clone(), None).boxed();
+        assert_eq!(next(&mut watch), ("p-0".into(), None));
+        assert_eq!(next(&mut watch), ("p-1".into(), None));
+        assert_eq!(next(&mut watch), ("p-2".into(), Some("6".into())));
+        api.push("p-3");
+        assert_eq!(next(&mut watch), ("p-3".into(), Some("7".into())));
+        drop(watch);
+
+        // Restarting from the stored version neither replays already-seen
+        // changes nor relists.
+        api.push("p-4");
+        let mut watch = watch_pods_from(api.clone(), lp.clone(), Some("7".into())).boxed();
+        assert_eq!(next(&mut watch), ("p-4".into(), Some("9".into())));
+        assert_eq!(*api.lists.lock().expect("poisoned lock"), 1);
+        drop(watch);
+
+        // A caller that stops partway through the list has no version to
+        // resume from, so it relists and sees every pod.
+        let mut watch = watch_pods_from(api.clone(), lp.clone(), None).boxed();
+        assert_eq!(next(&mut watch), ("p-0".into(), None));
+        drop(watch);
+        let mut watch = watch_pods_from(api.clone(), lp, None).boxed();
+        let pods: Vec<_> = (0..5).map(|_| next(&mut watch)).collect();
+        assert_eq!(
+            pods.iter()
+                .map(|(name, _)| name.as_str())
+                .collect::<Vec<_>>(),
+            ["p-0", "p-1", "p-2", "p-3", "p-4"]
+        );
+        assert_eq!(pods[4].1, Some("10".into()));
+        api.push("p-5");
+        assert_eq!(next(&mut watch), ("p-5".into(), Some("11".into())));
+    }
+
+    /// A [`PodWatcher`] whose every request fails.
+    struct FailingPodWatcher;
+
+    #[async_trait]
+    impl PodWatcher for FailingPodWatcher {
+        async fn list_pods(&self, _lp: &ListParams) -> Result<ObjectList<Pod>, kube::Error> {
+            Err(internal_error())
+        }
+
+        async fn watch_pods(
+            &self,
+            _lp: &ListParams,
+            _version: &str,
+        ) -> Result<BoxStream<'static, Result<WatchEvent<Pod>, kube::Error>>, kube::Error> {
+            Err(internal_error())
+        }
+    }
+
+    fn internal_error() -> kube::Error {
+        kube::Error::Api(kube::error::ErrorResponse {
+            status: "Failure".into(),
+            message: "internal error".into(),
+            reason: "InternalError".into(),
+            code: 500,
+        })
+    }
+
+    #[mz_ore::test(tokio::test(start_paused = true))]
+    async fn test_watch_pods_from_backoff() {
+        // Persistent failures are retried with exponential backoff, rather
+        // than in a hot loop.
+        for resource_version in [None, Some("1".into())] {
+            let mut watch =
+                watch_pods_from(FailingPodWatcher, ListParams::default(), resource_version).boxed();
+            let mut times = vec![];
+            for _ in 0..9 {
+                assert!(watch.next().await.unwrap().is_err());
+                times.push(tokio::time::Instant::now());
+            }
+            let gaps: Vec<_> = times.windows(2).map(|w| w[1] - w[0]).collect();
+            let expected: Vec<_> = [800, 1600, 3200, 6400, 12800, 25600, 30000, 30000]
+                .into_iter()
+                .map(Duration::from_millis)
+                .collect();
+            assert_eq!(gaps, expected);
+        }
+    }
+
+    /// Returns the configuration of a dry-run orchestrator with no optional
+    /// features enabled.
+    fn orchestrator_config() -> KubernetesOrchestratorConfig {
+        KubernetesOrchestratorConfig {
+            context: "".into(),
+            scheduler_name: None,
+            priority_class_name: None,
+            service_labels: BTreeMap::new(),
+            service_node_selector: BTreeMap::new(),
+            service_account: None,
+            image_pull_policy: KubernetesImagePullPolicy::IfNotPresent,
+            aws_external_id_prefix: None,
+            coverage: false,
+            ephemeral_volume_storage_class: None,
+            service_fs_group: None,
+            pod_security_context: KubernetesPodSecurityContext::default(),
+            container_security_context: KubernetesContainerSecurityContext::default(),
+            update_strategy: KubernetesUpdateStrategy::default(),
+            announce_address: None,
+            dns_policy: None,
+            host_aliases: vec![],
+            anti_affinity_topology_key: None,
+            field_manager: None,
+            dry_run: true,
+        }
+    }
+
+    /// Constructs an orchestrator for the `cluster` namespace whose client
+    /// points at an address where no Kubernetes API server is listening, so
+    /// that any API call fails.
+    fn orchestrator(config: KubernetesOrchestratorConfig) -> NamespacedKubernetesOrchestrator {
+        let kube_config = kube::Config::new("http://127.0.0.1:1".parse().unwrap());
+        let client = Client::try_from(kube_config).unwrap();
+        NamespacedKubernetesOrchestrator {
+            metrics_api: Api::default_namespaced(client.clone()),
+            service_api: Api::default_namespaced(client.clone()),
+            stateful_set_api: Api::default_namespaced(client.clone()),
+            pod_api: Api::default_namespaced(client.clone()),
+            pod_disruption_budget_api: Api::default_namespaced(client),
+            kubernetes_namespace: "default".into(),
+            namespace: "cluster".into(),
+            config,
+            service_scales: Mutex::new(BTreeMap::new()),
+            service_budgets: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    fn no_args(_: &BTreeMap<String, String>) -> Vec<String> {
+        vec![]
+    }
+
+    /// Returns the configuration of a service with a `compute` and an
+    /// `internal-http` port and no optional features enabled.
+    fn service_config() -> ServiceConfig<'static> {
+        ServiceConfig {
+            image: "materialize/clusterd:latest".into(),
+            init_container_image: None,
+            args: &no_args,
+            env: vec![],
+            ports: vec![
+                mz_orchestrator::ServicePort {
+                    name: "compute".into(),
+                    port_hint: 2100,
+                },
+                mz_orchestrator::ServicePort {
+                    name: "internal-http".into(),
+                    port_hint: 6878,
+                },
+            ],
+            memory_limit: None,
+            cpu_limit: None,
+            scale: 1,
+            labels: BTreeMap::new(),
+            pod_labels: BTreeMap::new(),
+            availability_zone: None,
+            anti_affinity: None,
+            disruption_budget: None,
+            sidecars: vec![],
+            metrics_port: None,
+            metrics_path: None,
+            scratch_volume: None,
+            service_type: ServiceType::default(),
+            headless: false,
+            disk: false,
+            disk_limit: None,
+        }
+    }
+
+    /// Returns the pod template of the service's stateful set.
+    fn pod_template(objects: &ServiceObjects) -> &PodTemplateSpec {
+        &objects.stateful_set.spec.as_ref().unwrap().template
+    }
+
+    /// Returns the pod spec of the service's stateful set.
+    fn pod_spec(objects: &ServiceObjects) -> &PodSpec {
+        pod_template(objects).spec.as_ref().unwrap()
+    }
+
+    #[mz_ore::test(tokio::test)]
+    #[cfg_attr(miri, ignore)] // unsupported operation: can't call foreign functions
+    async fn test_ensure_service_dry_run() {
+        let orchestrator = orchestrator(orchestrator_config());
+
+        let service = orchestrator
+            .ensure_service(
+                "u1",
+                ServiceConfig {
+                    disruption_budget: Some(DisruptionBudget::MaxUnavailable(1)),
+                    metrics_port: Some("internal-http".into()),
+                    ..service_config()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(
+            service.addresses("compute"),
+            ["cluster-u1-0.cluster-u1.default.svc.cluster.local:2100"]
+        );
+
+        orchestrator.drop_service("u1").await.unwrap();
+    }
+
+    #[mz_ore::test(tokio::test)]
+    #[cfg_attr(miri, ignore)] // unsupported operation: can't call foreign functions
+    async fn test_delete_pod_disruption_budget() {
+        let orchestrator = orchestrator(orchestrator_config());
+        orchestrator
+            .service_budgets
+            .lock()
+            .unwrap()
+            .extend([("u1".to_string(), false), ("u2".to_string(), true)]);
+
+        // Services known not to have a budget make no requests, which would
+        // fail, as no API server is listening.
+        orchestrator
+            .delete_pod_disruption_budget("u1", "cluster-u1")
+            .await
+            .unwrap();
+
+        // Services that have, or may have, a budget attempt to delete it.
+        for id in ["u2", "u3"] {
+            let name = format!("cluster-{id}");
+            assert!(orchestrator
+                .delete_pod_disruption_budget(id, &name)
+                .await
+                .is_err());
+        }
+    }
+
+    #[mz_ore::test]
+    fn test_service_hosts() {
+        let ports = btreemap! { "compute".into() => 2100 };
+
+        let service = KubernetesService {
+            hosts: service_hosts("cluster-u1", "environment", 2, None),
+            ports: ports.clone(),
+        };
+        assert_eq!(
+            service.addresses("compute"),
+            [
+                "cluster-u1-0.cluster-u1.environment.svc.cluster.local:2100",
+                "cluster-u1-1.cluster-u1.environment.svc.cluster.local:2100",
+            ]
+        );
+
+        let service = KubernetesService {
+            hosts: service_hosts(
+                "cluster-u1",
+                "environment",
+                2,
+                Some("{name}-{index}.{namespace}.example.com"),
+            ),
+            ports,
+        };
+        assert_eq!(
+            service.addresses("compute"),
+            [
+                "cluster-u1-0.environment.example.com:2100",
+                "cluster-u1-1.environment.example.com:2100",
+            ]
+        );
+    }
+
+    #[mz_ore::test]
+    fn test_container_env() {
+        let env = main_container_env(
+            vec![
+                ("RUST_LOG".into(), "info".into()),
+                ("MZ_FOO".into(), "bar".into()),
+            ],
+            None,
+        );
+        let names: Vec<_> = env.iter().map(|var| var.name.as_str()).collect();
+        let expected = [
+            "MZ_NAMESPACE",
+            "MZ_POD_NAME",
+            "MZ_NODE_NAME",
+            "RUST_LOG",
+            "MZ_FOO",
+        ];
+        assert_eq!(names, expected);
+        assert_eq!(env[3].value.as_deref(), Some("info"));
+        assert_eq!(env[4].value.as_deref(), Some("bar"));
+        let field_ref = env[2].value_from.clone().unwrap().field_ref.unwrap();
+        assert_eq!(field_ref.field_path, "spec.nodeName");
+
+        let env = main_container_env(vec![], Some("/coverage/x.profraw".into()));
+        assert_eq!(env.last().unwrap().name, "LLVM_PROFILE_FILE");
+
+        // Service-specified variables are only set on the main container.
+        let init_names: Vec<_> = downward_api_env().into_iter().map(|var| var.name).collect();
+        assert_eq!(init_names, expected[..3]);
+    }
+
+    #[mz_ore::test]
+    fn test_pod_disruption_budget() {
+        let match_labels = btreemap! {
+            "environmentd.materialize.cloud/namespace".into() => "cluster".into(),
+            "environmentd.materialize.cloud/service-id".into() => "u1".into(),
+        };
+        let pdb = pod_disruption_budget(
+            "cluster-u1".into(),
+            match_labels.clone(),
+            DisruptionBudget::MinAvailable(2),
+        );
+        assert_eq!(pdb.metadata.name.as_deref(), Some("cluster-u1"));
+        let spec = pdb.spec.unwrap();
+        assert_eq!(spec.min_available, Some(IntOrString::Int(2)));
+        assert_eq!(spec.max_unavailable, None);
+        assert_eq!(
+            spec.selector.unwrap().match_labels,
+            Some(match_labels.clone())
+        );
+
+        let pdb = pod_disruption_budget(
+            "cluster-u1".into(),
+            match_labels,
+            DisruptionBudget::MaxUnavailable(1),
+        );
+        let spec = pdb.spec.unwrap();
+        assert_eq!(spec.min_available, None);
+        assert_eq!(spec.max_unavailable, Some(IntOrString::Int(1)));
+    }
+
+    #[mz_ore::test(tokio::test)]
+    #[cfg_attr(miri, ignore)] // unsupported operation: can't call foreign functions
+    async fn test_stateful_set_update_strategy() {
+        for (strategy, type_, partition) in [
+            (
+                KubernetesUpdateStrategy::RollingUpdate { partition: None },
+                "RollingUpdate",
+                None,
+            ),
+            (
+                KubernetesUpdateStrategy::RollingUpdate { partition: Some(2) },
+                "RollingUpdate",
+                Some(2),
+            ),
+            (KubernetesUpdateStrategy::OnDelete, "OnDelete", None),
+        ] {
+            let mut config = orchestrator_config();
+            config.update_strategy = strategy;
+            let objects = orchestrator(config)
+                .service_objects("u1", service_config())
+                .unwrap();
+            let update_strategy = objects.stateful_set.spec.unwrap().update_strategy.unwrap();
+            assert_eq!(update_strategy.type_.as_deref(), Some(type_));
+            assert_eq!(
+                update_strategy
+                    .rolling_update
+                    .and_then(|rolling_update| rolling_update.partition),
+                partition
+            );
+        }
+    }
+
+    #[mz_ore::test(tokio::test)]
+    #[cfg_attr(miri, ignore)] // unsupported operation: can't call foreign functions
+    async fn test_pod_labels() {
+        let orchestrator = orchestrator(orchestrator_config());
+        let objects = orchestrator
+            .service_objects(
+                "u1",
+                ServiceConfig {
+                    pod_labels: btreemap! { "cost-center".into() => "analytics".into() },
+                    ..service_config()
+                },
+            )
+            .unwrap();
+        let spec = objects.stateful_set.spec.as_ref().unwrap();
+        let match_labels = spec.selector.match_labels.as_ref().unwrap();
+        let pod_labels = pod_template(&objects)
+            .metadata
+            .as_ref()
+            .unwrap()
+            .labels
+            .as_ref()
+            .unwrap();
+
+        // The pod labels appear on the pods...
+        assert_eq!(pod_labels["cost-center"], "analytics");
+        // ...but not in the stateful set's selector, which is unchanged.
+        assert_eq!(
+            match_labels,
+            &btreemap! {
+                "environmentd.materialize.cloud/namespace".into() => "cluster".into(),
+                "environmentd.materialize.cloud/service-id".into() => "u1".into(),
+            }
+        );
+        // The pods are still selected by their stateful set.
+        for (key, value) in match_labels {
+            assert_eq!(&pod_labels[key], value);
+        }
+        // Pod labels are part of the pod template, so changing them replaces
+        // the pods.
+        let unlabeled = orchestrator
+            .service_objects("u1", service_config())
+            .unwrap();
+        assert_ne!(objects.pod_template_hash, unlabeled.pod_template_hash);
+
+        // Pod labels cannot override match labels.
+        let err = orchestrator
+            .service_objects(
+                "u1",
+                ServiceConfig {
+                    pod_labels: btreemap! {
+                        "environmentd.materialize.cloud/service-id".into() => "u2".into(),
+                    },
+                    ..service_config()
+                },
+            )
+            .err()
+            .unwrap();
+        assert_eq!(
+            err.to_string(),
+            "pod label environmentd.materialize.cloud/service-id conflicts with a match label"
+        );
+    }
+
+    #[mz_ore::test(tokio::test)]
+    #[cfg_attr(miri, ignore)] // unsupported operation: can't call foreign functions
+    async fn test_sidecars() {
+        let orchestrator = orchestrator(orchestrator_config());
+        let sidecar = SidecarSpec {
+            name: "proxy".into(),
+            image: "materialize/proxy:latest".into(),
+            args: vec!["--listen=0.0.0.0:6876".into()],
+            ports: vec![mz_orchestrator::ServicePort {
+                name: "proxy".into(),
+                port_hint: 6876,
+            }],
+            memory_limit: None,
+            cpu_limit: Some(CpuLimit::from_millicpus(100)),
+        };
+
+        let with_sidecar = orchestrator
+            .service_objects(
+                "u1",
+                ServiceConfig {
+                    sidecars: vec![sidecar],
+                    ..service_config()
+                },
+            )
+            .unwrap();
+        let containers = &pod_spec(&with_sidecar).containers;
+        let names: Vec<_> = containers.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["clusterd", "proxy"]);
+        let proxy = &containers[1];
+        assert_eq!(proxy.image.as_deref(), Some("materialize/proxy:latest"));
+        assert_eq!(proxy.args, Some(vec!["--listen=0.0.0.0:6876".into()]));
+        assert_eq!(proxy.image_pull_policy.as_deref(), Some("IfNotPresent"));
+        assert_eq!(proxy.ports.as_ref().unwrap()[0].container_port, 6876);
+        let resources = proxy.resources.as_ref().unwrap();
+        assert_eq!(
+            resources.limits,
+            Some(btreemap! { "cpu".into() => Quantity("100m".into()) })
+        );
+        assert_eq!(resources.requests, resources.limits);
+        // Service-specified environment variables are only set on the main
+        // container.
+        let env_names: Vec<_> = proxy
+            .env
+            .iter()
+            .flatten()
+            .map(|var| var.name.as_str())
+            .collect();
+        assert_eq!(env_names, ["MZ_NAMESPACE", "MZ_POD_NAME", "MZ_NODE_NAME"]);
+
+        // Adding a sidecar changes the pod template hash, so that existing
+        // pods are replaced.
+        let without_sidecar = orchestrator
+            .service_objects("u1", service_config())
+            .unwrap();
+        assert_eq!(pod_spec(&without_sidecar).containers.len(), 1);
+        assert_ne!(
+            with_sidecar.pod_template_hash,
+            without_sidecar.pod_template_hash
+        );
+    }
+
+    #[mz_ore::test(tokio::test)]
+    #[cfg_attr(miri, ignore)] // unsupported operation: can't call foreign functions
+    async fn test_prometheus_annotations() {
+        let orchestrator = orchestrator(orchestrator_config());
+        let annotations = |metrics_port: Option<&str>, metrics_path: Option<&str>| {
+            let objects = orchestrator.service_objects(
+                "u1",
+                ServiceConfig {
+                    metrics_port: metrics_port.map(|p| p.into()),
+                    metrics_path: metrics_path.map(|p| p.into()),
+                    ..service_config()
+                },
+            )?;
+            let annotations = pod_template(&objects)
+                .metadata
+                .as_ref()
+                .unwrap()
+                .annotations
+                .clone()
+                .unwrap();
+            Ok::<_, anyhow::Error>(annotations)
+        };
+
+        let without_metrics = annotations(None, None).unwrap();
+        assert!(!without_metrics.contains_key("prometheus.io/scrape"));
+
+        let with_metrics = annotations(Some("internal-http"), None).unwrap();
+        assert_eq!(with_metrics["prometheus.io/scrape"], "true");
+        assert_eq!(with_metrics["prometheus.io/port"], "6878");
+        assert_eq!(with_metrics["prometheus.io/path"], "/metrics");
+        // The annotations are part of the pod template hash, so that existing
+        // pods are replaced when metrics are advertised.
+        assert_ne!(
+            with_metrics[POD_TEMPLATE_HASH_ANNOTATION],
+            without_metrics[POD_TEMPLATE_HASH_ANNOTATION]
+        );
+
+        let with_path = annotations(Some("internal-http"), Some("/api/metrics")).unwrap();
+        assert_eq!(with_path["prometheus.io/path"], "/api/metrics");
+
+        let err = annotations(Some("bogus"), None).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "metrics port bogus is not a port of the service"
+        );
+    }
+
+    #[mz_ore::test(tokio::test)]
+    #[cfg_attr(miri, ignore)] // unsupported operation: can't call foreign functions
+    async fn test_dns_config() {
+        let objects = orchestrator(KubernetesOrchestratorConfig {
+            dns_policy: Some("ClusterFirstWithHostNet".into()),
+            host_aliases: vec![(
+                "10.0.0.1".into(),
+                vec!["persist.internal".into(), "blob.internal".into()],
+            )],
+            ..orchestrator_config()
+        })
+        .service_objects("u1", service_config())
+        .unwrap();
+        let spec = pod_spec(&objects);
+        assert_eq!(spec.dns_policy.as_deref(), Some("ClusterFirstWithHostNet"));
+        assert_eq!(
+            spec.host_aliases,
+            Some(vec![HostAlias {
+                ip: Some("10.0.0.1".into()),
+                hostnames: Some(vec!["persist.internal".into(), "blob.internal".into()]),
+            }])
+        );
+
+        // When unset, neither field appears on the generated pod spec, so
+        // that Kubernetes applies its defaults.
+        let objects = orchestrator(orchestrator_config())
+            .service_objects("u1", service_config())
+            .unwrap();
+        let value = serde_json::to_value(&objects.stateful_set).unwrap();
+        let spec = &value["spec"]["template"]["spec"];
+        assert!(spec.get("dnsPolicy").is_none());
+        assert!(spec.get("hostAliases").is_none());
+    }
+
+    #[mz_ore::test(tokio::test)]
+    #[cfg_attr(miri, ignore)] // unsupported operation: can't call foreign functions
+    async fn test_priority_class_name() {
+        let objects = orchestrator(KubernetesOrchestratorConfig {
+            priority_class_name: Some("materialize-critical".into()),
+            ..orchestrator_config()
+        })
+        .service_objects("u1", service_config())
+        .unwrap();
+        assert_eq!(
+            pod_spec(&objects).priority_class_name.as_deref(),
+            Some("materialize-critical")
+        );
+
+        // When unset, the pods get the cluster's default priority.
+        let objects = orchestrator(orchestrator_config())
+            .service_objects("u1", service_config())
+            .unwrap();
+        assert_eq!(pod_spec(&objects).priority_class_name, None);
+    }
+
+    #[mz_ore::test(tokio::test)]
+    #[cfg_attr(miri, ignore)] // unsupported operation: can't call foreign functions
+    async fn test_service_type() {
+        let orchestrator = orchestrator(orchestrator_config());
+        let service_spec = |service_type, headless| {
+            let objects = orchestrator.service_objects(
+                "u1",
+                ServiceConfig {
+                    service_type,
+                    headless,
+                    ..service_config()
+                },
+            )?;
+            Ok::<_, anyhow::Error>(objects.service.spec.unwrap())
+        };
+
+        let spec = service_spec(ServiceType::ClusterIp, false).unwrap();
+        assert_eq!(spec.type_.as_deref(), Some("ClusterIP"));
+        assert_eq!(spec.cluster_ip, None);
+
+        let spec = service_spec(ServiceType::ClusterIp, true).unwrap();
+        assert_eq!(spec.type_.as_deref(), Some("ClusterIP"));
+        assert_eq!(spec.cluster_ip.as_deref(), Some("None"));
+
+        let spec = service_spec(ServiceType::NodePort, false).unwrap();
+        assert_eq!(spec.type_.as_deref(), Some("NodePort"));
+        assert_eq!(spec.cluster_ip, None);
+
+        let err = service_spec(ServiceType::NodePort, true).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "headless services must have type ClusterIP, not NodePort"
+        );
+    }
+
+    #[mz_ore::test(tokio::test)]
+    #[cfg_attr(miri, ignore)] // unsupported operation: can't call foreign functions
+    async fn test_scratch_volume() {
+        let orchestrator = orchestrator(orchestrator_config());
+        let with_scratch = orchestrator
+            .service_objects(
+                "u1",
+                ServiceConfig {
+                    scratch_volume: Some(ScratchVolume {
+                        mount_path: "/tmp/scratch".into(),
+                        size_limit: Some(DiskLimit::ARBITRARY),
+                    }),
+                    ..service_config()
+                },
+            )
+            .unwrap();
+        let spec = pod_spec(&with_scratch);
+        assert_eq!(
+            spec.volumes,
+            Some(vec![Volume {
+                name: "scratch-empty-dir".into(),
+                empty_dir: Some(EmptyDirVolumeSource {
+                    size_limit: Some(Quantity("1073741824".into())),
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }])
+        );
+        // The volume is mounted into the main container only.
+        assert_eq!(
+            spec.containers[0].volume_mounts,
+            Some(vec![VolumeMount {
+                name: "scratch-empty-dir".into(),
+                mount_path: "/tmp/scratch".into(),
+                ..Default::default()
+            }])
+        );
+
+        // The volume is part of the pod template hash, so that existing pods
+        // are replaced when scratch space is added.
+        let without_scratch = orchestrator
+            .service_objects("u1", service_config())
+            .unwrap();
+        assert_eq!(pod_spec(&without_scratch).volumes, None);
+        assert_eq!(pod_spec(&without_scratch).containers[0].volume_mounts, None);
+        assert_ne!(
+            with_scratch.pod_template_hash,
+            without_scratch.pod_template_hash
+        );
+    }
+
+    #[mz_ore::test(tokio::test)]
+    #[cfg_attr(miri, ignore)] // unsupported operation: can't call foreign functions
+    async fn test_anti_affinity_topology_key() {
+        let anti_affinity = |config: KubernetesOrchestratorConfig| -> Vec<PodAffinityTerm> {
+            let objects = orchestrator(config)
+                .service_objects(
+                    "u1",
+                    ServiceConfig {
+                        anti_affinity: Some(vec![MzLabelSelector {
+                            label_name: "cluster-id".into(),
+                            logic: LabelSelectionLogic::Eq { value: "u1".into() },
+                        }]),
+                        ..service_config()
+                    },
+                )
+                .unwrap();
+            pod_spec(&objects)
+                .affinity
+                .clone()
+                .unwrap()
+                .pod_anti_affinity
+                .unwrap()
+                .required_during_scheduling_ignored_during_execution
+                .unwrap()
+        };
+
+        // Without a configured topology key, pods are spread across nodes.
+        let terms = anti_affinity(orchestrator_config());
+        assert_eq!(terms.len(), 1);
+        assert_eq!(terms[0].topology_key, "kubernetes.io/hostname");
+        assert_eq!(
+            terms[0].label_selector.as_ref().unwrap().match_expressions,
+            Some(vec![LabelSelectorRequirement {
+                key: "cluster.environmentd.materialize.cloud/cluster-id".into(),
+                operator: "In".into(),
+                values: Some(vec!["u1".into()]),
+            }])
+        );
+
+        let terms = anti_affinity(KubernetesOrchestratorConfig {
+            anti_affinity_topology_key: Some("topology.kubernetes.io/zone".into()),
+            ..orchestrator_config()
+        });
+        assert_eq!(terms.len(), 1);
+        assert_eq!(terms[0].topology_key, "topology.kubernetes.io/zone");
+
+        // Services without anti-affinity have no anti-affinity terms.
+        let objects = orchestrator(orchestrator_config())
+            .service_objects("u1", service_config())
+            .unwrap();
+        assert_eq!(
+            pod_spec(&objects)
+                .affinity
+                .as_ref()
+                .unwrap()
+                .pod_anti_affinity,
+            None
+        );
+    }
+
+    #[mz_ore::test]
+    fn test_field_manager() {
+        let mut config = orchestrator_config();
+        let params = config.apply_params();
+        assert_eq!(params.field_manager.as_deref(), Some(DEFAULT_FIELD_MANAGER));
+        assert!(params.force);
+
+        config.field_manager = Some("custom-manager".into());
+        let params = config.apply_params();
+        assert_eq!(params.field_manager.as_deref(), Some("custom-manager"));
+        assert!(params.force);
+    }
+
+    #[mz_ore::test(tokio::test)]
+    #[cfg_attr(miri, ignore)] // unsupported operation: can't call foreign functions
+    async fn test_security_context() {
+        // Nothing is configured by default.
+        assert_eq!(KubernetesPodSecurityContext::default().to_k8s(None), None);
+        assert_eq!(KubernetesContainerSecurityContext::default().to_k8s(), None);
+
+        // The fs group alone still produces a pod security context.
+        let pod = KubernetesPodSecurityContext::default().to_k8s(Some(2000));
+        assert_eq!(
+            pod,
+            Some(PodSecurityContext {
+                fs_group: Some(2000),
+                ..Default::default()
+            })
+        );
+
+        let pod = KubernetesPodSecurityContext {
+            run_as_non_root: true,
+            run_as_user: Some(999),
+            run_as_group: Some(1000),
+        }
+        .to_k8s(Some(2000))
+        .unwrap();
+        assert_eq!(pod.run_as_non_root, Some(true));
+        assert_eq!(pod.run_as_user, Some(999));
+        assert_eq!(pod.run_as_group, Some(1000));
+        assert_eq!(pod.fs_group, Some(2000));
+
+        let container = KubernetesContainerSecurityContext {
+            read_only_root_filesystem: true,
+            drop_capabilities: vec!["ALL".into()],
+        }
+        .to_k8s();
+        assert_eq!(
+            container,
+            Some(SecurityContext {
+                read_only_root_filesystem: Some(true),
+                capabilities: Some(Capabilities {
+                    add: None,
+                    drop: Some(vec!["ALL".into()]),
+                }),
+                ..Default::default()
+            })
+        );
+
+        // The container security context applies to sidecars too.
+        let sidecar = SidecarSpec {
+            name: "proxy".into(),
+            image: "materialize/proxy:latest".into(),
+            args: vec![],
+            ports: vec![],
+            memory_limit: None,
+            cpu_limit: None,
+        };
+        let containers = pod_containers(
+            Container {
+                name: "clusterd".into(),
+                security_context: container.clone(),
+                ..Default::default()
+            },
+            vec![sidecar],
+            KubernetesImagePullPolicy::IfNotPresent,
+            container.clone(),
+        );
+        assert!(containers.iter().all(|c| c.security_context == container));
+
+        // The generated pods carry the configured security contexts.
+        let objects = orchestrator(KubernetesOrchestratorConfig {
+            service_fs_group: Some(2000),
+            pod_security_context: KubernetesPodSecurityContext {
+                run_as_non_root: true,
+                run_as_user: Some(999),
+                run_as_group: Some(1000),
+            },
+            container_security_context: KubernetesContainerSecurityContext {
+                read_only_root_filesystem: true,
+                drop_capabilities: vec!["ALL".into()],
+            },
+            ..orchestrator_config()
+        })
+        .service_objects("u1", service_config())
+        .unwrap();
+        let spec = pod_spec(&objects);
+        assert_eq!(
+            spec.security_context,
+            Some(PodSecurityContext {
+                fs_group: Some(2000),
+                run_as_non_root: Some(true),
+                run_as_user: Some(999),
+                run_as_group: Some(1000),
+                ..Default::default()
+            })
+        );
+        assert!(spec
+            .containers
+            .iter()
+            .all(|c| c.security_context == container));
+
+        // When unset, no security context appears on the generated pods.
+        let objects = orchestrator(orchestrator_config())
+            .service_objects("u1", service_config())
+            .unwrap();
+        let spec = pod_spec(&objects);
+        assert_eq!(spec.security_context, None);
+        assert!(spec.containers.iter().all(|c| c.security_context.is_none()));
+    }
+
+    #[mz_ore::test(tokio::test)]
+    #[cfg_attr(miri, ignore)] // unsupported operation: returning ready events from epoll_wait is not yet implemented
+    async fn test_delete_all_secrets_ignores_missing() {
+        use mz_secrets::SecretsController;
+
+        // An API server on which `user-managed-u1` is deleted concurrently
+        // with `delete_all`, so that deleting it returns a 404.
+        let deleted = Arc::new(Mutex::new(vec![]));
+        let service = tower::service_fn({
+            let deleted = Arc::clone(&deleted);
+            move |req: http::Request<hyper::Body>| {
+                let deleted = Arc::clone(&deleted);
+                async move {
+                    let name = req.uri().path().rsplit('/').next().unwrap().to_string();
+                    let (status, body) = match *req.method() {
+                        http::Method::GET => (
+                            200,
+                            serde_json::json!({
+                                "apiVersion": "v1",
+                                "kind": "SecretList",
+                                "metadata": {"resourceVersion": "1"},
+                                "items": [
+                                    {"metadata": {"name": "user-managed-u1"}},
+                                    {"metadata": {"name": "user-managed-u2"}},
+                                    {"metadata": {"name": "unrelated"}},
+                                ],
+                            }),
+                        ),
+                        http::Method::DELETE => {
+                            deleted.lock().expect("poisoned lock").push(name.clone());
+                            if name == "user-managed-u1" {
+                                (
+                                    404,
+                                    serde_json::json!({
+                                        "apiVersion": "v1",
+                                        "kind": "Status",
+                                        "status": "Failure",
+                                        "message": "not found",
+                                        "reason": "NotFound",
+                                        "code": 404,
+                                    }),
+                                )
+                            } else {
+                                (
+                                    200,
+                                    serde_json::json!({
+                                        "apiVersion": "v1",
+                                        "kind": "Secret",
+                                        "metadata": {"name": name},
+                                    }),
+                                )
+                            }
+                        }
+                        ref method => panic!("unexpected request method {method}"),
+                    };
+                    let response = http::Response::builder()
+                        .status(status)
+                        .body(hyper::Body::from(body.to_string()))
+                        .unwrap();
+                    Ok::<_, std::convert::Infallible>(response)
+                }
+            }
+        });
+        let client = Client::new(service, "default");
+        let orchestrator = KubernetesOrchestrator {
+            client: client.clone(),
+            kubernetes_namespace: "default".into(),
+            config: orchestrator_config(),
+            secret_api: Api::default_namespaced(client.clone()),
+            vpc_endpoint_api: Api::default_namespaced(client),
+            namespaces: Mutex::new(BTreeMap::new()),
+        };
+
+        orchestrator.delete_all().await.unwrap();
+        assert_eq!(
+            *deleted.lock().expect("poisoned lock"),
+            ["user-managed-u1", "user-managed-u2"]
+        );
     }
 }
@@ -16,12 +16,12 @@ use anyhow::anyhow;
 use async_trait::async_trait;
 use k8s_openapi::api::core::v1::Secret;
 use k8s_openapi::ByteString;
-use kube::api::{DeleteParams, ListParams, ObjectMeta, Patch, PatchParams};
+use kube::api::{DeleteParams, ListParams, ObjectMeta, Patch};
 use kube::Api;
 use mz_repr::GlobalId;
 use mz_secrets::{SecretsController, SecretsReader};
 
-use crate::{util, KubernetesOrchestrator, FIELD_MANAGER};
+use crate::{util, KubernetesOrchestrator};
 
 #[async_trait]
 impl SecretsController for KubernetesOrchestrator {
@@ -37,11 +37,7 @@ impl SecretsController for KubernetesOrchestrator {
             ..Default::default()
         };
         self.secret_api
-            .patch(
-                &name,
-                &PatchParams::apply(FIELD_MANAGER).force(),
-                &Patch::Apply(secret),
-            )
+            .patch(&name, &self.config.apply_params(), &Patch::Apply(secret))
             .await?;
         Ok(())
     }
@@ -206,13 +206,15 @@ impl ProcessOrchestrator {
             scratch_directory,
         }: ProcessOrchestratorConfig,
     ) -> Result<ProcessOrchestrator, anyhow::Error> {
+        // Check the secrets directory before creating any other state, so
+        // that a misconfigured directory produces a clear error up front.
+        ensure_writable_dir(&secrets_dir)
+            .await
+            .context("validating secrets directory")?;
         let metadata_dir = env::temp_dir().join(format!("environmentd-{environment_id}"));
         fs::create_dir_all(&metadata_dir)
             .await
             .context("creating metadata directory")?;
-        fs::create_dir_all(&secrets_dir)
-            .await
-            .context("creating secrets directory")?;
         fs::set_permissions(&secrets_dir, Permissions::from_mode(0o700))
             .await
             .context("setting secrets directory permissions")?;
@@ -335,13 +337,22 @@ impl NamespacedOrchestrator for NamespacedProcessOrchestrator {
             image,
             init_container_image: _,
             args,
+            env: _,
             ports: ports_in,
             memory_limit: _,
             cpu_limit: _,
             scale,
             labels,
+            pod_labels: _,
             availability_zone: _,
             anti_affinity: _,
+            disruption_budget: _,
+            sidecars: _,
+            metrics_port: _,
+            metrics_path: _,
+            scratch_volume: _,
+            service_type: _,
+            headless: _,
             disk,
             disk_limit: _,
         }: ServiceConfig<'_>,
@@ -454,6 +465,7 @@ impl NamespacedOrchestrator for NamespacedProcessOrchestrator {
                         process_id: u64::cast_from(process_id),
                         status: process_state.status.into(),
                         time: process_state.status_time,
+                        resource_version: None,
                     });
                 }
             }
@@ -674,6 +686,21 @@ struct ServiceProcessPort {
     tcp_proxy_listener: Option<AddressedTcpListener>,
 }
 
+/// Ensures that `dir` exists and is writable, creating it if necessary.
+async fn ensure_writable_dir(dir: &Path) -> Result<(), anyhow::Error> {
+    fs::create_dir_all(dir)
+        .await
+        .with_context(|| format!("creating directory {}", dir.display()))?;
+    let probe = dir.join(".write-probe");
+    fs::write(&probe, b"")
+        .await
+        .with_context(|| format!("directory {} is not writable", dir.display()))?;
+    fs::remove_file(&probe)
+        .await
+        .with_context(|| format!("removing {}", probe.display()))?;
+    Ok(())
+}
+
 /// Supervises an existing process, if it exists.
 async fn supervise_existing_process(state_updater: &ProcessStateUpdater, pid_file: &Path) {
     let name = format!(
@@ -831,6 +858,7 @@ impl ProcessStateUpdater {
             process_id: u64::cast_from(self.i),
             status: status.into(),
             time: status_time,
+            resource_version: None,
         });
     }
 }
@@ -903,3 +931,46 @@ impl Service for ProcessService {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[mz_ore::test(tokio::test)]
+    #[cfg_attr(miri, ignore)] // unsupported operation: can't call foreign function `mkdir` on OS `linux`
+    async fn test_read_only_data_dir() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let data_dir = temp_dir.path().join("data");
+        std::fs::create_dir(&data_dir).unwrap();
+        std::fs::set_permissions(&data_dir, Permissions::from_mode(0o555)).unwrap();
+        if std::fs::write(data_dir.join("probe"), b"").is_ok() {
+            info!("file permissions are not enforced (running as root?): skipping test");
+            return;
+        }
+        let secrets_dir = data_dir.join("secrets");
+
+        let environment_id = format!("test-read-only-{}", std::process::id());
+        let err = ProcessOrchestrator::new(ProcessOrchestratorConfig {
+            image_dir: temp_dir.path().into(),
+            suppress_output: true,
+            environment_id: environment_id.clone(),
+            secrets_dir: secrets_dir.clone(),
+            command_wrapper: vec![],
+            propagate_crashes: false,
+            tcp_proxy: None,
+            scratch_directory: temp_dir.path().join("scratch"),
+        })
+        .await
+        .err()
+        .expect("orchestrator creation should fail");
+        let err = format!("{:#}", err);
+        let expected = format!("creating directory {}", secrets_dir.display());
+        assert!(err.contains(&expected), "unexpected error: {err}");
+
+        // No state is left behind.
+        assert_eq!(std::fs::read_dir(&data_dir).unwrap().count(), 0);
+        assert!(!env::temp_dir()
+            .join(format!("environmentd-{environment_id}"))
+            .exists());
+    }
+}
@@ -9,6 +9,7 @@
 
 //! Management of user secrets via the local file system.
 
+use std::io;
 use std::path::PathBuf;
 use std::sync::Arc;
 
@@ -37,8 +38,12 @@ impl SecretsController for ProcessOrchestrator {
     }
 
     async fn delete(&self, id: GlobalId) -> Result<(), anyhow::Error> {
-        fs::remove_file(self.secrets_dir.join(id.to_string())).await?;
-        Ok(())
+        match fs::remove_file(self.secrets_dir.join(id.to_string())).await {
+            Ok(()) => Ok(()),
+            // Secret is already deleted.
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
     }
 
     async fn list(&self) -> Result<Vec<GlobalId>, anyhow::Error> {
@@ -79,3 +84,39 @@ impl SecretsReader for ProcessSecretsReader {
         Ok(contents)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+
+    use super::*;
+
+    #[mz_ore::test(tokio::test)]
+    #[cfg_attr(miri, ignore)] // unsupported operation: can't call foreign function `open` on OS `linux`
+    async fn test_delete_missing_secret() {
+        let temp_dir = tempfile::tempdir().unwrap();
+        let orchestrator = ProcessOrchestrator {
+            image_dir: temp_dir.path().into(),
+            suppress_output: true,
+            namespaces: Mutex::new(BTreeMap::new()),
+            metadata_dir: temp_dir.path().into(),
+            secrets_dir: temp_dir.path().into(),
+            command_wrapper: vec![],
+            propagate_crashes: false,
+            tcp_proxy: None,
+            scratch_directory: temp_dir.path().into(),
+        };
+        let ids = [GlobalId::User(1), GlobalId::User(2)];
+        for id in ids {
+            orchestrator.ensure(id, b"contents").await.unwrap();
+        }
+
+        // Deleting a secret that was already deleted by another party
+        // succeeds, as does deleting all secrets afterwards.
+        std::fs::remove_file(temp_dir.path().join(ids[0].to_string())).unwrap();
+        orchestrator.delete(ids[0]).await.unwrap();
+        orchestrator.delete_all().await.unwrap();
+        assert!(orchestrator.list().await.unwrap().is_empty());
+    }
+}
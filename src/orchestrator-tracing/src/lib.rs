@@ -383,6 +383,15 @@ impl NamespacedOrchestrator for NamespacedTracingOrchestrator {
         self.inner.fetch_service_metrics(id).await
     }
 
+    async fn fetch_service_logs(
+        &self,
+        id: &str,
+        process: usize,
+        lines: Option<usize>,
+    ) -> Result<String, anyhow::Error> {
+        self.inner.fetch_service_logs(id, process, lines).await
+    }
+
     async fn ensure_service(
         &self,
         id: &str,
@@ -475,6 +484,13 @@ impl NamespacedOrchestrator for NamespacedTracingOrchestrator {
     fn watch_services(&self) -> BoxStream<'static, Result<ServiceEvent, anyhow::Error>> {
         self.inner.watch_services()
     }
+
+    fn watch_services_from(
+        &self,
+        resource_version: Option<String>,
+    ) -> BoxStream<'static, Result<ServiceEvent, anyhow::Error>> {
+        self.inner.watch_services_from(resource_version)
+    }
 }
 
 /// Specifies the format of a stderr log message.
@@ -138,6 +138,25 @@ pub trait NamespacedOrchestrator: fmt::Debug + Send + Sync {
     /// Watch for status changes of all known services.
     fn watch_services(&self) -> BoxStream<'static, Result<ServiceEvent, anyhow::Error>>;
 
+    /// Like [`NamespacedOrchestrator::watch_services`], but resumes a previous
+    /// watch.
+    ///
+    /// `resource_version` should be the last [`ServiceEvent::resource_version`]
+    /// that was `Some` among the events observed by the previous watch. Events that were
+    /// already emitted at or before that version are not emitted again. If
+    /// `resource_version` is `None`, the watch begins with the current status
+    /// of all known services.
+    ///
+    /// Orchestrator backends that cannot resume a watch ignore
+    /// `resource_version` and behave like `watch_services`.
+    fn watch_services_from(
+        &self,
+        resource_version: Option<String>,
+    ) -> BoxStream<'static, Result<ServiceEvent, anyhow::Error>> {
+        let _ = resource_version;
+        self.watch_services()
+    }
+
     /// Gets resource usage metrics for all processes associated with a service.
     ///
     /// Returns `Err` if the entire process failed. Returns `Ok(v)` otherwise,
@@ -148,6 +167,23 @@ pub trait NamespacedOrchestrator: fmt::Debug + Send + Sync {
         &self,
         id: &str,
     ) -> Result<Vec<ServiceProcessMetrics>, anyhow::Error>;
+
+    /// Fetches the logs of the `process`th process of the identified
+    /// service, for debugging.
+    ///
+    /// If `lines` is `Some(n)`, only the last `n` lines of the logs are
+    /// returned.
+    ///
+    /// Orchestrator backends that cannot fetch logs return an error.
+    async fn fetch_service_logs(
+        &self,
+        id: &str,
+        process: usize,
+        lines: Option<usize>,
+    ) -> Result<String, anyhow::Error> {
+        let _ = (id, process, lines);
+        anyhow::bail!("fetching service logs is not supported by this orchestrator")
+    }
 }
 
 /// An event describing a status change of an orchestrated service.
@@ -157,6 +193,15 @@ pub struct ServiceEvent {
     pub process_id: u64,
     pub status: ServiceStatus,
     pub time: DateTime<Utc>,
+    /// An opaque token identifying the position of this event in the
+    /// backend's event history, suitable for passing to
+    /// [`NamespacedOrchestrator::watch_services_from`].
+    ///
+    /// `None` if the orchestrator backend cannot resume a watch, or if
+    /// resuming from this event would skip events that have yet to be
+    /// emitted. Callers that track their position should keep the last
+    /// `Some` version they observed.
+    pub resource_version: Option<String>,
 }
 
 /// Why the service is not ready, if known
@@ -252,6 +297,10 @@ pub struct ServiceConfig<'a> {
     /// given the assigned listen addresses for each named port.
     #[derivative(Debug = "ignore")]
     pub args: &'a (dyn Fn(&BTreeMap<String, String>) -> Vec<String> + Send + Sync),
+    /// Environment variables to set for each process of the service.
+    ///
+    /// The orchestrator backend may or may not actually set the variables.
+    pub env: Vec<(String, String)>,
     /// Ports to expose.
     pub ports: Vec<ServicePort>,
     /// An optional limit on the memory that the service can use.
@@ -265,6 +314,18 @@ pub struct ServiceConfig<'a> {
     ///
     /// The orchestrator backend may apply a prefix to the key if appropriate.
     pub labels: BTreeMap<String, String>,
+    /// Arbitrary key–value pairs to attach to each process of the service,
+    /// e.g., cost-allocation tags.
+    ///
+    /// Unlike `labels`, these are applied verbatim and only to the processes
+    /// themselves. They are never used to select the service's processes, so
+    /// they may be changed after the service is created. Note, however, that
+    /// they are part of each process's configuration: in the Kubernetes
+    /// backend, changing them changes the pod template hash, and so replaces
+    /// every pod of the service.
+    ///
+    /// The orchestrator backend may or may not actually apply these labels.
+    pub pod_labels: BTreeMap<String, String>,
     /// The availability zone the service should be run in. If no availability
     /// zone is specified, the orchestrator is free to choose one.
     pub availability_zone: Option<String>,
@@ -274,6 +335,46 @@ pub struct ServiceConfig<'a> {
     ///
     /// The orchestrator backend may or may not actually implement anti-affinity functionality.
     pub anti_affinity: Option<Vec<LabelSelector>>,
+    /// An optional limit on how many processes of the service may be
+    /// voluntarily disrupted at once, e.g., by the eviction of pods from a
+    /// node that is being drained.
+    ///
+    /// The orchestrator backend may or may not actually implement disruption
+    /// budgets.
+    pub disruption_budget: Option<DisruptionBudget>,
+    /// Auxiliary containers to run alongside each process of the service,
+    /// e.g., a logging or proxy sidecar.
+    ///
+    /// The orchestrator backend may or may not actually run sidecars.
+    pub sidecars: Vec<SidecarSpec>,
+    /// The name of the port, among `ports`, on which the service exposes
+    /// Prometheus metrics, if any.
+    ///
+    /// The orchestrator backend may use this to advertise the service to
+    /// Prometheus for scraping.
+    pub metrics_port: Option<String>,
+    /// The HTTP path at which the service exposes Prometheus metrics.
+    ///
+    /// Defaults to `/metrics`. Ignored if `metrics_port` is not set.
+    pub metrics_path: Option<String>,
+    /// Local scratch space to mount into each process of the service, which
+    /// does not outlive the process.
+    ///
+    /// The orchestrator backend may or may not actually provide scratch
+    /// volumes.
+    pub scratch_volume: Option<ScratchVolume>,
+    /// How the service is exposed on the network.
+    ///
+    /// The orchestrator backend may or may not distinguish between service
+    /// types.
+    pub service_type: ServiceType,
+    /// Whether the service is headless, i.e., whether it is addressable only
+    /// through its individual processes rather than through a single virtual
+    /// address. Headless services must have type [`ServiceType::ClusterIp`].
+    ///
+    /// The orchestrator backend may or may not actually create headless
+    /// services.
+    pub headless: bool,
 
     /// Whether scratch disk space should be allocated for the service.
     pub disk: bool,
@@ -281,6 +382,53 @@ pub struct ServiceConfig<'a> {
     pub disk_limit: Option<DiskLimit>,
 }
 
+/// Describes how many processes of a service must remain available during
+/// voluntary disruptions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DisruptionBudget {
+    /// At least this many processes must remain available.
+    MinAvailable(u16),
+    /// At most this many processes may be unavailable.
+    MaxUnavailable(u16),
+}
+
+/// Describes how a service is exposed on the network.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ServiceType {
+    /// The service is reachable only from within the cluster.
+    #[default]
+    ClusterIp,
+    /// The service is additionally reachable on a port of every node in the
+    /// cluster.
+    NodePort,
+}
+
+/// Describes an auxiliary container that runs alongside a service.
+#[derive(Debug, Clone)]
+pub struct SidecarSpec {
+    /// The name of the sidecar, which must be unique within the service.
+    pub name: String,
+    /// The container image to run.
+    pub image: String,
+    /// The arguments to pass to the sidecar.
+    pub args: Vec<String>,
+    /// Ports to expose from the sidecar.
+    pub ports: Vec<ServicePort>,
+    /// An optional limit on the memory that the sidecar can use.
+    pub memory_limit: Option<MemoryLimit>,
+    /// An optional limit on the CPU that the sidecar can use.
+    pub cpu_limit: Option<CpuLimit>,
+}
+
+/// Describes an ephemeral scratch volume mounted into a service.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScratchVolume {
+    /// The path at which to mount the volume.
+    pub mount_path: String,
+    /// An optional limit on the size of the volume.
+    pub size_limit: Option<DiskLimit>,
+}
+
 /// A named port associated with a service.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ServicePort {
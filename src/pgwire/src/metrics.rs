@@ -14,6 +14,7 @@ use mz_ore::metrics::{IntCounter, MetricsRegistry};
 #[derive(Clone, Debug)]
 pub struct MetricsConfig {
     connection_status: IntCounterVec,
+    slow_queries: IntCounterVec,
 }
 
 impl MetricsConfig {
@@ -24,6 +25,11 @@ impl MetricsConfig {
                 help: "Count of completed network connections, by status",
                 var_labels: ["source", "status"],
             }),
+            slow_queries: registry.register(metric! {
+                name: "mz_slow_queries_total",
+                help: "Count of statements that took longer than the slow query threshold",
+                var_labels: ["source"],
+            }),
         }
     }
 }
@@ -42,6 +48,7 @@ impl Metrics {
         // always emitted as time series
         self_.connection_status(false);
         self_.connection_status(true);
+        self_.slow_queries();
 
         self_
     }
@@ -52,6 +59,12 @@ impl Metrics {
             .with_label_values(&[self.source_label(), Self::status_label(is_ok)])
     }
 
+    pub fn slow_queries(&self) -> IntCounter {
+        self.inner
+            .slow_queries
+            .with_label_values(&[self.source_label()])
+    }
+
     fn status_label(is_ok: bool) -> &'static str {
         if is_ok {
             "success"
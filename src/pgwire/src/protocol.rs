@@ -11,7 +11,7 @@ use std::collections::BTreeMap;
 use std::convert::TryFrom;
 use std::future::Future;
 use std::sync::{Arc, Mutex};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use std::{cmp, iter, mem};
 
 use byteorder::{ByteOrder, NetworkEndian};
@@ -26,6 +26,7 @@ use mz_adapter::{
 };
 use mz_frontegg_auth::Authentication as FronteggAuthentication;
 use mz_ore::cast::CastFrom;
+use mz_ore::metrics::IntCounter;
 use mz_ore::netio::AsyncReady;
 use mz_ore::str::StrExt;
 use mz_pgcopy::CopyFormatParams;
@@ -87,6 +88,15 @@ pub struct RunParams<'a, A> {
     pub internal: bool,
     /// Global connection limit and count
     pub active_connection_count: Arc<Mutex<ConnectionCounter>>,
+    /// How long the connection may go without receiving a message from the
+    /// client before it is closed. If `None`, the connection never times out.
+    pub idle_timeout: Option<Duration>,
+    /// How long a statement may take to execute before a warning is logged
+    /// about it. If `None`, slow statements are not logged.
+    pub slow_query_threshold: Option<Duration>,
+    /// The counter to increment for each statement that exceeds
+    /// `slow_query_threshold`.
+    pub slow_queries: IntCounter,
 }
 
 /// Runs a pgwire connection to completion.
@@ -109,6 +119,9 @@ pub async fn run<'a, A>(
         frontegg,
         internal,
         active_connection_count,
+        idle_timeout,
+        slow_query_threshold,
+        slow_queries,
     }: RunParams<'a, A>,
 ) -> Result<(), io::Error>
 where
@@ -321,6 +334,9 @@ where
     let machine = StateMachine {
         conn,
         adapter_client,
+        idle_timeout,
+        slow_query_threshold,
+        slow_queries,
     };
 
     select! {
@@ -425,6 +441,9 @@ enum State {
 struct StateMachine<'a, A> {
     conn: &'a mut FramedConn<A>,
     adapter_client: mz_adapter::SessionClient,
+    idle_timeout: Option<Duration>,
+    slow_query_threshold: Option<Duration>,
+    slow_queries: IntCounter,
 }
 
 impl<'a, A> StateMachine<'a, A>
@@ -453,6 +472,14 @@ where
     }
 
     async fn advance_ready(&mut self) -> Result<State, io::Error> {
+        let idle_timeout = self.idle_timeout;
+        let idle = async move {
+            match idle_timeout {
+                Some(idle_timeout) => time::sleep(idle_timeout).await,
+                None => pending().await,
+            }
+        };
+
         // Handle timeouts first so we don't execute any statements when there's a pending timeout.
         let message = select! {
             biased;
@@ -467,6 +494,15 @@ where
                 let _ = self.conn.recv().await?;
                 return self.error(error_response).await;
             },
+            // Unlike the timeouts above, the client is not expected to send
+            // any further requests, so we report the timeout immediately.
+            _ = idle => {
+                let error_response = ErrorResponse::fatal(
+                    SqlState::IDLE_SESSION_TIMEOUT,
+                    "terminating connection due to idle-session timeout",
+                );
+                return self.error(error_response).await;
+            },
             // `recv()` is cancel-safe as per it's docs.
             message = self.conn.recv() => message?,
         };
@@ -623,17 +659,20 @@ where
         {
             Ok((response, execute_started)) => {
                 self.send_pending_notices().await?;
-                self.send_execute_response(
-                    response,
-                    stmt_desc.relation_desc,
-                    EMPTY_PORTAL.to_string(),
-                    ExecuteCount::All,
-                    portal_exec_message,
-                    None,
-                    ExecuteTimeout::None,
-                    execute_started,
-                )
-                .await
+                let result = self
+                    .send_execute_response(
+                        response,
+                        stmt_desc.relation_desc,
+                        EMPTY_PORTAL.to_string(),
+                        ExecuteCount::All,
+                        portal_exec_message,
+                        None,
+                        ExecuteTimeout::None,
+                        execute_started,
+                    )
+                    .await;
+                self.log_slow_query(EMPTY_PORTAL, execute_started);
+                result
             }
             Err(e) => {
                 self.send_pending_notices().await?;
@@ -988,17 +1027,20 @@ where
                     {
                         Ok((response, execute_started)) => {
                             self.send_pending_notices().await?;
-                            self.send_execute_response(
-                                response,
-                                row_desc,
-                                portal_name,
-                                max_rows,
-                                get_response,
-                                fetch_portal_name,
-                                timeout,
-                                execute_started,
-                            )
-                            .await
+                            let result = self
+                                .send_execute_response(
+                                    response,
+                                    row_desc,
+                                    portal_name.clone(),
+                                    max_rows,
+                                    get_response,
+                                    fetch_portal_name,
+                                    timeout,
+                                    execute_started,
+                                )
+                                .await;
+                            self.log_slow_query(&portal_name, execute_started);
+                            result
                         }
                         Err(e) => {
                             self.send_pending_notices().await?;
@@ -1121,6 +1163,25 @@ where
         portal.state = PortalState::Completed(None);
     }
 
+    /// Logs a warning about the statement bound to the named portal if it
+    /// has been executing for longer than the slow query threshold.
+    fn log_slow_query(&mut self, portal_name: &str, execute_started: Instant) {
+        let duration = execute_started.elapsed();
+        match self.slow_query_threshold {
+            Some(threshold) if duration > threshold => (),
+            _ => return,
+        }
+        self.slow_queries.inc();
+        let session = self.adapter_client.session();
+        warn_slow_query(
+            session
+                .get_portal_unverified(portal_name)
+                .and_then(|portal| portal.stmt.as_ref()),
+            duration,
+            &session.user().name,
+        );
+    }
+
     async fn fetch(
         &mut self,
         name: String,
@@ -2038,6 +2099,17 @@ fn is_txn_exit_stmt(stmt: Option<&Statement<Raw>>) -> bool {
     }
 }
 
+/// Logs a warning about a slow statement.
+///
+/// Literal values are redacted from the logged SQL text, as they may contain
+/// sensitive values, like the contents of a secret.
+fn warn_slow_query(stmt: Option<&Statement<Raw>>, duration: Duration, user: &str) {
+    let sql = stmt
+        .map(|stmt| stmt.to_ast_string_redacted())
+        .unwrap_or_default();
+    warn!(%sql, ?duration, user, "slow query");
+}
+
 #[derive(Debug)]
 enum FetchResult {
     Rows(Option<Vec<Row>>),
@@ -2050,6 +2122,52 @@ enum FetchResult {
 mod test {
     use super::*;
 
+    #[mz_ore::test]
+    fn test_warn_slow_query() {
+        use tracing::field::{Field, Visit};
+        use tracing_subscriber::layer::{Context, SubscriberExt};
+        use tracing_subscriber::Layer;
+
+        /// Records the fields of every event as strings.
+        #[derive(Clone, Default)]
+        struct FieldRecorder(Arc<Mutex<Vec<BTreeMap<String, String>>>>);
+
+        struct FieldVisitor(BTreeMap<String, String>);
+
+        impl Visit for FieldVisitor {
+            fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+                self.0.insert(field.name().into(), format!("{value:?}"));
+            }
+
+            fn record_str(&mut self, field: &Field, value: &str) {
+                self.0.insert(field.name().into(), value.into());
+            }
+        }
+
+        impl<S: tracing::Subscriber> Layer<S> for FieldRecorder {
+            fn on_event(&self, event: &tracing::Event<'_>, _: Context<'_, S>) {
+                let mut visitor = FieldVisitor(BTreeMap::new());
+                event.record(&mut visitor);
+                self.0.lock().expect("poisoned lock").push(visitor.0);
+            }
+        }
+
+        let recorder = FieldRecorder::default();
+        let subscriber = tracing_subscriber::registry().with(recorder.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            let stmts = mz_sql::parse::parse("CREATE SECRET s AS 'hunter2'").unwrap();
+            warn_slow_query(stmts.first(), Duration::from_secs(2), "alice");
+        });
+
+        let events = recorder.0.lock().expect("poisoned lock");
+        assert_eq!(events.len(), 1);
+        let fields = &events[0];
+        assert_eq!(fields["message"], "slow query");
+        assert_eq!(fields["sql"], "CREATE SECRET s AS '<REDACTED>'");
+        assert_eq!(fields["duration"], "2s");
+        assert_eq!(fields["user"], "alice");
+    }
+
     #[mz_ore::test]
     fn test_parse_options() {
         struct TestCase {
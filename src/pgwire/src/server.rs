@@ -12,6 +12,7 @@ use std::future::Future;
 use std::pin::Pin;
 use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
+use std::time::Duration;
 
 use async_trait::async_trait;
 use mz_frontegg_auth::Authentication as FronteggAuthentication;
@@ -50,6 +51,16 @@ pub struct Config {
     pub internal: bool,
     /// Global connection limit and count
     pub active_connection_count: Arc<Mutex<ConnectionCounter>>,
+    /// How long a connection may go without receiving a message from its
+    /// client before the server closes it.
+    ///
+    /// If not present, connections never time out.
+    pub idle_timeout: Option<Duration>,
+    /// How long a statement may take to execute before the server logs a
+    /// warning about it.
+    ///
+    /// If not present, slow statements are not logged.
+    pub slow_query_threshold: Option<Duration>,
 }
 
 /// Configures a server's TLS encryption and authentication.
@@ -78,6 +89,8 @@ pub struct Server {
     metrics: Metrics,
     internal: bool,
     active_connection_count: Arc<Mutex<ConnectionCounter>>,
+    idle_timeout: Option<Duration>,
+    slow_query_threshold: Option<Duration>,
 }
 
 impl Server {
@@ -90,6 +103,8 @@ impl Server {
             metrics: Metrics::new(config.metrics, config.internal),
             internal: config.internal,
             active_connection_count: config.active_connection_count,
+            idle_timeout: config.idle_timeout,
+            slow_query_threshold: config.slow_query_threshold,
         }
     }
 
@@ -107,6 +122,9 @@ impl Server {
         let internal = self.internal;
         let metrics = self.metrics.clone();
         let active_connection_count = Arc::clone(&self.active_connection_count);
+        let idle_timeout = self.idle_timeout;
+        let slow_query_threshold = self.slow_query_threshold;
+        let slow_queries = metrics.slow_queries();
         async move {
             let result = (|| {
                 async move {
@@ -137,6 +155,9 @@ impl Server {
                                     frontegg: frontegg.as_ref(),
                                     internal,
                                     active_connection_count,
+                                    idle_timeout,
+                                    slow_query_threshold,
+                                    slow_queries,
                                 })
                                 .await?;
                                 conn.flush().await?;
@@ -72,11 +72,16 @@
 #![warn(clippy::disallowed_types)]
 #![warn(clippy::from_over_into)]
 // END LINT CONFIG
+use std::future::Future;
+use std::{io, iter, mem};
 
-use std::{io, iter};
-
+use anyhow::bail;
+use async_trait::async_trait;
 use aws_sdk_s3::operation::create_bucket::CreateBucketError;
-use aws_sdk_s3::types::{BucketLocationConstraint, CreateBucketConfiguration};
+use aws_sdk_s3::types::{
+    BucketLocationConstraint, CreateBucketConfiguration, Delete, ObjectIdentifier,
+};
+use aws_sdk_s3::Client;
 use clap::Parser;
 use futures::stream::{self, StreamExt, TryStreamExt};
 use mz_ore::cast::CastFrom;
@@ -89,20 +94,21 @@ use tracing_subscriber::filter::EnvFilter;
 #[derive(Parser)]
 struct Args {
     /// How large to make each line (record) in Bytes
-    #[clap(short = 'l', long)]
-    line_bytes: usize,
+    #[clap(short = 'l', long, required_unless_present = "cleanup")]
+    line_bytes: Option<usize>,
 
     /// How large to make each object, e.g. `1 KiB`
     #[clap(
         short = 's',
         long,
-        parse(try_from_str = parse_object_size)
+        parse(try_from_str = parse_object_size),
+        required_unless_present = "cleanup"
     )]
-    object_size: usize,
+    object_size: Option<usize>,
 
     /// How many objects to create
-    #[clap(short = 'c', long)]
-    object_count: usize,
+    #[clap(short = 'c', long, required_unless_present = "cleanup")]
+    object_count: Option<usize>,
 
     /// All objects will be inserted into this prefix
     #[clap(short = 'p', long)]
@@ -116,10 +122,20 @@ struct Args {
     #[clap(short = 'r', long, default_value = "us-east-1")]
     region: String,
 
-    /// Number of copy operations to run concurrently
+    /// Number of copy (or, with `--cleanup`, delete) operations to run
+    /// concurrently
     #[clap(long, default_value = "50")]
     concurrent_copies: usize,
 
+    /// Report what would be generated, without creating the bucket or
+    /// uploading any objects
+    #[clap(long)]
+    dry_run: bool,
+
+    /// Delete all objects under the key prefix instead of generating data
+    #[clap(long, conflicts_with = "dry-run")]
+    cleanup: bool,
+
     /// Which log messages to emit.
     ///
     /// See environmentd's `--log-filter` option for details.
@@ -127,6 +143,146 @@ struct Args {
     log_filter: EnvFilter,
 }
 
+/// The S3 operations required to generate data.
+#[async_trait]
+trait ObjectStore: Sync {
+    /// Creates `bucket`, succeeding if it already exists and is owned by us.
+    async fn create_bucket(&self, bucket: &str) -> anyhow::Result<()>;
+    /// Uploads `body` to `key` in `bucket`.
+    async fn put_object(&self, bucket: &str, key: &str, body: Vec<u8>) -> anyhow::Result<()>;
+    /// Copies the object at `source_key` in `bucket` to `key` in `bucket`.
+    async fn copy_object(&self, bucket: &str, source_key: &str, key: &str) -> anyhow::Result<()>;
+    /// Lists the keys of all objects in `bucket` whose key starts with
+    /// `prefix`.
+    async fn list_objects(&self, bucket: &str, prefix: &str) -> anyhow::Result<Vec<String>>;
+    /// Deletes the objects with the given `keys` from `bucket`.
+    ///
+    /// At most [`DELETE_BATCH_SIZE`] keys may be provided.
+    async fn delete_objects(&self, bucket: &str, keys: Vec<String>) -> anyhow::Result<()>;
+}
+
+/// The maximum number of keys that S3 permits in a single `DeleteObjects`
+/// request.
+const DELETE_BATCH_SIZE: usize = 1000;
+
+struct S3Store {
+    client: Client,
+    region: Option<String>,
+}
+
+impl S3Store {
+    async fn connect() -> S3Store {
+        let config = aws_config::load_from_env().await;
+        S3Store {
+            client: mz_aws_s3_util::new_client(&config),
+            region: config.region().map(|r| r.as_ref().to_owned()),
+        }
+    }
+}
+
+#[async_trait]
+impl ObjectStore for S3Store {
+    async fn create_bucket(&self, bucket: &str) -> anyhow::Result<()> {
+        let bucket_config = match self.region.as_deref() {
+            // us-east-1 is special and is not accepted as a location constraint.
+            None | Some("us-east-1") => None,
+            Some(r) => Some(
+                CreateBucketConfiguration::builder()
+                    .location_constraint(BucketLocationConstraint::from(r))
+                    .build(),
+            ),
+        };
+        self.client
+            .create_bucket()
+            .bucket(bucket)
+            .set_create_bucket_configuration(bucket_config)
+            .send()
+            .await
+            .map(|_| info!("created s3 bucket {}", bucket))
+            .or_else(|e| match e.into_service_error() {
+                CreateBucketError::BucketAlreadyOwnedByYou(_) => {
+                    event!(Level::INFO, bucket = %bucket, "reusing existing bucket");
+                    Ok(())
+                }
+                e => Err(e),
+            })?;
+        Ok(())
+    }
+
+    async fn put_object(&self, bucket: &str, key: &str, body: Vec<u8>) -> anyhow::Result<()> {
+        self.client
+            .put_object()
+            .bucket(bucket)
+            .key(key)
+            .body(body.into())
+            .send()
+            .await?;
+        Ok(())
+    }
+
+    async fn copy_object(&self, bucket: &str, source_key: &str, key: &str) -> anyhow::Result<()> {
+        self.client
+            .copy_object()
+            .bucket(bucket)
+            .copy_source(format!("{}/{}", bucket, source_key))
+            .key(key)
+            .send()
+            .await?;
+        Ok(())
+    }
+
+    async fn list_objects(&self, bucket: &str, prefix: &str) -> anyhow::Result<Vec<String>> {
+        let mut keys = vec![];
+        let mut continuation_token = None;
+        loop {
+            let resp = self
+                .client
+                .list_objects_v2()
+                .bucket(bucket)
+                .prefix(prefix)
+                .set_continuation_token(continuation_token)
+                .send()
+                .await?;
+            if let Some(contents) = resp.contents {
+                keys.extend(contents.into_iter().filter_map(|object| object.key));
+            }
+            if resp.next_continuation_token.is_some() {
+                continuation_token = resp.next_continuation_token;
+            } else {
+                break;
+            }
+        }
+        Ok(keys)
+    }
+
+    async fn delete_objects(&self, bucket: &str, keys: Vec<String>) -> anyhow::Result<()> {
+        let objects = keys
+            .into_iter()
+            .map(|key| ObjectIdentifier::builder().key(key).build())
+            .collect();
+        let resp = self
+            .client
+            .delete_objects()
+            .bucket(bucket)
+            .delete(
+                Delete::builder()
+                    .set_objects(Some(objects))
+                    .quiet(true)
+                    .build(),
+            )
+            .send()
+            .await?;
+        if let Some(error) = resp.errors.unwrap_or_default().into_iter().next() {
+            bail!(
+                "deleting {}: {}",
+                error.key.unwrap_or_default(),
+                error.message.unwrap_or_default()
+            );
+        }
+        Ok(())
+    }
+}
+
 #[tokio::main]
 async fn main() {
     if let Err(e) = run().await {
@@ -136,85 +292,93 @@ async fn main() {
 }
 
 async fn run() -> anyhow::Result<()> {
-    let args: Args = cli::parse_args(CliConfig::default());
+    let mut args: Args = cli::parse_args(CliConfig::default());
 
     tracing_subscriber::fmt()
-        .with_env_filter(args.log_filter)
+        .with_env_filter(mem::take(&mut args.log_filter))
         .with_writer(io::stderr)
         .init();
 
-    info!(
-        "starting up to create {} of data across {} objects in {}/{}",
-        bytefmt::format(u64::cast_from(args.object_size * args.object_count)),
-        args.object_count,
-        args.bucket,
-        args.key_prefix
-    );
+    execute(&args, S3Store::connect).await
+}
+
+/// Generates the data described by `args` in the store returned by `connect`,
+/// or, with `--cleanup`, deletes it.
+///
+/// In dry-run mode, `connect` is never called.
+async fn execute<S, F, Fut>(args: &Args, connect: F) -> anyhow::Result<()>
+where
+    S: ObjectStore,
+    F: FnOnce() -> Fut,
+    Fut: Future<Output = S>,
+{
+    if args.cleanup {
+        return cleanup(args, &connect().await).await;
+    }
+
+    // Clap requires these arguments unless `--cleanup` is specified.
+    let line_bytes = args.line_bytes.expect("required argument");
+    let object_size = args.object_size.expect("required argument");
+    let object_count = args.object_count.expect("required argument");
 
     let line = iter::repeat('A')
-        .take(args.line_bytes)
+        .take(line_bytes)
         .chain(iter::once('\n'))
         .collect::<String>();
-    let mut object_size = 0;
+    let mut generated_size = 0;
     let line_size = line.len();
     let object = iter::repeat(line)
         .take_while(|_| {
-            object_size += line_size;
-            object_size < args.object_size
+            generated_size += line_size;
+            generated_size < object_size
         })
         .collect::<String>();
 
-    let config = aws_config::load_from_env().await;
-    let client = mz_aws_s3_util::new_client(&config);
+    let total_size = bytefmt::format(u64::cast_from(object.len() * object_count));
+    let summary = format!(
+        "{} of data across {} objects in {}/{}",
+        total_size, object_count, args.bucket, args.key_prefix
+    );
+
+    if args.dry_run {
+        println!("dry run: would create {summary}");
+        println!("bucket: {}", args.bucket);
+        println!("key prefix: {}", args.key_prefix);
+        println!(
+            "object size: {}",
+            bytefmt::format(u64::cast_from(object.len()))
+        );
+        println!("object count: {}", object_count);
+        println!("total size: {total_size}");
+        return Ok(());
+    }
+
+    info!("starting up to create {summary}");
+
+    let store = connect().await;
 
     let first_object_key = format!("{}{:>05}", args.key_prefix, 0);
 
-    let progressbar = indicatif::ProgressBar::new(u64::cast_from(args.object_count));
-
-    let bucket_config = match config.region().map(|r| r.as_ref()) {
-        // us-east-1 is special and is not accepted as a location constraint.
-        None | Some("us-east-1") => None,
-        Some(r) => Some(
-            CreateBucketConfiguration::builder()
-                .location_constraint(BucketLocationConstraint::from(r))
-                .build(),
-        ),
-    };
-    client
-        .create_bucket()
-        .bucket(&args.bucket)
-        .set_create_bucket_configuration(bucket_config)
-        .send()
-        .await
-        .map(|_| info!("created s3 bucket {}", args.bucket))
-        .or_else(|e| match e.into_service_error() {
-            CreateBucketError::BucketAlreadyOwnedByYou(_) => {
-                event!(Level::INFO, bucket = %args.bucket, "reusing existing bucket");
-                Ok(())
-            }
-            e => Err(e),
-        })?;
+    let progressbar = indicatif::ProgressBar::new(u64::cast_from(object_count));
+
+    store.create_bucket(&args.bucket).await?;
 
     let mut total_created = 0;
-    client
-        .put_object()
-        .bucket(&args.bucket)
-        .key(&first_object_key)
-        .body(object.into_bytes().into())
-        .send()
+    store
+        .put_object(&args.bucket, &first_object_key, object.into_bytes())
         .await?;
     total_created += 1;
     progressbar.inc(1);
 
-    let copy_source = format!("{}/{}", args.bucket, first_object_key.clone());
-
-    let copy_reqs = (1..args.object_count).map(|i| {
-        client
-            .copy_object()
-            .bucket(&args.bucket)
-            .copy_source(&copy_source)
-            .key(format!("{}{:>05}", args.key_prefix, i))
-            .send()
+    let copy_reqs = (1..object_count).map(|i| {
+        let key = format!("{}{:>05}", args.key_prefix, i);
+        let store = &store;
+        let first_object_key = &first_object_key;
+        async move {
+            store
+                .copy_object(&args.bucket, first_object_key, &key)
+                .await
+        }
     });
     let mut copy_reqs_stream = stream::iter(copy_reqs).buffer_unordered(args.concurrent_copies);
     while let Some(_) = copy_reqs_stream.try_next().await? {
@@ -224,7 +388,45 @@ async fn run() -> anyhow::Result<()> {
     drop(progressbar);
 
     info!("created {} objects", total_created);
-    assert_eq!(total_created, args.object_count);
+    assert_eq!(total_created, object_count);
+
+    Ok(())
+}
+
+/// Deletes all objects under the key prefix described by `args`.
+///
+/// An empty key prefix is rejected, as it would delete every object in the
+/// bucket.
+async fn cleanup<S: ObjectStore>(args: &Args, store: &S) -> anyhow::Result<()> {
+    if args.key_prefix.is_empty() {
+        bail!(
+            "refusing to clean up with an empty key prefix, which would delete the entire bucket"
+        );
+    }
+    let keys = store.list_objects(&args.bucket, &args.key_prefix).await?;
+    info!(
+        "deleting {} objects in {}/{}",
+        keys.len(),
+        args.bucket,
+        args.key_prefix
+    );
+
+    let progressbar = indicatif::ProgressBar::new(u64::cast_from(keys.len()));
+    let delete_reqs = keys.chunks(DELETE_BATCH_SIZE).map(|batch| async move {
+        store
+            .delete_objects(&args.bucket, batch.to_vec())
+            .await
+            .map(|()| batch.len())
+    });
+    let mut total_deleted = 0;
+    let mut delete_reqs_stream = stream::iter(delete_reqs).buffer_unordered(args.concurrent_copies);
+    while let Some(deleted) = delete_reqs_stream.try_next().await? {
+        progressbar.inc(u64::cast_from(deleted));
+        total_deleted += deleted;
+    }
+    drop(progressbar);
+
+    info!("deleted {} objects", total_deleted);
 
     Ok(())
 }
@@ -232,3 +434,178 @@ async fn run() -> anyhow::Result<()> {
 fn parse_object_size(s: &str) -> Result<usize, &'static str> {
     bytefmt::parse(s).map(usize::cast_from)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    /// An in-memory [`ObjectStore`] that records the calls made against it.
+    #[derive(Clone, Default)]
+    struct MemoryStore {
+        objects: Arc<Mutex<BTreeMap<(String, String), Vec<u8>>>>,
+        calls: Arc<Mutex<Vec<String>>>,
+    }
+
+    impl MemoryStore {
+        fn record(&self, call: &str) {
+            self.calls.lock().expect("lock poisoned").push(call.into());
+        }
+    }
+
+    #[async_trait]
+    impl ObjectStore for MemoryStore {
+        async fn create_bucket(&self, _: &str) -> anyhow::Result<()> {
+            self.record("create_bucket");
+            Ok(())
+        }
+
+        async fn put_object(&self, bucket: &str, key: &str, body: Vec<u8>) -> anyhow::Result<()> {
+            self.record("put_object");
+            let mut objects = self.objects.lock().expect("lock poisoned");
+            objects.insert((bucket.into(), key.into()), body);
+            Ok(())
+        }
+
+        async fn copy_object(
+            &self,
+            bucket: &str,
+            source_key: &str,
+            key: &str,
+        ) -> anyhow::Result<()> {
+            self.record("copy_object");
+            let mut objects = self.objects.lock().expect("lock poisoned");
+            let body = objects[&(bucket.into(), source_key.into())].clone();
+            objects.insert((bucket.into(), key.into()), body);
+            Ok(())
+        }
+
+        async fn list_objects(&self, bucket: &str, prefix: &str) -> anyhow::Result<Vec<String>> {
+            self.record("list_objects");
+            let objects = self.objects.lock().expect("lock poisoned");
+            Ok(objects
+                .keys()
+                .filter(|(b, key)| b == bucket && key.starts_with(prefix))
+                .map(|(_, key)| key.clone())
+                .collect())
+        }
+
+        async fn delete_objects(&self, bucket: &str, keys: Vec<String>) -> anyhow::Result<()> {
+            self.record("delete_objects");
+            assert!(keys.len() <= DELETE_BATCH_SIZE);
+            let mut objects = self.objects.lock().expect("lock poisoned");
+            for key in keys {
+                objects.remove(&(bucket.into(), key));
+            }
+            Ok(())
+        }
+    }
+
+    fn args(extra: &[&str]) -> Args {
+        let mut argv = vec![
+            "mz-s3-datagen",
+            "--line-bytes=10",
+            "--object-size=1KiB",
+            "--object-count=5",
+            "--key-prefix=test/",
+            "--bucket=bucket",
+        ];
+        argv.extend(extra);
+        Args::try_parse_from(argv).unwrap()
+    }
+
+    #[mz_ore::test(tokio::test)]
+    async fn test_dry_run() {
+        execute::<MemoryStore, _, _>(&args(&["--dry-run"]), || async {
+            panic!("dry run must not connect to S3")
+        })
+        .await
+        .unwrap();
+
+        // Without `--dry-run`, the same arguments do call out to the store.
+        let store = MemoryStore::default();
+        execute(&args(&[]), || async { store.clone() })
+            .await
+            .unwrap();
+        assert_eq!(
+            *store.calls.lock().unwrap(),
+            [
+                "create_bucket",
+                "put_object",
+                "copy_object",
+                "copy_object",
+                "copy_object",
+                "copy_object"
+            ]
+        );
+        assert_eq!(store.objects.lock().unwrap().len(), 5);
+    }
+
+    #[mz_ore::test(tokio::test)]
+    #[cfg_attr(miri, ignore)] // too slow
+    async fn test_cleanup() {
+        let store = MemoryStore::default();
+        store
+            .put_object("bucket", "other/00000", vec![])
+            .await
+            .unwrap();
+        let args = Args::try_parse_from([
+            "mz-s3-datagen",
+            "--line-bytes=10",
+            "--object-size=1KiB",
+            "--object-count=2500",
+            "--key-prefix=test/",
+            "--bucket=bucket",
+        ])
+        .unwrap();
+        execute(&args, || async { store.clone() }).await.unwrap();
+        assert_eq!(store.objects.lock().unwrap().len(), 2501);
+
+        let args = Args::try_parse_from([
+            "mz-s3-datagen",
+            "--cleanup",
+            "--key-prefix=test/",
+            "--bucket=bucket",
+        ])
+        .unwrap();
+        execute(&args, || async { store.clone() }).await.unwrap();
+        assert!(store
+            .list_objects("bucket", "test/")
+            .await
+            .unwrap()
+            .is_empty());
+        // Objects outside of the prefix are untouched.
+        assert_eq!(store.objects.lock().unwrap().len(), 1);
+        // The 2500 objects are deleted in batches.
+        let calls = store.calls.lock().unwrap();
+        assert_eq!(calls.iter().filter(|c| *c == "delete_objects").count(), 3);
+    }
+
+    #[mz_ore::test(tokio::test)]
+    async fn test_cleanup_empty_prefix() {
+        let store = MemoryStore::default();
+        store
+            .put_object("bucket", "test/00000", vec![])
+            .await
+            .unwrap();
+        let args = Args::try_parse_from([
+            "mz-s3-datagen",
+            "--cleanup",
+            "--key-prefix=",
+            "--bucket=bucket",
+        ])
+        .unwrap();
+        let err = execute(&args, || async { store.clone() })
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "refusing to clean up with an empty key prefix, which would delete the entire bucket"
+        );
+        // The store is not even listed.
+        assert_eq!(*store.calls.lock().unwrap(), ["put_object"]);
+        assert_eq!(store.objects.lock().unwrap().len(), 1);
+    }
+}
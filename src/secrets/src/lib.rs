@@ -91,12 +91,26 @@ pub trait SecretsController: Debug + Send + Sync {
     async fn ensure(&self, id: GlobalId, contents: &[u8]) -> Result<(), anyhow::Error>;
 
     /// Deletes the specified secret.
+    ///
+    /// Deleting a secret that does not exist is not an error.
     async fn delete(&self, id: GlobalId) -> Result<(), anyhow::Error>;
 
     /// Lists known secrets. Unrecognized secret objects do not produce an error
     /// and are ignored.
     async fn list(&self) -> Result<Vec<GlobalId>, anyhow::Error>;
 
+    /// Deletes all known secrets, as reported by
+    /// [`SecretsController::list`].
+    ///
+    /// Secrets that are concurrently deleted by another party do not produce
+    /// an error.
+    async fn delete_all(&self) -> Result<(), anyhow::Error> {
+        for id in self.list().await? {
+            self.delete(id).await?;
+        }
+        Ok(())
+    }
+
     /// Returns a reader for the secrets managed by this controller.
     fn reader(&self) -> Arc<dyn SecretsReader>;
 }
@@ -161,3 +175,24 @@ impl SecretsReader for InMemorySecretsController {
         contents.ok_or_else(|| anyhow::anyhow!("secret does not exist"))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use futures::executor::block_on;
+
+    use super::*;
+
+    #[mz_ore::test]
+    fn test_delete_all() {
+        let controller = InMemorySecretsController::new();
+        let ids = [GlobalId::User(1), GlobalId::User(2), GlobalId::User(3)];
+        for id in ids {
+            block_on(controller.ensure(id, b"contents")).unwrap();
+        }
+        assert_eq!(block_on(controller.list()).unwrap(), ids);
+
+        block_on(controller.delete_all()).unwrap();
+        assert!(block_on(controller.list()).unwrap().is_empty());
+        assert!(block_on(controller.reader().read(ids[0])).is_err());
+    }
+}
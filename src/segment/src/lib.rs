@@ -115,11 +115,21 @@ impl fmt::Debug for Client {
 impl Client {
     /// Creates a new client.
     pub fn new(api_key: String) -> Client {
+        Client::with_http_client(api_key, HttpClient::default())
+    }
+
+    /// Creates a new client that sends events to the Segment-compatible API
+    /// at `host` (e.g., `https://api.segment.io`) instead of to Segment.
+    pub fn with_host(api_key: String, host: String) -> Client {
+        Client::with_http_client(api_key, HttpClient::new(reqwest::Client::new(), host))
+    }
+
+    fn with_http_client(api_key: String, http_client: HttpClient) -> Client {
         let (tx, rx) = mpsc::channel(MAX_PENDING_EVENTS);
 
         let send_task = SendTask {
             api_key,
-            http_client: HttpClient::default(),
+            http_client,
         };
         mz_ore::task::spawn(
             || "segment_send_task",
@@ -88,10 +88,11 @@ pub enum Expr<T: AstInfo> {
         case_insensitive: bool,
         negated: bool,
     },
-    /// `<expr> [ NOT ] BETWEEN <low> AND <high>`
+    /// `<expr> [ NOT ] BETWEEN [ SYMMETRIC ] <low> AND <high>`
     Between {
         expr: Box<Expr<T>>,
         negated: bool,
+        symmetric: bool,
         low: Box<Expr<T>>,
         high: Box<Expr<T>>,
     },
@@ -289,6 +290,7 @@ impl<T: AstInfo> AstDisplay for Expr<T> {
             Expr::Between {
                 expr,
                 negated,
+                symmetric,
                 low,
                 high,
             } => {
@@ -297,6 +299,9 @@ impl<T: AstInfo> AstDisplay for Expr<T> {
                     f.write_str(" NOT");
                 }
                 f.write_str(" BETWEEN ");
+                if *symmetric {
+                    f.write_str("SYMMETRIC ");
+                }
                 f.write_node(&low);
                 f.write_str(" AND ");
                 f.write_node(&high);
@@ -531,6 +531,7 @@ pub enum TableFactor<T: AstInfo> {
     Table {
         name: T::ItemName,
         alias: Option<TableAlias>,
+        sample: Option<TableSample<T>>,
     },
     Function {
         function: Function<T>,
@@ -560,12 +561,20 @@ pub enum TableFactor<T: AstInfo> {
 impl<T: AstInfo> AstDisplay for TableFactor<T> {
     fn fmt<W: fmt::Write>(&self, f: &mut AstFormatter<W>) {
         match self {
-            TableFactor::Table { name, alias } => {
+            TableFactor::Table {
+                name,
+                alias,
+                sample,
+            } => {
                 f.write_node(name);
                 if let Some(alias) = alias {
                     f.write_str(" AS ");
                     f.write_node(alias);
                 }
+                if let Some(sample) = sample {
+                    f.write_str(" ");
+                    f.write_node(sample);
+                }
             }
             TableFactor::Function {
                 function,
@@ -627,6 +636,41 @@ impl<T: AstInfo> AstDisplay for TableFactor<T> {
 }
 impl_display_t!(TableFactor);
 
+/// A `TABLESAMPLE` clause, like `TABLESAMPLE BERNOULLI (10)`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TableSample<T: AstInfo> {
+    pub method: TableSampleMethod,
+    /// The percentage of rows to sample.
+    pub percentage: Expr<T>,
+}
+
+impl<T: AstInfo> AstDisplay for TableSample<T> {
+    fn fmt<W: fmt::Write>(&self, f: &mut AstFormatter<W>) {
+        f.write_str("TABLESAMPLE ");
+        f.write_node(&self.method);
+        f.write_str(" (");
+        f.write_node(&self.percentage);
+        f.write_str(")");
+    }
+}
+impl_display_t!(TableSample);
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum TableSampleMethod {
+    Bernoulli,
+    System,
+}
+
+impl AstDisplay for TableSampleMethod {
+    fn fmt<W: fmt::Write>(&self, f: &mut AstFormatter<W>) {
+        f.write_str(match self {
+            TableSampleMethod::Bernoulli => "BERNOULLI",
+            TableSampleMethod::System => "SYSTEM",
+        })
+    }
+}
+impl_display!(TableSampleMethod);
+
 #[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
 pub struct TableAlias {
     pub name: Ident,
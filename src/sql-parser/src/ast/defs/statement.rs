@@ -971,6 +971,7 @@ pub struct ViewDefinition<T: AstInfo> {
     /// View name
     pub name: UnresolvedItemName,
     pub columns: Vec<Ident>,
+    pub with_options: Vec<ViewOption<T>>,
     pub query: Query<T>,
 }
 
@@ -984,12 +985,49 @@ impl<T: AstInfo> AstDisplay for ViewDefinition<T> {
             f.write_str(")");
         }
 
+        if !self.with_options.is_empty() {
+            f.write_str(" WITH (");
+            f.write_node(&display::comma_separated(&self.with_options));
+            f.write_str(")");
+        }
+
         f.write_str(" AS ");
         f.write_node(&self.query);
     }
 }
 impl_display_t!(ViewDefinition);
 
+/// An option in a `CREATE VIEW` statement.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ViewOptionName {
+    /// The `RETAIN HISTORY` option.
+    RetainHistory,
+}
+
+impl AstDisplay for ViewOptionName {
+    fn fmt<W: fmt::Write>(&self, f: &mut AstFormatter<W>) {
+        match self {
+            ViewOptionName::RetainHistory => f.write_str("RETAIN HISTORY"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ViewOption<T: AstInfo> {
+    pub name: ViewOptionName,
+    pub value: Option<WithOptionValue<T>>,
+}
+
+impl<T: AstInfo> AstDisplay for ViewOption<T> {
+    fn fmt<W: fmt::Write>(&self, f: &mut AstFormatter<W>) {
+        f.write_node(&self.name);
+        if let Some(v) = &self.value {
+            f.write_str(" = ");
+            f.write_node(v);
+        }
+    }
+}
+
 /// `CREATE VIEW`
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct CreateViewStatement<T: AstInfo> {
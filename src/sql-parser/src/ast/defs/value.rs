@@ -62,6 +62,12 @@ pub enum Value {
 
 impl AstDisplay for Value {
     fn fmt<W: fmt::Write>(&self, f: &mut AstFormatter<W>) {
+        if f.redacted() {
+            // Emit a string literal in place of every value, so that the
+            // redacted SQL still parses.
+            f.write_str("'<REDACTED>'");
+            return;
+        }
         match self {
             Value::Number(v) => f.write_str(v),
             Value::String(v) => {
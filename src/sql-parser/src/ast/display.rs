@@ -76,6 +76,9 @@ pub enum FormatMode {
     // quoted, even if not necessary. This mode is used when persisting table information to the
     // catalog.
     Stable,
+    // SimpleRedacted is like Simple, but strips out literal values, e.g. the contents of a secret.
+    // This mode is used when the AST is printed to logs.
+    SimpleRedacted,
 }
 
 #[derive(Debug)]
@@ -103,6 +106,11 @@ where
         self.mode == FormatMode::Stable
     }
 
+    // Whether literal values should be redacted.
+    pub fn redacted(&self) -> bool {
+        self.mode == FormatMode::SimpleRedacted
+    }
+
     pub fn new(buf: W, mode: FormatMode) -> Self {
         AstFormatter { buf, mode }
     }
@@ -128,6 +136,13 @@ pub trait AstDisplay {
         self.fmt(&mut f);
         buf
     }
+
+    fn to_ast_string_redacted(&self) -> String {
+        let mut buf = String::new();
+        let mut f = AstFormatter::new(&mut buf, FormatMode::SimpleRedacted);
+        self.fmt(&mut f);
+        buf
+    }
 }
 
 // Derive a fmt::Display implementation for types implementing AstDisplay.
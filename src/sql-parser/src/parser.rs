@@ -1409,8 +1409,10 @@ impl<'a> Parser<'a> {
         Ok(in_op)
     }
 
-    /// Parses `BETWEEN <low> AND <high>`, assuming the `BETWEEN` keyword was already consumed
+    /// Parses `BETWEEN [ SYMMETRIC ] <low> AND <high>`, assuming the `BETWEEN`
+    /// keyword was already consumed
     fn parse_between(&mut self, expr: Expr<Raw>, negated: bool) -> Result<Expr<Raw>, ParserError> {
+        let symmetric = self.parse_keyword(SYMMETRIC);
         // Stop parsing subexpressions for <low> and <high> on tokens with
         // precedence lower than that of `BETWEEN`, such as `AND`, `IS`, etc.
         let low = self.parse_subexpr(Precedence::Like)?;
@@ -1419,6 +1421,7 @@ impl<'a> Parser<'a> {
         Ok(Expr::Between {
             expr: Box::new(expr),
             negated,
+            symmetric,
             low: Box::new(low),
             high: Box::new(high),
         })
@@ -3083,17 +3086,32 @@ impl<'a> Parser<'a> {
         // ANSI SQL and Postgres support RECURSIVE here, but we don't.
         let name = self.parse_item_name()?;
         let columns = self.parse_parenthesized_column_list(Optional)?;
-        // Postgres supports WITH options here, but we don't.
+        let with_options = if self.parse_keyword(WITH) {
+            self.expect_token(&Token::LParen)?;
+            let options = self.parse_comma_separated(Parser::parse_view_option)?;
+            self.expect_token(&Token::RParen)?;
+            options
+        } else {
+            vec![]
+        };
         self.expect_keyword(AS)?;
         let query = self.parse_query()?;
         // Optional `WITH [ CASCADED | LOCAL ] CHECK OPTION` is widely supported here.
         Ok(ViewDefinition {
             name,
             columns,
+            with_options,
             query,
         })
     }
 
+    fn parse_view_option(&mut self) -> Result<ViewOption<Raw>, ParserError> {
+        self.expect_keywords(&[RETAIN, HISTORY])?;
+        let name = ViewOptionName::RetainHistory;
+        let value = self.parse_optional_option_value()?;
+        Ok(ViewOption { name, value })
+    }
+
     fn parse_create_materialized_view(&mut self) -> Result<Statement<Raw>, ParserError> {
         let mut if_exists = if self.parse_keyword(OR) {
             self.expect_keyword(REPLACE)?;
@@ -6155,14 +6173,40 @@ impl<'a> Parser<'a> {
                     with_ordinality,
                 })
             } else {
+                // `TABLESAMPLE` is only treated as the start of a sample
+                // clause when it is followed by a sampling method, so that it
+                // remains usable as a bare table alias.
+                let alias = if self.peek_keywords(&[TABLESAMPLE, BERNOULLI])
+                    || self.peek_keywords(&[TABLESAMPLE, SYSTEM])
+                {
+                    None
+                } else {
+                    self.parse_optional_table_alias()?
+                };
                 Ok(TableFactor::Table {
                     name,
-                    alias: self.parse_optional_table_alias()?,
+                    alias,
+                    sample: self.parse_optional_table_sample()?,
                 })
             }
         }
     }
 
+    fn parse_optional_table_sample(&mut self) -> Result<Option<TableSample<Raw>>, ParserError> {
+        if !self.parse_keyword(TABLESAMPLE) {
+            return Ok(None);
+        }
+        let method = match self.expect_one_of_keywords(&[BERNOULLI, SYSTEM])? {
+            BERNOULLI => TableSampleMethod::Bernoulli,
+            SYSTEM => TableSampleMethod::System,
+            _ => unreachable!(),
+        };
+        self.expect_token(&Token::LParen)?;
+        let percentage = self.parse_expr()?;
+        self.expect_token(&Token::RParen)?;
+        Ok(Some(TableSample { method, percentage }))
+    }
+
     fn parse_rows_from(&mut self) -> Result<TableFactor<Raw>, ParserError> {
         self.expect_token(&Token::LParen)?;
         let functions = self.parse_comma_separated(Parser::parse_named_function)?;
@@ -6591,7 +6635,7 @@ impl<'a> Parser<'a> {
                 Some(TEXT) => ExplainFormat::Text,
                 Some(JSON) => ExplainFormat::Json,
                 Some(DOT) => ExplainFormat::Dot,
-                None => return Err(ParserError::new(self.index, "expected a format")),
+                None => return parser_err!(self, self.peek_pos(), "expected a format"),
                 _ => unreachable!(),
             }
         } else {
@@ -274,6 +274,27 @@ fn format_ident() {
     }
 }
 
+#[mz_ore::test]
+#[cfg_attr(miri, ignore)] // unsupported operation: can't call foreign function `rust_psm_stack_pointer` on OS `linux`
+fn format_redacted() {
+    let cases = vec![
+        (
+            "CREATE SECRET s AS 'hunter2'",
+            "CREATE SECRET s AS '<REDACTED>'",
+        ),
+        (
+            "SELECT a + 1, 'b' FROM t WHERE c > INTERVAL '1 day'",
+            "SELECT a + '<REDACTED>', '<REDACTED>' FROM t WHERE c > '<REDACTED>'",
+        ),
+    ];
+    for (sql, redacted) in cases {
+        let stmt = parse_statements(sql).unwrap().into_element();
+        assert_eq!(redacted, stmt.to_ast_string_redacted());
+        // Redacted SQL must still parse.
+        parse_statements(redacted).unwrap();
+    }
+}
+
 #[mz_ore::test]
 #[cfg_attr(miri, ignore)] // unsupported operation: can't call foreign function `rust_psm_stack_pointer` on OS `linux`
 fn test_basic_visitor() -> Result<(), Box<dyn Error>> {
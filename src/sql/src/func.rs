@@ -526,6 +526,20 @@ impl From<BinaryFunc> for Operation<HirScalarExpr> {
     }
 }
 
+/// Returns an operation that divides its arguments using `func`, rejecting a
+/// literal zero divisor during planning rather than at runtime.
+///
+/// Constant divisions like `1 / 0` are left alone so that they keep their
+/// usual evaluation-time semantics, e.g. short-circuiting in `AND`/`OR`.
+fn checked_div(func: BinaryFunc) -> Operation<HirScalarExpr> {
+    Operation::binary(move |_ecx, left, right| {
+        if right.is_literal_zero() && left.as_literal().is_none() {
+            return Err(PlanError::DivisionByZero);
+        }
+        Ok(left.call_binary(right, func.clone()))
+    })
+}
+
 impl From<VariadicFunc> for Operation<HirScalarExpr> {
     fn from(v: VariadicFunc) -> Operation<HirScalarExpr> {
         Operation::variadic(move |_ecx, exprs| {
@@ -3813,16 +3827,16 @@ pub static OP_IMPLS: Lazy<BTreeMap<&'static str, Func>> = Lazy::new(|| {
             params!(RangeAny, RangeAny) => RangeIntersection => RangeAny, 3900;
         },
         "/" => Scalar {
-            params!(Int16, Int16) => DivInt16 => Int16, 527;
-            params!(Int32, Int32) => DivInt32 => Int32, 528;
-            params!(Int64, Int64) => DivInt64 => Int64, 687;
-            params!(UInt16, UInt16) => DivUInt16 => UInt16, oid::FUNC_DIV_UINT16;
-            params!(UInt32, UInt32) => DivUInt32 => UInt32, oid::FUNC_DIV_UINT32;
-            params!(UInt64, UInt64) => DivUInt64 => UInt64, oid::FUNC_DIV_UINT64;
+            params!(Int16, Int16) => checked_div(DivInt16) => Int16, 527;
+            params!(Int32, Int32) => checked_div(DivInt32) => Int32, 528;
+            params!(Int64, Int64) => checked_div(DivInt64) => Int64, 687;
+            params!(UInt16, UInt16) => checked_div(DivUInt16) => UInt16, oid::FUNC_DIV_UINT16;
+            params!(UInt32, UInt32) => checked_div(DivUInt32) => UInt32, oid::FUNC_DIV_UINT32;
+            params!(UInt64, UInt64) => checked_div(DivUInt64) => UInt64, oid::FUNC_DIV_UINT64;
             params!(Float32, Float32) => DivFloat32 => Float32, 588;
             params!(Float64, Float64) => DivFloat64 => Float64, 593;
             params!(Interval, Float64) => DivInterval => Interval, 1585;
-            params!(Numeric, Numeric) => DivNumeric => Numeric, 1761;
+            params!(Numeric, Numeric) => checked_div(DivNumeric) => Numeric, 1761;
         },
         "%" => Scalar {
             params!(Int16, Int16) => ModInt16 => Int16, 529;
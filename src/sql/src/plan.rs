@@ -1102,6 +1102,8 @@ pub struct View {
     pub expr: mz_expr::MirRelationExpr,
     pub column_names: Vec<ColumnName>,
     pub temporary: bool,
+    /// The logical compaction window to use for the view, if not the default.
+    pub retain_history: Option<Duration>,
 }
 
 #[derive(Clone, Debug)]
@@ -208,6 +208,16 @@ pub enum PlanError {
     ShowCommandInView,
     WebhookValidationDoesNotUseColumns,
     WebhookValidationNonDeterministic,
+    DivisionByZero,
+    /// The types of two expressions cannot be coerced to a common type. The
+    /// column is the 1-based position at which a set operation failed to
+    /// match its inputs' types, if any.
+    UnmatchedTypes {
+        name: String,
+        left: String,
+        right: String,
+        column: Option<usize>,
+    },
     // TODO(benesch): eventually all errors should be structured.
     Unstructured(String),
 }
@@ -520,6 +530,14 @@ impl fmt::Display for PlanError {
             },
             Self::InvalidPrivatelinkAvailabilityZone { name, ..} => write!(f, "invalid AWS PrivateLink availability zone {}", name.quoted()),
             Self::InvalidSchemaName => write!(f, "no schema has been selected to create in"),
+            Self::DivisionByZero => write!(f, "division by zero"),
+            Self::UnmatchedTypes { name, left, right, column } => {
+                write!(f, "{name} types {left} and {right} cannot be matched")?;
+                if let Some(column) = column {
+                    write!(f, " at column {column}")?;
+                }
+                Ok(())
+            }
             Self::ItemAlreadyExists { name, item_type } => write!(f, "{item_type} {} already exists", name.quoted()),
             Self::ManagedCluster {cluster_name} => write!(f, "cannot modify managed cluster {cluster_name}"),
             Self::ModifyLinkedCluster {cluster_name, ..} => write!(f, "cannot modify linked cluster {}", cluster_name.quoted()),
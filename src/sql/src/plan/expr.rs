@@ -2458,6 +2458,22 @@ impl HirScalarExpr {
         Some(Datum::Null) == self.as_literal()
     }
 
+    /// Reports whether this expression is a literal integer or numeric zero.
+    pub fn is_literal_zero(&self) -> bool {
+        match self.as_literal() {
+            Some(
+                Datum::Int16(0)
+                | Datum::Int32(0)
+                | Datum::Int64(0)
+                | Datum::UInt16(0)
+                | Datum::UInt32(0)
+                | Datum::UInt64(0),
+            ) => true,
+            Some(Datum::Numeric(n)) => n.0.is_zero(),
+            _ => false,
+        }
+    }
+
     pub fn call_unary(self, func: UnaryFunc) -> Self {
         HirScalarExpr::CallUnary {
             func,
@@ -59,8 +59,9 @@ use mz_sql_parser::ast::{
     HomogenizingFunction, Ident, InsertSource, IsExprConstruct, Join, JoinConstraint, JoinOperator,
     Limit, MutRecBlock, MutRecBlockOption, MutRecBlockOptionName, OrderByExpr, Query, Select,
     SelectItem, SelectOption, SelectOptionName, SetExpr, SetOperator, ShowStatement,
-    SubscriptPosition, TableAlias, TableFactor, TableWithJoins, UnresolvedItemName,
-    UpdateStatement, Value, Values, WindowFrame, WindowFrameBound, WindowFrameUnits, WindowSpec,
+    SubscriptPosition, TableAlias, TableFactor, TableSample, TableSampleMethod, TableWithJoins,
+    UnresolvedItemName, UpdateStatement, Value, Values, WindowFrame, WindowFrameBound,
+    WindowFrameUnits, WindowSpec,
 };
 use uuid::Uuid;
 
@@ -72,8 +73,8 @@ use crate::plan::error::PlanError;
 use crate::plan::expr::{
     AbstractColumnType, AbstractExpr, AggregateExpr, AggregateFunc, BinaryFunc,
     CoercibleScalarExpr, ColumnOrder, ColumnRef, Hir, HirRelationExpr, HirScalarExpr, JoinKind,
-    ScalarWindowExpr, ScalarWindowFunc, UnaryFunc, ValueWindowExpr, ValueWindowFunc, VariadicFunc,
-    WindowExpr, WindowExprType,
+    ScalarWindowExpr, ScalarWindowFunc, UnaryFunc, UnmaterializableFunc, ValueWindowExpr,
+    ValueWindowFunc, VariadicFunc, WindowExpr, WindowExprType,
 };
 use crate::plan::plan_utils::{self, JoinSide};
 use crate::plan::scope::{Scope, ScopeItem};
@@ -1425,8 +1426,12 @@ fn plan_set_expr(
                     Some(left_type.scalar_type.clone()),
                     Some(right_type.scalar_type.clone()),
                 ];
-                let target =
-                    typeconv::guess_best_common_type(&left_ecx.with_name(&op.to_string()), types)?;
+                let mut target =
+                    typeconv::guess_best_common_type(&left_ecx.with_name(&op.to_string()), types);
+                if let Err(PlanError::UnmatchedTypes { column, .. }) = &mut target {
+                    *column = Some(i + 1);
+                }
+                let target = target?;
                 match typeconv::plan_cast(
                     left_ecx,
                     CastContext::Implicit,
@@ -1434,12 +1439,14 @@ fn plan_set_expr(
                     &target,
                 ) {
                     Ok(expr) => left_casts.push(expr),
-                    Err(_) => sql_bail!(
-                        "{} types {} and {} cannot be matched",
-                        op,
-                        qcx.humanize_scalar_type(&left_type.scalar_type),
-                        qcx.humanize_scalar_type(&target),
-                    ),
+                    Err(_) => {
+                        return Err(PlanError::UnmatchedTypes {
+                            name: op.to_string(),
+                            left: qcx.humanize_scalar_type(&left_type.scalar_type),
+                            right: qcx.humanize_scalar_type(&target),
+                            column: Some(i + 1),
+                        })
+                    }
                 }
                 match typeconv::plan_cast(
                     right_ecx,
@@ -1448,12 +1455,14 @@ fn plan_set_expr(
                     &target,
                 ) {
                     Ok(expr) => right_casts.push(expr),
-                    Err(_) => sql_bail!(
-                        "{} types {} and {} cannot be matched",
-                        op,
-                        qcx.humanize_scalar_type(&target),
-                        qcx.humanize_scalar_type(&right_type.scalar_type),
-                    ),
+                    Err(_) => {
+                        return Err(PlanError::UnmatchedTypes {
+                            name: op.to_string(),
+                            left: qcx.humanize_scalar_type(&target),
+                            right: qcx.humanize_scalar_type(&right_type.scalar_type),
+                            column: Some(i + 1),
+                        })
+                    }
                 }
             }
             let lhs = if left_casts
@@ -2409,8 +2418,15 @@ fn plan_table_factor(
     table_factor: &TableFactor<Aug>,
 ) -> Result<(HirRelationExpr, Scope), PlanError> {
     match table_factor {
-        TableFactor::Table { name, alias } => {
-            let (expr, scope) = qcx.resolve_table_name(name.clone())?;
+        TableFactor::Table {
+            name,
+            alias,
+            sample,
+        } => {
+            let (mut expr, scope) = qcx.resolve_table_name(name.clone())?;
+            if let Some(sample) = sample {
+                expr = plan_table_sample(expr, sample)?;
+            }
             let scope = plan_table_alias(scope, alias.as_ref())?;
             Ok((expr, scope))
         }
@@ -2458,6 +2474,56 @@ fn plan_table_factor(
     }
 }
 
+/// Plans a `TABLESAMPLE` clause by filtering `expr` down to the rows for which
+/// a uniformly distributed random value falls below the sample fraction.
+///
+/// A new random value is drawn for every row each time the query runs, so
+/// sampled relations cannot be maintained in indexes or materialized views.
+fn plan_table_sample(
+    expr: HirRelationExpr,
+    sample: &TableSample<Aug>,
+) -> Result<HirRelationExpr, PlanError> {
+    if let TableSampleMethod::System = sample.method {
+        bail_unsupported!("TABLESAMPLE SYSTEM");
+    }
+    let fraction = tablesample_fraction(tablesample_percentage(&sample.percentage)?)?;
+    let predicate = HirScalarExpr::CallUnmaterializable(UnmaterializableFunc::Random).call_binary(
+        HirScalarExpr::literal(Datum::from(fraction), ScalarType::Float64),
+        BinaryFunc::Lt,
+    );
+    Ok(expr.filter(vec![predicate]))
+}
+
+/// Evaluates the percentage of a `TABLESAMPLE` clause, which must be a numeric
+/// constant, optionally signed or parenthesized.
+fn tablesample_percentage(expr: &Expr<Aug>) -> Result<f64, PlanError> {
+    match expr {
+        Expr::Value(Value::Number(x)) => x
+            .parse()
+            .map_err(|_| sql_err!("invalid TABLESAMPLE percentage: {}", x)),
+        Expr::Nested(expr) => tablesample_percentage(expr),
+        Expr::Op {
+            op,
+            expr1,
+            expr2: None,
+        } if op.namespace.is_none() && op.op == "-" => Ok(-tablesample_percentage(expr1)?),
+        Expr::Op {
+            op,
+            expr1,
+            expr2: None,
+        } if op.namespace.is_none() && op.op == "+" => tablesample_percentage(expr1),
+        _ => sql_bail!("TABLESAMPLE percentage must be a numeric constant"),
+    }
+}
+
+/// Converts a `TABLESAMPLE` percentage into the fraction of rows to sample.
+fn tablesample_fraction(percentage: f64) -> Result<f64, PlanError> {
+    if !(0.0..=100.0).contains(&percentage) {
+        sql_bail!("sample percentage must be between 0 and 100");
+    }
+    Ok(percentage / 100.0)
+}
+
 /// Plans a `ROWS FROM` expression.
 ///
 /// `ROWS FROM` concatenates table functions into a single table, filling in
@@ -3569,7 +3635,22 @@ fn plan_in_list(
     let or = HirScalarExpr::variadic_or(
         list.into_iter()
             .map(|e| {
-                let eq = lhs.clone().equals(e.clone());
+                let eq = match (lhs, e) {
+                    // Expand `(l1, ..., ln) IN ((r1, ..., rn), ...)` into
+                    // `(l1 = r1 AND ... AND ln = rn) OR ...`, so that each
+                    // column is compared (and type checked) on its own.
+                    (Expr::Row { exprs: left }, Expr::Row { exprs: right }) => {
+                        if left.len() != right.len() {
+                            sql_bail!("unequal number of entries in row expressions");
+                        }
+                        left.iter()
+                            .zip(right)
+                            .map(|(l, r)| l.clone().equals(r.clone()))
+                            .reduce(|acc, eq| acc.and(eq))
+                            .ok_or_else(|| sql_err!("cannot compare rows of zero length"))?
+                    }
+                    _ => lhs.clone().equals(e.clone()),
+                };
                 plan_expr(&ecx, &eq)?.type_as(&ecx, &ScalarType::Bool)
             })
             .collect::<Result<Vec<HirScalarExpr>, PlanError>>()?,
@@ -83,7 +83,7 @@ use crate::ast::{
     PostgresConnectionOptionName, ProtobufSchema, QualifiedReplica, ReferencedSubsources,
     ReplicaDefinition, ReplicaOption, ReplicaOptionName, RoleAttribute, SourceIncludeMetadata,
     SourceIncludeMetadataType, SshConnectionOptionName, Statement, TableConstraint,
-    UnresolvedDatabaseName, ViewDefinition,
+    UnresolvedDatabaseName, ViewDefinition, ViewOption, ViewOptionName,
 };
 use crate::catalog::{
     CatalogCluster, CatalogDatabase, CatalogItem, CatalogItemType, CatalogType, CatalogTypeDetails,
@@ -1812,6 +1812,8 @@ pub fn describe_create_view(
     Ok(StatementDesc::new(None))
 }
 
+generate_extracted_config!(ViewOption, (RetainHistory, Interval));
+
 pub fn plan_view(
     scx: &StatementContext,
     def: &mut ViewDefinition<Aug>,
@@ -1830,9 +1832,26 @@ pub fn plan_view(
     let ViewDefinition {
         name,
         columns,
+        with_options,
         query,
     } = def;
 
+    let ViewOptionExtracted {
+        retain_history,
+        seen: _,
+    } = with_options.clone().try_into()?;
+    let retain_history = match retain_history {
+        Some(interval) => {
+            scx.require_feature_flag_w_dynamic_desc(
+                &vars::ENABLE_LOGICAL_COMPACTION_WINDOW,
+                "RETAIN HISTORY".into(),
+                "RETAIN HISTORY is gated by the same flag as LOGICAL COMPACTION WINDOW".into(),
+            )?;
+            Some(interval.duration()?)
+        }
+        None => None,
+    };
+
     let query::PlannedQuery {
         mut expr,
         mut desc,
@@ -1851,6 +1870,26 @@ pub fn plan_view(
         scx.allocate_qualified_name(normalize::unresolved_item_name(name.to_owned())?)?
     };
 
+    let arity = desc.arity();
+    if columns.len() > arity {
+        let extra = columns[arity..]
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                let column = normalize::column_name(column.clone());
+                format!("{} {}", arity + i + 1, column.as_str().quoted())
+            })
+            .join(", ");
+        sql_bail!(
+            "view {} declares {} columns but query produces {}; column{} {} {} no corresponding output",
+            scx.catalog.resolve_full_name(&name),
+            columns.len(),
+            arity,
+            if columns.len() - arity == 1 { "" } else { "s" },
+            extra,
+            if columns.len() - arity == 1 { "has" } else { "have" },
+        );
+    }
     plan_utils::maybe_rename_columns(
         format!("view {}", scx.catalog.resolve_full_name(&name)),
         &mut desc,
@@ -1867,6 +1906,7 @@ pub fn plan_view(
         expr: relation_expr,
         column_names: names,
         temporary,
+        retain_history,
     };
 
     Ok((name, view))
@@ -5046,3 +5086,29 @@ pub(crate) fn ensure_cluster_is_not_managed(
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use mz_sql_parser::ast::{Value, WithOptionValue};
+
+    use super::*;
+
+    #[mz_ore::test]
+    fn test_view_options() {
+        let retain_history = |value: &str| ViewOption {
+            name: ViewOptionName::RetainHistory,
+            value: Some(WithOptionValue::Value(Value::String(value.into()))),
+        };
+
+        let extracted: ViewOptionExtracted = vec![retain_history("1h")].try_into().unwrap();
+        assert_eq!(
+            extracted.retain_history.unwrap().duration().unwrap(),
+            Duration::from_secs(60 * 60)
+        );
+
+        let extracted: Result<ViewOptionExtracted, _> = vec![retain_history("bogus")].try_into();
+        assert!(extracted.is_err());
+    }
+}
@@ -514,18 +514,42 @@ impl<'a> Desugarer<'a> {
 
         // `$expr BETWEEN $low AND $high` => `$expr >= $low AND $expr <= $low`
         // `$expr NOT BETWEEN $low AND $high` => `$expr < $low OR $expr > $low`
+        //
+        // `$expr BETWEEN SYMMETRIC $low AND $high`
+        // =>
+        // `($expr >= $low AND $expr <= $high) OR ($expr >= $high AND $expr <= $low)`
+        //
+        // `$expr NOT BETWEEN SYMMETRIC $low AND $high`
+        // =>
+        // `($expr < $low OR $expr > $high) AND ($expr < $high OR $expr > $low)`
         if let Expr::Between {
             expr: e,
             low,
             high,
             negated,
+            symmetric,
         } = expr
         {
-            if *negated {
-                *expr = Expr::lt(*e.clone(), low.take()).or(e.take().gt(high.take()));
+            let (negated, symmetric) = (*negated, *symmetric);
+            let between = |e: Expr<Aug>, low: Expr<Aug>, high: Expr<Aug>| {
+                if negated {
+                    Expr::lt(e.clone(), low).or(e.gt(high))
+                } else {
+                    e.clone().gt_eq(low).and(e.lt_eq(high))
+                }
+            };
+            let (e, low, high) = (e.take(), low.take(), high.take());
+            *expr = if symmetric {
+                let forward = between(e.clone(), low.clone(), high.clone());
+                let backward = between(e, high, low);
+                if negated {
+                    forward.and(backward)
+                } else {
+                    forward.or(backward)
+                }
             } else {
-                *expr = e.clone().gt_eq(low.take()).and(e.take().lt_eq(high.take()));
-            }
+                between(e, low, high)
+            };
         }
 
         // `$expr IN ($subquery)` => `$expr = ANY ($subquery)`
@@ -817,12 +817,12 @@ pub fn guess_best_common_type(
     for typ in types {
         if TypeCategory::from_type(candidate) != TypeCategory::from_type(typ) {
             // The next type is in a different category; give up.
-            sql_bail!(
-                "{} types {} and {} cannot be matched",
-                ecx.name,
-                ecx.humanize_scalar_type(candidate),
-                ecx.humanize_scalar_type(typ),
-            );
+            return Err(PlanError::UnmatchedTypes {
+                name: ecx.name.to_string(),
+                left: ecx.humanize_scalar_type(candidate),
+                right: ecx.humanize_scalar_type(typ),
+                column: None,
+            });
         };
 
         // If this type is the preferred type, make it the candidate.
@@ -851,6 +851,18 @@ const STORAGE_DATAFLOW_MAX_INFLIGHT_BYTES: ServerVar<Option<usize>> = ServerVar
     internal: true,
 };
 
+/// The index of the next statement in the bootstrap SQL file to execute, or
+/// `None` if the file has been fully applied (or was never configured).
+///
+/// Persisted so that a bootstrap that fails partway through resumes from the
+/// failed statement on the next restart rather than being abandoned.
+pub const BOOTSTRAP_SQL_NEXT_STATEMENT: ServerVar<Option<usize>> = ServerVar {
+    name: UncasedStr::new("bootstrap_sql_next_statement"),
+    value: &None,
+    description: "The index of the next bootstrap SQL statement to execute (Materialize).",
+    internal: true,
+};
+
 /// Controls [`mz_persist_client::cfg::PersistConfig::sink_minimum_batch_updates`].
 const PERSIST_SINK_MINIMUM_BATCH_UPDATES: ServerVar<usize> = ServerVar {
     name: UncasedStr::new("persist_sink_minimum_batch_updates"),
@@ -1837,6 +1849,7 @@ impl SystemVars {
             .with_var(&CRDB_TCP_USER_TIMEOUT)
             .with_var(&DATAFLOW_MAX_INFLIGHT_BYTES)
             .with_var(&STORAGE_DATAFLOW_MAX_INFLIGHT_BYTES)
+            .with_var(&BOOTSTRAP_SQL_NEXT_STATEMENT)
             .with_var(&PERSIST_SINK_MINIMUM_BATCH_UPDATES)
             .with_var(&STORAGE_PERSIST_SINK_MINIMUM_BATCH_UPDATES)
             .with_var(&PERSIST_NEXT_LISTEN_BATCH_RETRYER_INITIAL_BACKOFF)
@@ -2301,6 +2314,11 @@ impl SystemVars {
         *self.expect_value(&STORAGE_DATAFLOW_MAX_INFLIGHT_BYTES)
     }
 
+    /// Returns the `bootstrap_sql_next_statement` configuration parameter.
+    pub fn bootstrap_sql_next_statement(&self) -> Option<usize> {
+        *self.expect_value(&BOOTSTRAP_SQL_NEXT_STATEMENT)
+    }
+
     /// Returns the `persist_sink_minimum_batch_updates` configuration parameter.
     pub fn persist_sink_minimum_batch_updates(&self) -> usize {
         *self.expect_value(&PERSIST_SINK_MINIMUM_BATCH_UPDATES)
@@ -978,7 +978,10 @@ impl<'a> RunnerInner<'a> {
             secrets_controller,
             cloud_resource_controller: None,
             tls: None,
+            internal_http_tls: false,
             frontegg: None,
+            idle_connection_timeout: None,
+            slow_query_threshold: None,
             cors_allowed_origin: AllowOrigin::list([]),
             unsafe_mode: true,
             all_features: false,
@@ -996,6 +999,9 @@ impl<'a> RunnerInner<'a> {
             storage_usage_collection_interval: Duration::from_secs(3600),
             storage_usage_retention_period: None,
             segment_api_key: None,
+            segment_api_host: None,
+            disable_telemetry_reporting: false,
+            air_gapped: false,
             egress_ips: vec![],
             aws_account_id: None,
             aws_privatelink_availability_zones: None,
@@ -1003,6 +1009,7 @@ impl<'a> RunnerInner<'a> {
             launchdarkly_key_map: Default::default(),
             config_sync_loop_interval: None,
             bootstrap_role: Some("materialize".into()),
+            bootstrap_sql: None,
             deploy_generation: None,
         };
         // We need to run the server on its own Tokio runtime, which in turn
@@ -2115,6 +2122,7 @@ fn generate_view_sql(
         definition: ViewDefinition {
             name: name.clone(),
             columns: columns.clone(),
+            with_options: vec![],
             query,
         },
     })
@@ -2171,6 +2179,7 @@ fn generate_view_sql(
                     relation: TableFactor::Table {
                         name: RawItemName::Name(name.clone()),
                         alias: None,
+                        sample: None,
                     },
                     joins: vec![],
                 }],
@@ -474,6 +474,7 @@ impl Usage {
                 builtin_cluster_replica_size: "1".into(),
                 default_availability_zone: DUMMY_AVAILABILITY_ZONE.into(),
                 bootstrap_role: None,
+                bootstrap_sql: false,
             },
             None,
         )
@@ -646,3 +646,47 @@ impl<'a, 'b> std::fmt::Display for HumanizedSymbolicExpression<'a, 'b> {
         self.expr.normalize().humanize(self.humanizer, f)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use mz_expr::visit::Visit;
+    use mz_repr::{Datum, RelationType, ScalarType};
+
+    use crate::attribute::RequiredAttributes;
+
+    use super::*;
+
+    fn cardinality(expr: &MirRelationExpr) -> SymExp {
+        let mut builder = RequiredAttributes::default();
+        builder.require::<Cardinality>();
+        let mut attributes = builder.finish();
+        expr.visit(&mut attributes).unwrap();
+        attributes
+            .get_results_mut::<Cardinality>()
+            .pop()
+            .expect("cardinality")
+    }
+
+    #[mz_ore::test]
+    fn test_constant_and_filter() {
+        let constant = MirRelationExpr::constant(
+            vec![
+                vec![Datum::Int64(1)],
+                vec![Datum::Int64(2)],
+                vec![Datum::Int64(3)],
+            ],
+            RelationType::new(vec![ScalarType::Int64.nullable(false)]),
+        );
+        // The estimate for a constant is exact.
+        assert_eq!(cardinality(&constant), SymExp::from(3));
+
+        // A filter scales down the estimate of its input.
+        let filter = constant.filter(vec![MirScalarExpr::column(0).call_binary(
+            MirScalarExpr::literal_ok(Datum::Int64(1), ScalarType::Int64),
+            BinaryFunc::Gt,
+        )]);
+        let estimate = cardinality(&filter);
+        assert_eq!(estimate, SymExp::f64(3.0 * 0.33));
+        assert!(estimate.evaluate(&|_| 0.0) < 3.0);
+    }
+}
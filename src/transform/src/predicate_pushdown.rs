@@ -151,6 +151,20 @@ impl PredicatePushdown {
             // In the case of Filter or Get we have specific work to do;
             // otherwise we should recursively descend.
             match relation {
+                MirRelationExpr::Filter { input, predicates }
+                    if predicates.iter().any(|p| p.contains_random()) =>
+                {
+                    // Predicates that call `random` sample the rows of `input`,
+                    // so they must stay where they are: elsewhere they would
+                    // sample different rows, or be applied more than once.
+                    // The remaining predicates can still be pushed down.
+                    let (random, other): (Vec<_>, Vec<_>) = std::mem::take(predicates)
+                        .into_iter()
+                        .partition(|p| p.contains_random());
+                    *predicates = random;
+                    **input = input.take_dangerous().filter(other);
+                    self.action(input, get_predicates)?;
+                }
                 MirRelationExpr::Filter { input, predicates } => {
                     // Reduce the predicates to determine as best as possible
                     // whether they are literal errors before working with them.
@@ -19,12 +19,55 @@ use mz_ore::codegen::CodegenBuf;
 
 use crate::ir::{Ir, Item, Type};
 
+/// Options that control code generation.
+#[derive(Debug, Clone, Default)]
+pub struct GenOptions {
+    /// Whether to precede each generated function with a `#[rustfmt::skip]`
+    /// attribute and a `// <source-type>` comment naming the type from which
+    /// the function was generated.
+    ///
+    /// The markers make it possible to trace compile errors in the generated
+    /// code back to the type definition that produced them.
+    pub markers: bool,
+    /// Whether to produce a sourcemap alongside the generated code. Implies
+    /// `markers`.
+    pub sourcemap: bool,
+}
+
+impl GenOptions {
+    fn markers(&self) -> bool {
+        self.markers || self.sourcemap
+    }
+}
+
+/// The output of a code generation function.
+#[derive(Debug, Clone)]
+pub struct GenOutput {
+    /// The generated Rust code.
+    pub code: String,
+    /// The sourcemap for the generated code, if requested via
+    /// [`GenOptions::sourcemap`].
+    ///
+    /// Each line of the sourcemap has the form `<line>\t<fn>\t<type>`, where
+    /// `<line>` is the one-based line number in `code` at which the function
+    /// named `<fn>` is declared and `<type>` is the name of the type from which
+    /// the function was generated.
+    pub sourcemap: Option<String>,
+}
+
 /// Generates a fold transformer for a mutable AST.
 ///
 /// Returns a string of Rust code that should be compiled alongside the module
 /// from which it was generated.
 pub fn gen_fold(ir: &Ir) -> String {
-    gen_fold_root(ir)
+    gen_fold_with_options(ir, &GenOptions::default()).code
+}
+
+/// Like [`gen_fold`], but with the specified options.
+pub fn gen_fold_with_options(ir: &Ir, options: &GenOptions) -> GenOutput {
+    let mut markers = Markers::new(options);
+    let code = gen_fold_root(ir, &mut markers);
+    markers.finish(code)
 }
 
 /// Generates a visitor for an immutable AST.
@@ -32,7 +75,14 @@ pub fn gen_fold(ir: &Ir) -> String {
 /// Returns a string of Rust code that should be compiled alongside the module
 /// from which it was generated.
 pub fn gen_visit(ir: &Ir) -> String {
-    gen_visit_root(&VisitConfig { mutable: false }, ir)
+    gen_visit_with_options(ir, &GenOptions::default()).code
+}
+
+/// Like [`gen_visit`], but with the specified options.
+pub fn gen_visit_with_options(ir: &Ir, options: &GenOptions) -> GenOutput {
+    let mut markers = Markers::new(options);
+    let code = gen_visit_root(&VisitConfig { mutable: false }, ir, &mut markers);
+    markers.finish(code)
 }
 
 /// Generates a visitor for a mutable AST.
@@ -40,10 +90,65 @@ pub fn gen_visit(ir: &Ir) -> String {
 /// Returns a string of Rust code that should be compiled alongside the module
 /// from which it was generated.
 pub fn gen_visit_mut(ir: &Ir) -> String {
-    gen_visit_root(&VisitConfig { mutable: true }, ir)
+    gen_visit_mut_with_options(ir, &GenOptions::default()).code
+}
+
+/// Like [`gen_visit_mut`], but with the specified options.
+pub fn gen_visit_mut_with_options(ir: &Ir, options: &GenOptions) -> GenOutput {
+    let mut markers = Markers::new(options);
+    let code = gen_visit_root(&VisitConfig { mutable: true }, ir, &mut markers);
+    markers.finish(code)
 }
 
-pub fn gen_fold_root(ir: &Ir) -> String {
+/// Tracks the source markers emitted during code generation.
+struct Markers {
+    enabled: bool,
+    sourcemap: bool,
+    /// The `(fn name, type name)` pair for each emitted marker, in order of
+    /// emission.
+    entries: Vec<(String, String)>,
+}
+
+impl Markers {
+    fn new(options: &GenOptions) -> Markers {
+        Markers {
+            enabled: options.markers(),
+            sourcemap: options.sourcemap,
+            entries: vec![],
+        }
+    }
+
+    /// Writes a marker for the function `fn_name` generated from the type
+    /// `ty`, if markers are enabled.
+    fn write(&mut self, buf: &mut CodegenBuf, fn_name: &str, ty: &str) {
+        if self.enabled {
+            buf.writeln(format!("// {ty}"));
+            buf.writeln(MARKER_ATTR);
+            self.entries.push((fn_name.into(), ty.into()));
+        }
+    }
+
+    fn finish(self, code: String) -> GenOutput {
+        let sourcemap = self.sourcemap.then(|| {
+            // Each marker attribute is immediately followed by the function
+            // it annotates, so the nth marker attribute locates the nth entry.
+            let lines = code
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| line.trim() == MARKER_ATTR)
+                .map(|(i, _)| i + 2);
+            lines
+                .zip(&self.entries)
+                .map(|(line, (fn_name, ty))| format!("{line}\t{fn_name}\t{ty}\n"))
+                .collect()
+        });
+        GenOutput { code, sourcemap }
+    }
+}
+
+const MARKER_ATTR: &str = "#[rustfmt::skip]";
+
+fn gen_fold_root(ir: &Ir, markers: &mut Markers) -> String {
     let mut generics = BTreeMap::new();
     for (name, bounds) in &ir.generics {
         generics.insert(name.clone(), bounds.clone());
@@ -65,12 +170,14 @@ pub fn gen_fold_root(ir: &Ir) -> String {
                         // simpler than trying to use a structured type for `name`.
                         let name2 = name.replacen("::", "2::", 1);
                         let fn_name = fold_fn_name(name);
+                        markers.write(buf, &fn_name, name);
                         buf.writeln(format!("fn {fn_name}(&mut self, node: {name}) -> {name2};"))
                     }
                     Item::Struct(_) | Item::Enum(_) => {
                         let generics = item_generics(item, "");
                         let generics2 = item_generics(item, "2");
                         let fn_name = fold_fn_name(name);
+                        markers.write(buf, &fn_name, name);
                         buf.write_block(
                             format!("fn {fn_name}(&mut self, node: {name}{generics}) -> {name}{generics2}"),
                             |buf| buf.writeln(format!("{fn_name}(self, node)")),
@@ -113,6 +220,7 @@ pub fn gen_fold_root(ir: &Ir) -> String {
             },
         );
 
+        markers.write(&mut buf, &fn_name, name);
         buf.writeln(format!(
             "pub fn {fn_name}<F, {trait_generics_and_bounds}>(folder: &mut F, node: {name}{generics}) -> {name}{generics2}"
         ));
@@ -212,7 +320,7 @@ struct VisitConfig {
     mutable: bool,
 }
 
-fn gen_visit_root(c: &VisitConfig, ir: &Ir) -> String {
+fn gen_visit_root(c: &VisitConfig, ir: &Ir, markers: &mut Markers) -> String {
     let trait_name = if c.mutable { "VisitMut" } else { "Visit" };
     let fn_name_base = if c.mutable { "visit_mut" } else { "visit" };
     let muta = if c.mutable { "mut " } else { "" };
@@ -227,6 +335,7 @@ fn gen_visit_root(c: &VisitConfig, ir: &Ir) -> String {
             for (name, item) in &ir.items {
                 let generics = item_generics(item, "");
                 let fn_name = visit_fn_name(c, name);
+                markers.write(buf, &fn_name, name);
                 buf.write_block(
                     format!("fn {fn_name}(&mut self, node: &'ast {muta}{name}{generics})"),
                     |buf| buf.writeln(format!("{fn_name}(self, node)")),
@@ -253,6 +362,7 @@ fn gen_visit_root(c: &VisitConfig, ir: &Ir) -> String {
                 ), |buf| buf.writeln(format!("visitor.{fn_name}(self)")));
             });
         }
+        markers.write(&mut buf, &fn_name, name);
         buf.writeln(format!(
             "pub fn {fn_name}<'ast, V, {trait_generics_and_bounds}>(visitor: &mut V, node: &'ast {muta}{name}{generics})"
         ));
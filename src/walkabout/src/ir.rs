@@ -218,14 +218,41 @@ fn analyze_fields(fields: &syn::Fields) -> Result<Vec<Field>> {
     fields
         .iter()
         .map(|f| {
+            // Fields marked `#[walkabout(skip)]` are treated as primitives, so
+            // that they are never visited and are passed through folds
+            // unchanged. Their types are not analyzed at all, so they may be
+            // of types that walkabout does not otherwise understand.
+            let ty = if analyze_skip(&f.attrs)? {
+                Type::Primitive
+            } else {
+                analyze_type(&f.ty)?
+            };
             Ok(Field {
                 name: f.ident.as_ref().map(|id| id.to_string()),
-                ty: analyze_type(&f.ty)?,
+                ty,
             })
         })
         .collect()
 }
 
+/// Reports whether `attrs` contains a `#[walkabout(skip)]` attribute.
+fn analyze_skip(attrs: &[syn::Attribute]) -> Result<bool> {
+    let mut skip = false;
+    for attr in attrs {
+        if !attr.path.is_ident("walkabout") {
+            continue;
+        }
+        match attr.parse_args::<syn::Ident>() {
+            Ok(ident) if ident == "skip" => skip = true,
+            _ => bail!(
+                "Unable to analyze walkabout attribute: {}",
+                attr.to_token_stream()
+            ),
+        }
+    }
+    Ok(skip)
+}
+
 fn analyze_generics(generics: &syn::Generics) -> Result<Vec<ItemGeneric>> {
     let mut out = vec![];
     for g in generics.params.iter() {
@@ -107,7 +107,10 @@ mod parse;
 
 pub mod ir;
 
-pub use gen::{gen_fold, gen_visit, gen_visit_mut};
+pub use gen::{
+    gen_fold, gen_fold_with_options, gen_visit, gen_visit_mut, gen_visit_mut_with_options,
+    gen_visit_with_options, GenOptions, GenOutput,
+};
 
 /// Loads type definitions from the specified module.
 ///
@@ -125,6 +128,13 @@ pub use gen::{gen_fold, gen_visit, gen_visit_mut};
 /// Rust containers, like [`Option`] and [`Vec`]. It does, however, endeavor to
 /// produce understandable error messages when it encounters a type it does not
 /// know how to handle.
+///
+/// Struct and enum variant fields annotated with `#[walkabout(skip)]` are not
+/// traversed by the generated visitors and are passed through unchanged by the
+/// generated folders. Note that walkabout only inspects the attribute; the
+/// crate containing the type definitions is responsible for making it
+/// acceptable to the compiler, e.g., via a derive macro that declares
+/// `walkabout` as a helper attribute.
 pub fn load<P>(path: P) -> Result<ir::Ir>
 where
     P: AsRef<Path>,
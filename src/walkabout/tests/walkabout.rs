@@ -75,6 +75,7 @@
 
 use std::io::Write;
 
+use mz_walkabout::GenOptions;
 use tempfile::NamedTempFile;
 
 #[mz_ore::test]
@@ -95,3 +96,63 @@ fn datadriven() {
         })
     })
 }
+
+#[mz_ore::test]
+#[cfg_attr(miri, ignore)] // unsupported operation: non-default mode 0o600 is not supported
+fn markers() {
+    let mut f = NamedTempFile::new().unwrap();
+    f.write_all(
+        b"
+enum Expr {
+    Function(Function),
+    Ident(Ident),
+}
+struct Function {
+    name: Ident,
+    args: Vec<Expr>,
+}
+struct Ident(String);
+",
+    )
+    .unwrap();
+    let ir = mz_walkabout::load(f.path()).unwrap();
+    let options = GenOptions {
+        markers: true,
+        sourcemap: true,
+    };
+    for out in [
+        mz_walkabout::gen_fold_with_options(&ir, &options),
+        mz_walkabout::gen_visit_with_options(&ir, &options),
+        mz_walkabout::gen_visit_mut_with_options(&ir, &options),
+    ] {
+        let lines: Vec<_> = out.code.lines().map(|l| l.trim()).collect();
+        let sourcemap = out.sourcemap.unwrap();
+        for name in ir.items.keys() {
+            // Each type gets a marker for its trait method and its free
+            // function.
+            let marker = format!("// {name}");
+            assert_eq!(lines.iter().filter(|l| **l == marker).count(), 2);
+            assert_eq!(
+                sourcemap
+                    .lines()
+                    .filter(|l| l.ends_with(&format!("\t{name}")))
+                    .count(),
+                2
+            );
+        }
+        for entry in sourcemap.lines() {
+            let mut parts = entry.split('\t');
+            let line: usize = parts.next().unwrap().parse().unwrap();
+            let fn_name = parts.next().unwrap();
+            let ty = parts.next().unwrap();
+            assert_eq!(lines[line - 3], format!("// {ty}"));
+            assert_eq!(lines[line - 2], "#[rustfmt::skip]");
+            assert!(lines[line - 1].contains(&format!("fn {fn_name}")));
+        }
+    }
+    assert!(
+        mz_walkabout::gen_visit_with_options(&ir, &GenOptions::default())
+            .sourcemap
+            .is_none()
+    );
+}
@@ -82,8 +82,8 @@ use tokio_postgres::NoTls;
 use tracing::debug;
 
 use mz_metabase::{
-    DatabaseMetadata, LoginRequest, SetupDatabase, SetupDatabaseDetails, SetupPrefs, SetupRequest,
-    SetupUser, Table, TableField,
+    DatabaseEngine, DatabaseMetadata, LoginRequest, SetupDatabase, SetupDatabaseDetails,
+    SetupPrefs, SetupRequest, SetupUser, Table, TableField,
 };
 use mz_ore::retry::Retry;
 use mz_ore::task;
@@ -140,7 +140,7 @@ async fn connect_metabase() -> Result<mz_metabase::Client, anyhow::Error> {
             let req = &SetupRequest {
                 allow_tracking: false,
                 database: SetupDatabase {
-                    engine: "postgres".into(),
+                    engine: DatabaseEngine::Postgres,
                     name: "Materialize".into(),
                     details: SetupDatabaseDetails {
                         host: "materialized".into(),